@@ -0,0 +1,462 @@
+//! Static analysis of Intcode programs: a control-flow graph recovered from
+//! the code reachable from address 0.
+//!
+//! Only jumps with an immediate target are followed. Compiled Intcode calls
+//! functions by storing a return address on the relative-base stack and
+//! jumping to the function, which returns through a relative-mode jump; such
+//! calls are recognized, so code after a call is still found. Any other jump
+//! with a computed target ends the analysis of that path.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+};
+
+use crate::{
+    disasm::{decode, DecodeError, Instruction, Operand},
+    Opcode, ParamMode,
+};
+
+/// How control leaves a [`BasicBlock`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Terminator {
+    /// Execution continues with the block starting at the given address,
+    /// which is also the target of a jump.
+    Fallthrough(usize),
+    /// An unconditional jump.
+    Jump(usize),
+    /// A conditional jump to `taken`, continuing at `not_taken` otherwise.
+    Branch {
+        taken: usize,
+        not_taken: usize,
+    },
+    /// A jump to `target` that stores `ret` on the relative-base stack first.
+    Call {
+        target: usize,
+        ret: usize,
+    },
+    /// An unconditional jump to an address read from the relative-base
+    /// stack.
+    Return,
+    /// A jump to an address that is not known statically. A conditional
+    /// one continues at `not_taken` if it is not taken.
+    Indirect {
+        not_taken: Option<usize>,
+    },
+    Halt,
+    /// The cell at `address` is not a valid instruction.
+    Invalid {
+        address: usize,
+        error: DecodeError,
+    },
+}
+
+impl Terminator {
+    /// The addresses this block can statically continue at.
+    pub fn successors(&self) -> Vec<usize> {
+        match self {
+            Self::Fallthrough(next) | Self::Jump(next) => vec![*next],
+            Self::Branch { taken, not_taken } => vec![*taken, *not_taken],
+            Self::Call { target, ret } => vec![*target, *ret],
+            Self::Indirect { not_taken } => not_taken.iter().copied().collect(),
+            Self::Return | Self::Halt | Self::Invalid { .. } => Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BasicBlock {
+    pub start: usize,
+    pub instructions: Vec<(usize, Instruction)>,
+    pub terminator: Terminator,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Warning {
+    /// The instruction at `address` writes to `target`, which holds code.
+    CodeWrite { address: usize, target: usize },
+    /// The instruction at `address` jumps to `target`, which is outside the
+    /// program.
+    JumpOutOfRange { address: usize, target: isize },
+    /// Control reaches a cell that is not a valid instruction.
+    Invalid { address: usize, error: DecodeError },
+}
+
+impl std::fmt::Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::CodeWrite { address, target } => {
+                write!(f, "{address}: writes to code at {target}")
+            }
+            Self::JumpOutOfRange { address, target } => {
+                write!(f, "{address}: jumps outside the program to {target}")
+            }
+            Self::Invalid { address, error } => write!(f, "{address}: {error}"),
+        }
+    }
+}
+
+/// The control-flow graph of a program.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cfg {
+    /// The basic blocks, by start address.
+    pub blocks: BTreeMap<usize, BasicBlock>,
+    /// The targets of all recognized calls.
+    pub functions: BTreeSet<usize>,
+    pub warnings: Vec<Warning>,
+}
+
+/// How the instruction at an address affects control flow.
+#[derive(Debug, Clone, PartialEq)]
+enum Flow {
+    Next,
+    Terminator(Terminator),
+}
+
+/// Recover the control-flow graph of `program`, starting at address 0.
+pub fn analyze(program: &[isize]) -> Cfg {
+    let mut instructions = BTreeMap::new();
+    let mut flows = BTreeMap::new();
+    let mut leaders = BTreeSet::from([0]);
+    let mut functions = BTreeSet::new();
+    let mut warnings = Vec::new();
+
+    // Discover all reachable instructions, following one straight-line path
+    // at a time.
+    let mut todo = vec![0];
+    while let Some(mut address) = todo.pop() {
+        // Return addresses stored on the stack since the start of this path.
+        let mut pushed = BTreeSet::new();
+
+        while !flows.contains_key(&address) {
+            let instruction = match decode(program, address) {
+                Ok(instruction) => instruction,
+                Err(error) => {
+                    warnings.push(Warning::Invalid {
+                        address,
+                        error: error.clone(),
+                    });
+                    flows.insert(
+                        address,
+                        Flow::Terminator(Terminator::Invalid { address, error }),
+                    );
+                    break;
+                }
+            };
+
+            if let Some(value) = pushed_value(&instruction) {
+                pushed.insert(value);
+            }
+
+            let next = address + instruction.size();
+            let flow = flow(address, next, &instruction, &pushed, &mut warnings);
+            instructions.insert(address, instruction);
+
+            match &flow {
+                Flow::Next => {
+                    flows.insert(address, flow);
+                    address = next;
+                }
+                Flow::Terminator(terminator) => {
+                    if let Terminator::Call { target, .. } = terminator {
+                        functions.insert(*target);
+                    }
+
+                    let successors = terminator.successors();
+                    leaders.extend(&successors);
+                    todo.extend(successors);
+                    flows.insert(address, flow);
+                    break;
+                }
+            }
+        }
+    }
+
+    // Split the discovered code into blocks at every leader.
+    let mut blocks = BTreeMap::new();
+    for &start in &leaders {
+        let mut block = BasicBlock {
+            start,
+            instructions: Vec::new(),
+            terminator: Terminator::Halt,
+        };
+
+        let mut address = start;
+        block.terminator = loop {
+            if address != start && leaders.contains(&address) {
+                break Terminator::Fallthrough(address);
+            }
+
+            match &flows[&address] {
+                Flow::Next => {
+                    let instruction = instructions[&address].clone();
+                    let next = address + instruction.size();
+                    block.instructions.push((address, instruction));
+                    address = next;
+                }
+                Flow::Terminator(terminator) => {
+                    if let Some(instruction) = instructions.get(&address) {
+                        block.instructions.push((address, instruction.clone()));
+                    }
+                    break terminator.clone();
+                }
+            }
+        };
+
+        blocks.insert(start, block);
+    }
+
+    // Writes to any cell occupied by reachable code.
+    let code: BTreeSet<usize> = instructions
+        .iter()
+        .flat_map(|(address, i)| *address..address + i.size())
+        .collect();
+
+    for (address, instruction) in &instructions {
+        let Some(idx) = instruction.opcode.write_param() else {
+            continue;
+        };
+
+        let operand = instruction.operands[idx];
+        if operand.mode != ParamMode::Position {
+            continue;
+        }
+
+        if let Ok(target) = usize::try_from(operand.value) {
+            if code.contains(&target) {
+                warnings.push(Warning::CodeWrite {
+                    address: *address,
+                    target,
+                });
+            }
+        }
+    }
+
+    warnings.sort_by_key(|w| match w {
+        Warning::CodeWrite { address, .. }
+        | Warning::JumpOutOfRange { address, .. }
+        | Warning::Invalid { address, .. } => *address,
+    });
+
+    Cfg {
+        blocks,
+        functions,
+        warnings,
+    }
+}
+
+/// The value an instruction stores on the relative-base stack, if it
+/// stores a constant there.
+fn pushed_value(instruction: &Instruction) -> Option<isize> {
+    let [a, b, dest] = instruction.operands[..] else {
+        return None;
+    };
+
+    if dest.mode != ParamMode::Relative
+        || a.mode != ParamMode::Immediate
+        || b.mode != ParamMode::Immediate
+    {
+        return None;
+    }
+
+    match instruction.opcode {
+        Opcode::Add => a.value.checked_add(b.value),
+        Opcode::Mul => a.value.checked_mul(b.value),
+        _ => None,
+    }
+}
+
+fn flow(
+    address: usize,
+    next: usize,
+    instruction: &Instruction,
+    pushed: &BTreeSet<isize>,
+    warnings: &mut Vec<Warning>,
+) -> Flow {
+    let (condition, target): (Operand, Operand) = match instruction.opcode {
+        Opcode::Halt => return Flow::Terminator(Terminator::Halt),
+        Opcode::JumpIfTrue | Opcode::JumpIfFalse => {
+            (instruction.operands[0], instruction.operands[1])
+        }
+        _ => return Flow::Next,
+    };
+
+    // Whether the jump is always (`Some(true)`) or never taken.
+    let always = (condition.mode == ParamMode::Immediate)
+        .then(|| (condition.value != 0) == (instruction.opcode == Opcode::JumpIfTrue));
+
+    if always == Some(false) {
+        return Flow::Next;
+    }
+
+    let not_taken = if always.is_some() { None } else { Some(next) };
+
+    let terminator = match target.mode {
+        ParamMode::Immediate => match usize::try_from(target.value) {
+            Ok(target) => match not_taken {
+                Some(not_taken) => Terminator::Branch {
+                    taken: target,
+                    not_taken,
+                },
+                None if pushed.contains(&(next as isize)) => Terminator::Call { target, ret: next },
+                None => Terminator::Jump(target),
+            },
+            Err(_) => {
+                warnings.push(Warning::JumpOutOfRange {
+                    address,
+                    target: target.value,
+                });
+                Terminator::Indirect { not_taken }
+            }
+        },
+        ParamMode::Relative if not_taken.is_none() => Terminator::Return,
+        _ => Terminator::Indirect { not_taken },
+    };
+
+    Flow::Terminator(terminator)
+}
+
+impl Cfg {
+    /// Render the graph in Graphviz DOT format, with the disassembly of each
+    /// block as its label.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph cfg {\n    node [shape=box, fontname=monospace];\n");
+
+        for block in self.blocks.values() {
+            let mut label = String::new();
+            if self.functions.contains(&block.start) {
+                write!(label, "function {}\\l", block.start).unwrap();
+            }
+            for (address, instruction) in &block.instructions {
+                write!(label, "{address:>6}: {instruction}\\l").unwrap();
+            }
+            match &block.terminator {
+                Terminator::Return => label.push_str("return\\l"),
+                Terminator::Indirect { .. } => label.push_str("indirect jump\\l"),
+                Terminator::Invalid { address, error } => {
+                    write!(label, "{address:>6}: invalid: {error}\\l").unwrap()
+                }
+                _ => {}
+            }
+
+            writeln!(
+                dot,
+                "    b{} [label=\"{}\"];",
+                block.start,
+                label.replace('"', "\\\"")
+            )
+            .unwrap();
+
+            let edges: Vec<(usize, &str)> = match &block.terminator {
+                Terminator::Fallthrough(next) => vec![(*next, "")],
+                Terminator::Jump(target) => vec![(*target, "")],
+                Terminator::Branch { taken, not_taken } => {
+                    vec![(*taken, "taken"), (*not_taken, "not taken")]
+                }
+                Terminator::Call { target, ret } => vec![(*target, "call"), (*ret, "return")],
+                Terminator::Indirect {
+                    not_taken: Some(not_taken),
+                } => vec![(*not_taken, "not taken")],
+                _ => Vec::new(),
+            };
+
+            for (to, label) in edges {
+                let style = if label == "return" {
+                    ", style=dashed"
+                } else {
+                    ""
+                };
+                writeln!(
+                    dot,
+                    "    b{} -> b{to} [label=\"{label}\"{style}];",
+                    block.start
+                )
+                .unwrap();
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Increments the operand of the `OUT` at 0 until it reaches 10.
+    const SELF_MODIFYING: [isize; 14] = [104, 7, 1001, 1, 1, 1, 1007, 1, 10, 20, 1005, 20, 0, 99];
+
+    #[test]
+    fn loop_and_code_write() {
+        let cfg = analyze(&SELF_MODIFYING);
+
+        assert_eq!(cfg.blocks.keys().copied().collect::<Vec<_>>(), [0, 13]);
+        let starts = |block: &BasicBlock| -> Vec<usize> {
+            block
+                .instructions
+                .iter()
+                .map(|(address, _)| *address)
+                .collect()
+        };
+
+        assert_eq!(starts(&cfg.blocks[&0]), [0, 2, 6, 10]);
+        assert_eq!(
+            cfg.blocks[&0].terminator,
+            Terminator::Branch {
+                taken: 0,
+                not_taken: 13
+            }
+        );
+        assert_eq!(starts(&cfg.blocks[&13]), [13]);
+        assert_eq!(cfg.blocks[&13].terminator, Terminator::Halt);
+        assert!(cfg.functions.is_empty());
+
+        // The write to 20 is outside of the code.
+        assert_eq!(
+            cfg.warnings,
+            [Warning::CodeWrite {
+                address: 2,
+                target: 1
+            }]
+        );
+        assert_eq!(cfg.warnings[0].to_string(), "2: writes to code at 1");
+    }
+
+    #[test]
+    fn dot() {
+        let expected = r#"digraph cfg {
+    node [shape=box, fontname=monospace];
+    b0 [label="     0: OUT #7\l     2: ADD 1, #1, 1\l     6: LT 1, #10, 20\l    10: JNZ 20, #0\l"];
+    b0 -> b0 [label="taken"];
+    b0 -> b13 [label="not taken"];
+    b13 [label="    13: HLT\l"];
+}
+"#;
+
+        assert_eq!(analyze(&SELF_MODIFYING).to_dot(), expected);
+    }
+
+    #[test]
+    fn calls() {
+        // Calls the function at 10, which outputs 1 and returns to 9.
+        let program = [109, 20, 21101, 9, 0, 0, 1105, 1, 10, 99, 104, 1, 2105, 1, 0];
+        let cfg = analyze(&program);
+
+        assert_eq!(cfg.functions, BTreeSet::from([10]));
+        assert_eq!(
+            cfg.blocks[&0].terminator,
+            Terminator::Call { target: 10, ret: 9 }
+        );
+        assert_eq!(cfg.blocks[&9].terminator, Terminator::Halt);
+        assert_eq!(cfg.blocks[&10].terminator, Terminator::Return);
+        assert!(cfg.warnings.is_empty());
+
+        let dot = cfg.to_dot();
+        assert!(
+            dot.contains("b0 -> b9 [label=\"return\", style=dashed];"),
+            "{dot}"
+        );
+        assert!(dot.contains("function 10\\l"), "{dot}");
+    }
+}
@@ -0,0 +1,301 @@
+//! Talking to Intcode programs that use ASCII for input and output.
+
+use std::io::{BufRead, Write};
+
+use crate::{IntcodeError, ProgramState, RunState};
+
+#[derive(Debug)]
+pub enum AsciiError {
+    Intcode(IntcodeError),
+    Io(std::io::Error),
+}
+
+impl std::fmt::Display for AsciiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Intcode(e) => write!(f, "{e}"),
+            Self::Io(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for AsciiError {}
+
+impl From<IntcodeError> for AsciiError {
+    fn from(value: IntcodeError) -> Self {
+        Self::Intcode(value)
+    }
+}
+
+impl From<std::io::Error> for AsciiError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+/// A [`ProgramState`] that takes text commands and produces lines of text.
+///
+/// Output values outside the ASCII range (negative or larger than 127) are
+/// not part of the text; they are collected separately and the last one is
+/// available as [`AsciiMachine::answer`].
+#[derive(Debug, Clone)]
+pub struct AsciiMachine {
+    machine: ProgramState,
+    lines: Vec<String>,
+    partial: String,
+    values: Vec<isize>,
+}
+
+impl AsciiMachine {
+    pub fn new(machine: ProgramState) -> Self {
+        Self {
+            machine,
+            lines: Vec::new(),
+            partial: String::new(),
+            values: Vec::new(),
+        }
+    }
+
+    pub fn machine(&self) -> &ProgramState {
+        &self.machine
+    }
+
+    pub fn machine_mut(&mut self) -> &mut ProgramState {
+        &mut self.machine
+    }
+
+    /// Queue `command` as input, followed by a newline.
+    pub fn send(&mut self, command: &str) {
+        command
+            .bytes()
+            .chain(std::iter::once(b'\n'))
+            .for_each(|b| self.machine.add_input(b as isize));
+    }
+
+    /// Run until the program needs input or halts.
+    ///
+    /// Never returns [`RunState::Output`].
+    pub fn run(&mut self) -> Result<RunState, IntcodeError> {
+        loop {
+            match self.machine.poll()? {
+                RunState::Output(value) => self.push_output(value),
+                state => break Ok(state),
+            }
+        }
+    }
+
+    /// Run the program, sending `commands` one at a time whenever it needs
+    /// input. Stops when the program halts or the commands run out.
+    pub fn run_script<'a>(
+        &mut self,
+        commands: impl IntoIterator<Item = &'a str>,
+    ) -> Result<RunState, IntcodeError> {
+        let mut commands = commands.into_iter();
+
+        loop {
+            match self.run()? {
+                RunState::NeedsInput => match commands.next() {
+                    Some(command) => self.send(command),
+                    None => break Ok(RunState::NeedsInput),
+                },
+                state => break Ok(state),
+            }
+        }
+    }
+
+    /// Run the program, printing its output to `output` and forwarding lines
+    /// read from `input` whenever it needs input.
+    ///
+    /// Non-ASCII values are printed as `[value]` on a line of their own, in
+    /// the order they were output, and stay available to
+    /// [`AsciiMachine::answer`].
+    ///
+    /// Stops when the program halts or `input` reaches end of file.
+    pub fn run_interactive(
+        &mut self,
+        mut input: impl BufRead,
+        mut output: impl Write,
+    ) -> Result<RunState, AsciiError> {
+        loop {
+            let state = loop {
+                match self.machine.poll()? {
+                    RunState::Output(value) => {
+                        let values = self.values.len();
+                        self.push_output(value);
+
+                        for line in self.take_lines() {
+                            writeln!(output, "{line}")?;
+                        }
+
+                        if self.values.len() > values {
+                            if !self.partial.is_empty() {
+                                writeln!(output, "{}", self.partial)?;
+                                self.partial.clear();
+                            }
+
+                            writeln!(output, "[{value}]")?;
+                        }
+                    }
+                    state => break state,
+                }
+            };
+
+            // Lines output before this was called have not been printed yet.
+            for line in self.take_lines() {
+                writeln!(output, "{line}")?;
+            }
+
+            // Whatever is left is a prompt without a trailing newline.
+            write!(output, "{}", self.partial)?;
+            self.partial.clear();
+            output.flush()?;
+
+            if state == RunState::Halted {
+                break Ok(state);
+            }
+
+            let mut command = String::new();
+            if input.read_line(&mut command)? == 0 {
+                break Ok(state);
+            }
+
+            self.send(command.trim_end_matches(['\n', '\r']));
+        }
+    }
+
+    /// Take all complete lines of text output so far.
+    pub fn take_lines(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.lines)
+    }
+
+    /// Text output after the last newline.
+    pub fn partial_line(&self) -> &str {
+        &self.partial
+    }
+
+    /// All non-ASCII values output so far.
+    pub fn values(&self) -> &[isize] {
+        &self.values
+    }
+
+    /// The last non-ASCII value output so far, which is usually the answer
+    /// to the puzzle.
+    pub fn answer(&self) -> Option<isize> {
+        self.values.last().copied()
+    }
+
+    fn push_output(&mut self, value: isize) {
+        match u8::try_from(value) {
+            Ok(b'\n') => self.lines.push(std::mem::take(&mut self.partial)),
+            Ok(byte) if byte.is_ascii() => self.partial.push(byte as char),
+            _ => self.values.push(value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Echoes every input value until it reads a 0, outputs 1000 and halts.
+    const ECHO: [isize; 18] = [
+        3, 17, 1005, 17, 9, 104, 1000, 99, 0, 4, 17, 1105, 1, 0, 0, 0, 0, 0,
+    ];
+
+    /// Outputs "Hi\n", 1234, "ok" and halts.
+    fn greeter() -> ProgramState {
+        ProgramState::new_empty(&[
+            104, 72, 104, 105, 104, 10, 104, 1234, 104, 111, 104, 107, 99,
+        ])
+    }
+
+    #[test]
+    fn encode_and_decode() {
+        let mut machine = AsciiMachine::new(ProgramState::new_empty(&ECHO));
+        machine.send("go!");
+        machine.machine_mut().add_input(-7);
+        machine.send("");
+
+        assert_eq!(machine.run(), Ok(RunState::NeedsInput));
+        assert_eq!(machine.take_lines(), ["go!", ""]);
+        assert_eq!(machine.values(), [-7]);
+
+        machine.machine_mut().add_input(0);
+        assert_eq!(machine.run(), Ok(RunState::Halted));
+        assert_eq!(machine.values(), [-7, 1000]);
+    }
+
+    #[test]
+    fn lines_and_partial_lines() {
+        let mut machine = AsciiMachine::new(ProgramState::new_empty(&ECHO));
+        machine.send("one");
+        "tw".bytes()
+            .for_each(|b| machine.machine_mut().add_input(b as isize));
+
+        assert_eq!(machine.run(), Ok(RunState::NeedsInput));
+        assert_eq!(machine.take_lines(), ["one"]);
+        assert_eq!(machine.partial_line(), "tw");
+        assert!(machine.take_lines().is_empty());
+
+        machine.send("o");
+        assert_eq!(machine.run(), Ok(RunState::NeedsInput));
+        assert_eq!(machine.take_lines(), ["two"]);
+        assert_eq!(machine.partial_line(), "");
+    }
+
+    #[test]
+    fn answer() {
+        let mut machine = AsciiMachine::new(ProgramState::new_empty(&ECHO));
+        assert_eq!(machine.run_script(["a", "b"]), Ok(RunState::NeedsInput));
+        assert_eq!(machine.answer(), None);
+
+        machine.machine_mut().add_input(0);
+        assert_eq!(machine.run(), Ok(RunState::Halted));
+        assert_eq!(machine.take_lines(), ["a", "b"]);
+        assert_eq!(machine.answer(), Some(1000));
+    }
+
+    #[test]
+    fn interactive() {
+        let mut machine = AsciiMachine::new(greeter());
+        let mut output = Vec::new();
+
+        let state = machine.run_interactive(&b""[..], &mut output).unwrap();
+
+        assert_eq!(state, RunState::Halted);
+        assert_eq!(String::from_utf8(output).unwrap(), "Hi\n[1234]\nok");
+        assert_eq!(machine.answer(), Some(1234));
+    }
+
+    #[test]
+    fn interactive_values_inline() {
+        // Outputs "a", 500, "b\n", -6, "c" and halts.
+        let machine =
+            ProgramState::new_empty(&[104, 97, 104, 500, 104, 98, 104, 10, 104, -6, 104, 99, 99]);
+        let mut machine = AsciiMachine::new(machine);
+        let mut output = Vec::new();
+
+        machine.run_interactive(&b""[..], &mut output).unwrap();
+
+        assert_eq!(String::from_utf8(output).unwrap(), "a\n[500]\nb\n[-6]\nc");
+        assert_eq!(machine.values(), [500, -6]);
+        assert_eq!(machine.answer(), Some(-6));
+    }
+
+    #[test]
+    fn interactive_input() {
+        let mut machine = AsciiMachine::new(ProgramState::new_empty(&ECHO));
+        let mut output = Vec::new();
+
+        let state = machine
+            .run_interactive(&b"hello\r\nworld\n"[..], &mut output)
+            .unwrap();
+
+        assert_eq!(state, RunState::NeedsInput);
+        assert_eq!(String::from_utf8(output).unwrap(), "hello\nworld\n");
+
+        machine.machine_mut().add_input(0);
+        assert_eq!(machine.run(), Ok(RunState::Halted));
+        assert_eq!(machine.answer(), Some(1000));
+    }
+}
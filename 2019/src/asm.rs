@@ -0,0 +1,431 @@
+//! A small assembler for Intcode programs.
+//!
+//! Every line holds an optional `label:`, followed by an optional
+//! instruction or `data` directive. Comments start with `;`.
+//!
+//! ```text
+//! ; Echo numbers until a zero is read.
+//! loop:   IN value
+//!         JZ value, #done
+//!         OUT value
+//!         JZ #0, #loop
+//! done:   HLT
+//! value:  data 0
+//! ```
+//!
+//! Operands are written as `12` or `label` for position mode, `#12` or
+//! `#label` for immediate mode and `@rb+12`/`@rb-12` for relative mode.
+//! Labels may carry an offset, e.g. `loop+1`.
+
+use std::collections::HashMap;
+
+use crate::{
+    disasm::{disassemble, Instruction, Line, Operand},
+    Opcode, ParamMode,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AsmErrorKind {
+    UnknownMnemonic(String),
+    OperandCount { expected: usize, found: usize },
+    InvalidOperand(String),
+    InvalidLabel(String),
+    DuplicateLabel(String),
+    UndefinedLabel(String),
+    ImmediateWrite,
+}
+
+/// An assembly error, pointing at the 1-based source line it occurred on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AsmError {
+    pub line: usize,
+    pub kind: AsmErrorKind,
+}
+
+impl std::fmt::Display for AsmError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+
+        match &self.kind {
+            AsmErrorKind::UnknownMnemonic(m) => write!(f, "unknown mnemonic `{m}`"),
+            AsmErrorKind::OperandCount { expected, found } => {
+                write!(f, "expected {expected} operands, found {found}")
+            }
+            AsmErrorKind::InvalidOperand(o) => write!(f, "invalid operand `{o}`"),
+            AsmErrorKind::InvalidLabel(l) => write!(f, "invalid label `{l}`"),
+            AsmErrorKind::DuplicateLabel(l) => write!(f, "label `{l}` is defined more than once"),
+            AsmErrorKind::UndefinedLabel(l) => write!(f, "label `{l}` is not defined"),
+            AsmErrorKind::ImmediateWrite => write!(f, "cannot write to an immediate operand"),
+        }
+    }
+}
+
+impl std::error::Error for AsmError {}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Number(isize),
+    Label { name: String, offset: isize },
+}
+
+impl Value {
+    fn parse(text: &str) -> Result<Self, AsmErrorKind> {
+        let invalid = || AsmErrorKind::InvalidOperand(text.to_string());
+
+        if let Ok(number) = text.parse() {
+            return Ok(Self::Number(number));
+        }
+
+        let (name, offset) = if let Some((name, offset)) = text.split_once('+') {
+            (name, offset.parse().map_err(|_| invalid())?)
+        } else if let Some((name, offset)) = text.split_once('-') {
+            (name, -offset.parse::<isize>().map_err(|_| invalid())?)
+        } else {
+            (text, 0)
+        };
+
+        if !is_identifier(name) {
+            return Err(invalid());
+        }
+
+        Ok(Self::Label {
+            name: name.to_string(),
+            offset,
+        })
+    }
+
+    fn resolve(&self, labels: &HashMap<&str, usize>) -> Result<isize, AsmErrorKind> {
+        match self {
+            Self::Number(number) => Ok(*number),
+            Self::Label { name, offset } => labels
+                .get(name.as_str())
+                .map(|address| *address as isize + offset)
+                .ok_or_else(|| AsmErrorKind::UndefinedLabel(name.clone())),
+        }
+    }
+}
+
+fn is_identifier(text: &str) -> bool {
+    let mut chars = text.chars();
+
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn parse_operand(text: &str) -> Result<(ParamMode, Value), AsmErrorKind> {
+    if let Some(value) = text.strip_prefix('#') {
+        Ok((ParamMode::Immediate, Value::parse(value)?))
+    } else if let Some(offset) = text.strip_prefix("@rb") {
+        let value = if offset.is_empty() {
+            Value::Number(0)
+        } else if let Some(offset) = offset.strip_prefix('+') {
+            Value::parse(offset)?
+        } else if offset.starts_with('-') {
+            Value::Number(
+                offset
+                    .parse()
+                    .map_err(|_| AsmErrorKind::InvalidOperand(text.to_string()))?,
+            )
+        } else {
+            return Err(AsmErrorKind::InvalidOperand(text.to_string()));
+        };
+
+        Ok((ParamMode::Relative, value))
+    } else {
+        Ok((ParamMode::Position, Value::parse(text)?))
+    }
+}
+
+enum Statement {
+    Instruction {
+        opcode: Opcode,
+        operands: Vec<(ParamMode, Value)>,
+    },
+    Data(Vec<Value>),
+}
+
+impl Statement {
+    fn size(&self) -> usize {
+        match self {
+            Self::Instruction { operands, .. } => 1 + operands.len(),
+            Self::Data(values) => values.len(),
+        }
+    }
+}
+
+/// Parse a single source line into its label and statement.
+fn parse_line(line: &str) -> Result<(Option<&str>, Option<Statement>), AsmErrorKind> {
+    let line = line.split_once(';').map(|(code, _)| code).unwrap_or(line);
+    let mut line = line.trim();
+
+    let mut label = None;
+    if let Some((name, rest)) = line.split_once(':') {
+        let name = name.trim();
+        if !is_identifier(name) {
+            return Err(AsmErrorKind::InvalidLabel(name.to_string()));
+        }
+
+        label = Some(name);
+        line = rest.trim();
+    }
+
+    if line.is_empty() {
+        return Ok((label, None));
+    }
+
+    let (mnemonic, operands) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let operands: Vec<_> = operands
+        .split(',')
+        .map(str::trim)
+        .filter(|o| !o.is_empty())
+        .collect();
+
+    if mnemonic.eq_ignore_ascii_case("data") {
+        let values = operands
+            .into_iter()
+            .map(Value::parse)
+            .collect::<Result<_, _>>()?;
+        return Ok((label, Some(Statement::Data(values))));
+    }
+
+    let opcode = Opcode::ALL
+        .into_iter()
+        .find(|op| op.mnemonic().eq_ignore_ascii_case(mnemonic))
+        .ok_or_else(|| AsmErrorKind::UnknownMnemonic(mnemonic.to_string()))?;
+
+    if operands.len() != opcode.arity() {
+        return Err(AsmErrorKind::OperandCount {
+            expected: opcode.arity(),
+            found: operands.len(),
+        });
+    }
+
+    let operands = operands
+        .into_iter()
+        .map(parse_operand)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if let Some(idx) = opcode.write_param() {
+        if operands[idx].0 == ParamMode::Immediate {
+            return Err(AsmErrorKind::ImmediateWrite);
+        }
+    }
+
+    Ok((label, Some(Statement::Instruction { opcode, operands })))
+}
+
+/// Assemble `source` into a program.
+pub fn assemble(source: &str) -> Result<Vec<isize>, AsmError> {
+    let mut labels = HashMap::new();
+    let mut statements = Vec::new();
+    let mut address = 0;
+
+    for (idx, line) in source.lines().enumerate() {
+        let line_no = idx + 1;
+        let error = |kind| AsmError {
+            line: line_no,
+            kind,
+        };
+
+        let (label, statement) = parse_line(line).map_err(error)?;
+
+        if let Some(label) = label {
+            if labels.insert(label, address).is_some() {
+                return Err(error(AsmErrorKind::DuplicateLabel(label.to_string())));
+            }
+        }
+
+        if let Some(statement) = statement {
+            address += statement.size();
+            statements.push((line_no, statement));
+        }
+    }
+
+    let mut program = Vec::with_capacity(address);
+
+    for (line, statement) in statements {
+        let error = |kind| AsmError { line, kind };
+
+        match statement {
+            Statement::Instruction { opcode, operands } => {
+                let operands = operands
+                    .into_iter()
+                    .map(|(mode, value)| {
+                        let value = value.resolve(&labels)?;
+                        Ok(Operand { mode, value })
+                    })
+                    .collect::<Result<_, _>>()
+                    .map_err(error)?;
+
+                program.extend(Instruction { opcode, operands }.encode());
+            }
+            Statement::Data(values) => {
+                for value in values {
+                    program.push(value.resolve(&labels).map_err(error)?);
+                }
+            }
+        }
+    }
+
+    Ok(program)
+}
+
+/// Print `program` as assembly source that reassembles to the identical
+/// program.
+///
+/// Cells that don't decode, or that only decode leniently (e.g. with unused
+/// mode digits set), are emitted as `data`.
+pub fn to_source(program: &[isize]) -> String {
+    let mut source = String::new();
+    let mut data: Vec<isize> = Vec::new();
+    let mut data_start = 0;
+
+    let flush = |source: &mut String, data: &mut Vec<isize>, start: usize| {
+        if !data.is_empty() {
+            let values: Vec<_> = data.drain(..).map(|v| v.to_string()).collect();
+            let statement = format!("data {}", values.join(", "));
+            source.push_str(&format!("    {statement:<32} ; {start}\n"));
+        }
+    };
+
+    for line in disassemble(program) {
+        let address = line.address();
+
+        match line {
+            Line::Instruction { instruction, .. }
+                if instruction.encode()[..] == program[address..address + instruction.size()] =>
+            {
+                flush(&mut source, &mut data, data_start);
+                let statement = instruction.to_string();
+                source.push_str(&format!("    {statement:<32} ; {address}\n"));
+            }
+            Line::Instruction { instruction, .. } => {
+                if data.is_empty() {
+                    data_start = address;
+                }
+                data.extend_from_slice(&program[address..address + instruction.size()]);
+            }
+            Line::Data { value, .. } => {
+                if data.is_empty() {
+                    data_start = address;
+                }
+                data.push(value);
+            }
+        }
+    }
+
+    flush(&mut source, &mut data, data_start);
+
+    source
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(source: &str) -> AsmError {
+        assemble(source).unwrap_err()
+    }
+
+    #[test]
+    fn operands() {
+        let source = "
+            start:  ARB #1
+                    OUT @rb
+                    OUT @rb-2
+                    ADD @rb+end, #end+1, end-1
+            end:    HLT
+        ";
+
+        assert_eq!(
+            assemble(source),
+            Ok(vec![109, 1, 204, 0, 204, -2, 1201, 10, 11, 9, 99])
+        );
+        assert_eq!(
+            assemble("data start, start+2\nstart: HLT"),
+            Ok(vec![2, 4, 99])
+        );
+    }
+
+    #[test]
+    fn line_numbers() {
+        let err = error("; comment\n\n  HLT\n  FOO 1");
+        assert_eq!(err.line, 4);
+        assert_eq!(err.kind, AsmErrorKind::UnknownMnemonic("FOO".to_string()));
+        assert_eq!(err.to_string(), "line 4: unknown mnemonic `FOO`");
+    }
+
+    #[test]
+    fn labels() {
+        assert_eq!(
+            error("a: HLT\nb: HLT\na: HLT"),
+            AsmError {
+                line: 3,
+                kind: AsmErrorKind::DuplicateLabel("a".to_string())
+            }
+        );
+
+        // Labels are resolved after the whole source is read, so the error
+        // points at the line that uses the label.
+        assert_eq!(
+            error("JZ #0, #done\nJZ #0, #nowhere\ndone: HLT"),
+            AsmError {
+                line: 2,
+                kind: AsmErrorKind::UndefinedLabel("nowhere".to_string())
+            }
+        );
+
+        assert_eq!(
+            error("1a: HLT").kind,
+            AsmErrorKind::InvalidLabel("1a".to_string())
+        );
+    }
+
+    #[test]
+    fn operand_count() {
+        assert_eq!(
+            error("HLT\nADD 1, 2"),
+            AsmError {
+                line: 2,
+                kind: AsmErrorKind::OperandCount {
+                    expected: 3,
+                    found: 2
+                }
+            }
+        );
+        assert_eq!(
+            error("HLT 1").kind,
+            AsmErrorKind::OperandCount {
+                expected: 0,
+                found: 1
+            }
+        );
+    }
+
+    #[test]
+    fn immediate_write() {
+        assert_eq!(
+            error("IN #5"),
+            AsmError {
+                line: 1,
+                kind: AsmErrorKind::ImmediateWrite
+            }
+        );
+        assert_eq!(error("ADD #1, #2, #3").kind, AsmErrorKind::ImmediateWrite);
+
+        // Only the written operand has to be an address.
+        assert_eq!(assemble("ADD #1, #2, @rb+3"), Ok(vec![21101, 1, 2, 3]));
+    }
+
+    #[test]
+    fn invalid_operands() {
+        for operand in ["@rb3", "loop+x", "@rb-x"] {
+            assert_eq!(
+                error(&format!("OUT {operand}")).kind,
+                AsmErrorKind::InvalidOperand(operand.to_string())
+            );
+        }
+    }
+}
@@ -0,0 +1,353 @@
+//! A minimal arbitrary-precision signed integer, supporting just what
+//! Intcode needs: addition, multiplication, comparison and decimal
+//! conversion.
+
+use std::{cmp::Ordering, ops::Add, ops::Mul, str::FromStr};
+
+/// An arbitrary-precision signed integer.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigInt {
+    negative: bool,
+    /// Little-endian base 2^32 digits, without trailing zeroes. Zero is
+    /// represented by an empty magnitude and is never negative.
+    magnitude: Vec<u32>,
+}
+
+impl BigInt {
+    fn new(negative: bool, mut magnitude: Vec<u32>) -> Self {
+        while magnitude.last() == Some(&0) {
+            magnitude.pop();
+        }
+
+        Self {
+            negative: negative && !magnitude.is_empty(),
+            magnitude,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.magnitude.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+}
+
+fn cmp_magnitude(a: &[u32], b: &[u32]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len().max(b.len()) + 1);
+    let mut carry = 0u64;
+
+    for idx in 0..a.len().max(b.len()) {
+        let sum = *a.get(idx).unwrap_or(&0) as u64 + *b.get(idx).unwrap_or(&0) as u64 + carry;
+        result.push(sum as u32);
+        carry = sum >> 32;
+    }
+
+    result.push(carry as u32);
+    result
+}
+
+/// Computes `a - b`, where `a >= b`.
+fn sub_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len());
+    let mut borrow = 0i64;
+
+    for (idx, a) in a.iter().enumerate() {
+        let mut diff = *a as i64 - *b.get(idx).unwrap_or(&0) as i64 - borrow;
+        borrow = (diff < 0) as i64;
+        diff += borrow << 32;
+        result.push(diff as u32);
+    }
+
+    result
+}
+
+fn mul_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = vec![0u32; a.len() + b.len()];
+
+    for (i, a) in a.iter().enumerate() {
+        let mut carry = 0u64;
+
+        for (j, b) in b.iter().enumerate() {
+            let product = *a as u64 * *b as u64 + result[i + j] as u64 + carry;
+            result[i + j] = product as u32;
+            carry = product >> 32;
+        }
+
+        result[i + b.len()] = carry as u32;
+    }
+
+    result
+}
+
+impl Add for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, rhs: Self) -> BigInt {
+        if self.negative == rhs.negative {
+            return BigInt::new(
+                self.negative,
+                add_magnitude(&self.magnitude, &rhs.magnitude),
+            );
+        }
+
+        match cmp_magnitude(&self.magnitude, &rhs.magnitude) {
+            Ordering::Less => {
+                BigInt::new(rhs.negative, sub_magnitude(&rhs.magnitude, &self.magnitude))
+            }
+            _ => BigInt::new(
+                self.negative,
+                sub_magnitude(&self.magnitude, &rhs.magnitude),
+            ),
+        }
+    }
+}
+
+impl Mul for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, rhs: Self) -> BigInt {
+        BigInt::new(
+            self.negative != rhs.negative,
+            mul_magnitude(&self.magnitude, &rhs.magnitude),
+        )
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_magnitude(&self.magnitude, &other.magnitude),
+            (true, true) => cmp_magnitude(&other.magnitude, &self.magnitude),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl From<i128> for BigInt {
+    fn from(value: i128) -> Self {
+        let mut abs = value.unsigned_abs();
+        let mut magnitude = Vec::new();
+
+        while abs != 0 {
+            magnitude.push(abs as u32);
+            abs >>= 32;
+        }
+
+        Self::new(value < 0, magnitude)
+    }
+}
+
+impl TryFrom<&BigInt> for i128 {
+    type Error = ();
+
+    fn try_from(value: &BigInt) -> Result<Self, Self::Error> {
+        if value.magnitude.len() > 4 {
+            return Err(());
+        }
+
+        let abs = value
+            .magnitude
+            .iter()
+            .rev()
+            .fold(0u128, |acc, digit| (acc << 32) | *digit as u128);
+
+        if value.negative {
+            0i128.checked_sub_unsigned(abs).ok_or(())
+        } else {
+            i128::try_from(abs).map_err(|_| ())
+        }
+    }
+}
+
+impl std::fmt::Display for BigInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        const CHUNK: u64 = 1_000_000_000;
+
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+
+        // Repeatedly divide by 10^9, collecting the remainders.
+        let mut magnitude = self.magnitude.clone();
+        let mut chunks = Vec::new();
+
+        while !magnitude.is_empty() {
+            let mut rem = 0u64;
+            for digit in magnitude.iter_mut().rev() {
+                let value = (rem << 32) | *digit as u64;
+                *digit = (value / CHUNK) as u32;
+                rem = value % CHUNK;
+            }
+
+            chunks.push(rem);
+
+            while magnitude.last() == Some(&0) {
+                magnitude.pop();
+            }
+        }
+
+        if self.negative {
+            write!(f, "-")?;
+        }
+
+        let mut chunks = chunks.iter().rev();
+        write!(f, "{}", chunks.next().unwrap())?;
+        for chunk in chunks {
+            write!(f, "{chunk:09}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseBigIntError;
+
+impl std::fmt::Display for ParseBigIntError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid integer")
+    }
+}
+
+impl std::error::Error for ParseBigIntError {}
+
+impl FromStr for BigInt {
+    type Err = ParseBigIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+
+        if digits.is_empty() {
+            return Err(ParseBigIntError);
+        }
+
+        let mut magnitude: Vec<u32> = Vec::new();
+
+        for digit in digits.chars() {
+            let mut carry = digit.to_digit(10).ok_or(ParseBigIntError)? as u64;
+
+            for cell in magnitude.iter_mut() {
+                let value = *cell as u64 * 10 + carry;
+                *cell = value as u32;
+                carry = value >> 32;
+            }
+
+            if carry != 0 {
+                magnitude.push(carry as u32);
+            }
+        }
+
+        Ok(Self::new(negative, magnitude))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(s: &str) -> BigInt {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn mixed_signs() {
+        let large = "340282366920938463463374607431768211456";
+
+        // Adding values of different signs subtracts their magnitudes.
+        assert_eq!(&big("5") + &big("-3"), big("2"));
+        assert_eq!(&big("3") + &big("-5"), big("-2"));
+        assert_eq!(&big("-5") + &big("3"), big("-2"));
+        assert_eq!(&big("-3") + &big("5"), big("2"));
+        assert_eq!(&big("-3") + &big("-5"), big("-8"));
+        assert_eq!(&big("7") + &big("-7"), BigInt::default());
+        assert!(!(&big("-7") + &big("7")).is_negative());
+
+        // Borrowing across digits.
+        assert_eq!(
+            &big(large) + &big("-1"),
+            big("340282366920938463463374607431768211455")
+        );
+        assert_eq!(
+            &big("1") + &big(&format!("-{large}")),
+            big("-340282366920938463463374607431768211455")
+        );
+
+        assert_eq!(&big("-4") * &big("6"), big("-24"));
+        assert_eq!(&big("4") * &big("-6"), big("-24"));
+        assert_eq!(&big("-4") * &big("-6"), big("24"));
+        assert_eq!(&big("-4") * &BigInt::default(), BigInt::default());
+        assert!(!(&big("-4") * &BigInt::default()).is_negative());
+        assert_eq!(
+            &big(&format!("-{large}")) * &big(large),
+            big("-115792089237316195423570985008687907853269984665640564039457584007913129639936")
+        );
+    }
+
+    #[test]
+    fn parse_and_display() {
+        for text in [
+            "0",
+            "1",
+            "-1",
+            "4294967296",
+            "-4294967296",
+            "1000000000",
+            "-1000000000000000000000000000001",
+        ] {
+            assert_eq!(big(text).to_string(), text);
+        }
+
+        assert_eq!(big("-0").to_string(), "0");
+        assert!(!big("-0").is_negative());
+        assert!(big("-0").is_zero());
+        assert_eq!(big("+12").to_string(), "12");
+        assert_eq!(big("007").to_string(), "7");
+
+        for text in ["", "-", "+", "1-", "12a", " 1", "--1"] {
+            assert_eq!(text.parse::<BigInt>(), Err(ParseBigIntError), "{text:?}");
+        }
+    }
+
+    #[test]
+    fn i128_boundaries() {
+        for value in [
+            0,
+            1,
+            -1,
+            i64::MIN as i128,
+            i128::MAX,
+            i128::MIN,
+            i128::MIN + 1,
+        ] {
+            let big = BigInt::from(value);
+            assert_eq!(big.to_string(), value.to_string());
+            assert_eq!(i128::try_from(&big), Ok(value));
+        }
+
+        let above_max = &BigInt::from(i128::MAX) + &BigInt::from(1);
+        let below_min = &BigInt::from(i128::MIN) + &BigInt::from(-1);
+        let five_digits = &BigInt::from(i128::MIN) * &BigInt::from(-4);
+
+        assert_eq!(i128::try_from(&above_max), Err(()));
+        assert_eq!(i128::try_from(&below_min), Err(()));
+        assert_eq!(i128::try_from(&five_digits), Err(()));
+    }
+}
@@ -0,0 +1,31 @@
+use std::io::Read;
+
+use aoc2019::{
+    asm::{assemble, to_source},
+    Program,
+};
+
+/// Assemble the source on stdin into a comma-separated program, or, with
+/// `--source`, turn the program on stdin back into assembly source.
+fn main() {
+    let to_asm = std::env::args().nth(1).as_deref() == Some("--source");
+
+    let mut input = String::new();
+    std::io::stdin().read_to_string(&mut input).unwrap();
+
+    if to_asm {
+        let program: Program = input.parse().unwrap();
+        print!("{}", to_source(&program));
+    } else {
+        match assemble(&input) {
+            Ok(program) => {
+                let program: Vec<_> = program.iter().map(|v| v.to_string()).collect();
+                println!("{}", program.join(","));
+            }
+            Err(e) => {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+        }
+    }
+}
@@ -1,22 +1,36 @@
-fn main() {
-    let modules: Vec<usize> = std::io::stdin()
-        .lines()
-        .map(|v| v.unwrap().parse().unwrap())
-        .collect();
+use std::fmt::Display;
+
+use aoc_common::Solution;
 
-    part1(&modules);
-    part2(&modules);
+fn main() {
+    aoc_common::run::<Day1>();
 }
 
-fn part1(modules: &[usize]) {
-    let fuel_reqs: usize = modules.into_iter().map(|m| (m / 3) - 2).sum();
+struct Day1;
+
+impl Solution for Day1 {
+    type Parsed = Vec<usize>;
+
+    fn parse(input: &str) -> Self::Parsed {
+        input.lines().map(|v| v.parse().unwrap()).collect()
+    }
+
+    fn part1(modules: &Self::Parsed) -> impl Display {
+        part1(modules)
+    }
+
+    fn part2(modules: &Self::Parsed) -> impl Display {
+        part2(modules)
+    }
+}
 
-    println!("Part 1: {fuel_reqs}");
+fn part1(modules: &[usize]) -> usize {
+    modules.iter().map(|m| (m / 3) - 2).sum()
 }
 
-fn part2(modules: &[usize]) {
+fn part2(modules: &[usize]) -> usize {
     let mut sum = 0;
-    let mut modules: Vec<_> = modules.iter().cloned().collect();
+    let mut modules = modules.to_vec();
 
     while !modules.is_empty() {
         for idx in (0..modules.len()).rev() {
@@ -30,5 +44,5 @@ fn part2(modules: &[usize]) {
         }
     }
 
-    println!("Part 2: {sum}");
+    sum
 }
@@ -1,50 +1,65 @@
-use std::{collections::HashSet, usize};
+use std::{collections::HashSet, fmt::Display};
+
+use aoc_common::{Solution, Unsolved};
 
 fn main() {
-    let lines: Vec<_> = std::io::stdin().lines().map(|v| v.unwrap()).collect();
+    aoc_common::run::<Day10>();
+}
+
+struct Day10;
 
-    let mut map = Vec::new();
+impl Solution for Day10 {
+    /// For every position, whether it has an asteroid.
+    type Parsed = Vec<Vec<bool>>;
 
-    for line in lines {
-        let mut row = Vec::new();
-        for cell in line.chars() {
-            let cell = match cell {
-                '.' => false,
-                '#' => true,
-                _ => panic!(),
-            };
+    fn parse(input: &str) -> Self::Parsed {
+        let mut map = Vec::new();
 
-            row.push(cell);
+        for line in input.lines() {
+            let mut row = Vec::new();
+            for cell in line.chars() {
+                let cell = match cell {
+                    '.' => false,
+                    '#' => true,
+                    _ => panic!(),
+                };
+
+                row.push(cell);
+            }
+            map.push(row);
         }
-        map.push(row);
+
+        map
+    }
+
+    fn part1(map: &Self::Parsed) -> impl Display {
+        part1(map)
     }
 
-    part1(&map);
+    fn part2(_: &Self::Parsed) -> impl Display {
+        Unsolved
+    }
 }
 
-fn part1(map: &Vec<Vec<bool>>) {
+fn part1(map: &[Vec<bool>]) -> usize {
     let y_len = map.len() as isize;
     let x_len = map[0].len() as isize;
 
     let mut visible = usize::MIN;
-    let mut pos = (0, 0);
 
     for y in 0..y_len {
         for x in 0..x_len {
             if map[y as usize][x as usize] {
                 let new_visible = find_visible(map, (x, y));
-                if new_visible > visible {
-                    visible = new_visible;
-                    pos = (x, y);
-                }
+                visible = visible.max(new_visible);
             }
         }
     }
 
-    println!("Part 1: {visible} @ {pos:?}");
+    visible
 }
 
-fn find_visible(map: &Vec<Vec<bool>>, (x, y): (isize, isize)) -> usize {
+fn find_visible(map: &[Vec<bool>], (x, y): (isize, isize)) -> usize {
     let mut visible = HashSet::new();
     let mut visited = HashSet::new();
     let y_len = map.len() as isize;
@@ -77,17 +92,14 @@ fn find_visible(map: &Vec<Vec<bool>>, (x, y): (isize, isize)) -> usize {
 
                     if map
                         .get(y as usize)
-                        .map(|v| v.get(x as usize))
-                        .flatten()
+                        .and_then(|v| v.get(x as usize))
                         .is_none()
                     {
                         break;
                     }
 
-                    if visited.insert((x, y)) {
-                        if first.is_none() && map[y as usize][x as usize] {
-                            first = Some((x, y));
-                        }
+                    if visited.insert((x, y)) && first.is_none() && map[y as usize][x as usize] {
+                        first = Some((x, y));
                     }
                 }
 
@@ -1,36 +1,94 @@
-use aoc2019::ProgramState;
+use std::fmt::Display;
+
+use aoc2019::{
+    symbolic::{Symbol, SymbolicError, SymbolicMachine, Value},
+    FuelState, Program, ProgramState, RunState,
+};
+use aoc_common::Solution;
+
+/// The most instructions a single noun/verb candidate may execute.
+const FUEL: u64 = 100_000;
 
 fn main() {
-    let line = std::io::stdin().lines().next().unwrap().unwrap();
-    let program: Vec<isize> = line.split(',').map(|v| v.parse().unwrap()).collect();
+    aoc_common::run::<Day2>();
+}
+
+struct Day2;
+
+impl Solution for Day2 {
+    type Parsed = Program;
 
-    part1(&program);
-    part2(&program);
+    fn parse(input: &str) -> Self::Parsed {
+        input.parse().unwrap()
+    }
+
+    fn part1(program: &Self::Parsed) -> impl Display {
+        part1(program)
+    }
+
+    fn part2(program: &Self::Parsed) -> impl Display {
+        part2(program)
+    }
 }
 
-fn part1(program: &[isize]) {
+fn part1(program: &[isize]) -> isize {
     let mut program = program.to_vec();
 
     program[1] = 12;
     program[2] = 2;
 
-    let mut program = ProgramState::new(0, &mut program);
-    program.run_to_exit();
-    let result = program.program()[0];
+    let mut program = ProgramState::new(0, &program);
+    program.run_to_exit().unwrap();
+    program.program()[0]
+}
 
-    println!("Part 1: {}", result);
+fn part2(program: &[isize]) -> isize {
+    let result = match solve_symbolic(program) {
+        Ok(Some(result)) => result,
+        Ok(None) => {
+            eprintln!("Symbolic solving found no solution, falling back to brute force");
+            brute_force(program)
+        }
+        Err(e) => {
+            eprintln!("Symbolic execution failed ({e}), falling back to brute force");
+            brute_force(program)
+        }
+    };
+
+    100 * result.0 + result.1
 }
 
-fn part2(program: &[isize]) {
+/// Treat the noun and verb as unknowns and solve the resulting formula for
+/// the value at address 0.
+fn solve_symbolic(program: &[isize]) -> Result<Option<(isize, isize)>, SymbolicError> {
+    let mut machine = SymbolicMachine::new(program);
+    machine.set_symbolic(1);
+    machine.set_symbolic(2);
+    machine.run(FUEL)?;
+
+    let Value::Linear(result) = machine.read(0) else {
+        return Ok(None);
+    };
+
+    Ok(result.solve(19690720, 0..=99).map(|values| {
+        let value = |address| values.get(&Symbol::Cell(address)).copied().unwrap_or(0);
+        (value(1), value(2))
+    }))
+}
+
+fn brute_force(program: &[isize]) -> (isize, isize) {
     let mut result = (0, 0);
-    for noun in 0..99 {
-        for verb in 0..99 {
+    for noun in 0..=99 {
+        for verb in 0..=99 {
             let mut run_program = program.to_vec();
             run_program[1] = noun;
             run_program[2] = verb;
 
-            let mut program = ProgramState::new(0, &mut run_program);
-            program.run_to_exit();
+            // Skip candidates that end up in an infinite loop.
+            let mut program = ProgramState::new(0, &run_program);
+            if program.run_with_fuel(FUEL) != Ok(FuelState::Ran(RunState::Halted)) {
+                continue;
+            }
 
             if program.program()[0] == 19690720 {
                 result = (noun, verb);
@@ -39,5 +97,5 @@ fn part2(program: &[isize]) {
         }
     }
 
-    println!("Part 2: {}", 100 * result.0 + result.1);
+    result
 }
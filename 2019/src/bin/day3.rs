@@ -1,3 +1,6 @@
+use std::fmt::Display;
+
+use aoc_common::Solution;
 use line::Line;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -152,43 +155,59 @@ struct Move {
 }
 
 fn main() {
-    let mut lines = std::io::stdin().lines().map(|v| v.unwrap());
-    let wire1 = parse(&lines.next().unwrap());
-    let wire2 = parse(&lines.next().unwrap());
+    aoc_common::run::<Day3>();
+}
 
-    let wire1 = build_lines(&wire1);
-    let wire2 = build_lines(&wire2);
+struct Day3;
 
-    let intersections: Vec<_> = wire1
-        .iter()
-        .flat_map(|w1| wire2.iter().map(move |w2| (w2, w1)))
-        .flat_map(|(w1, w2)| w1.intersection(w2))
-        .collect();
+impl Solution for Day3 {
+    /// Both wires, and the points where they cross.
+    type Parsed = (Vec<Line>, Vec<Line>, Vec<Point>);
+
+    fn parse(input: &str) -> Self::Parsed {
+        let mut lines = input.lines();
+        let wire1 = parse(lines.next().unwrap());
+        let wire2 = parse(lines.next().unwrap());
+
+        let wire1 = build_lines(&wire1);
+        let wire2 = build_lines(&wire2);
+
+        let intersections: Vec<_> = wire1
+            .iter()
+            .flat_map(|w1| wire2.iter().map(move |w2| (w2, w1)))
+            .flat_map(|(w1, w2)| w1.intersection(w2))
+            .collect();
 
-    part1(&intersections);
-    part2(&wire1, &wire2, &intersections);
+        (wire1, wire2, intersections)
+    }
+
+    fn part1((_, _, intersections): &Self::Parsed) -> impl Display {
+        part1(intersections)
+    }
+
+    fn part2((wire1, wire2, intersections): &Self::Parsed) -> impl Display {
+        part2(wire1, wire2, intersections)
+    }
 }
 
-fn part1(intersections: &[Point]) {
+fn part1(intersections: &[Point]) -> isize {
     let origin = Point::new(0, 0);
 
-    let min = intersections
+    intersections
         .iter()
         .map(|v| origin.distance(v))
         .min()
-        .unwrap();
-
-    println!("Part 1: {min:?}");
+        .unwrap()
 }
 
-fn part2(wire1: &[Line], wire2: &[Line], intersections: &[Point]) {
+fn part2(wire1: &[Line], wire2: &[Line], intersections: &[Point]) -> usize {
     let mut min_distance = usize::MAX;
     for intersection in intersections.iter().cloned() {
-        let sum = walk_to(&wire1, intersection) + walk_to(&wire2, intersection);
+        let sum = walk_to(wire1, intersection) + walk_to(wire2, intersection);
         min_distance = sum.min(min_distance);
     }
 
-    println!("Part 2: {min_distance}");
+    min_distance
 }
 
 fn walk_to(wire: &[Line], point: Point) -> usize {
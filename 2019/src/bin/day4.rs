@@ -1,20 +1,37 @@
+use std::fmt::Display;
+
+use aoc_common::Solution;
+
 fn main() {
-    let line = std::io::stdin().lines().next().unwrap().unwrap();
+    aoc_common::run::<Day4>();
+}
 
-    let (start, end) = line.split_once('-').unwrap();
-    let start: usize = start.parse().unwrap();
-    let end: usize = end.parse().unwrap();
+struct Day4;
 
-    part1(start, end);
-    part2(start, end);
+impl Solution for Day4 {
+    /// The first and last password of the range.
+    type Parsed = (usize, usize);
+
+    fn parse(input: &str) -> Self::Parsed {
+        let (start, end) = input.trim().split_once('-').unwrap();
+        (start.parse().unwrap(), end.parse().unwrap())
+    }
+
+    fn part1(&(start, end): &Self::Parsed) -> impl Display {
+        part1(start, end)
+    }
+
+    fn part2(&(start, end): &Self::Parsed) -> impl Display {
+        part2(start, end)
+    }
 }
 
-fn part2(start: usize, end: usize) {
+fn part2(start: usize, end: usize) -> usize {
     let mut valid = 0;
     for password in start..=end {
         valid += is_valid_p2(password) as usize;
     }
-    println!("Part 2: {valid}");
+    valid
 }
 
 fn is_valid_p2(mut password: usize) -> bool {
@@ -51,12 +68,12 @@ fn is_valid_p2(mut password: usize) -> bool {
     groups.iter().any(|(_, len)| *len == 2)
 }
 
-fn part1(start: usize, end: usize) {
+fn part1(start: usize, end: usize) -> usize {
     let mut valid = 0;
     for password in start..=end {
         valid += is_valid_p1(password) as usize;
     }
-    println!("Part 1: {valid}")
+    valid
 }
 
 fn is_valid_p1(mut password: usize) -> bool {
@@ -1,25 +1,38 @@
-use aoc2019::ProgramState;
+use std::fmt::Display;
+
+use aoc2019::{Program, ProgramState};
+use aoc_common::Solution;
 
 fn main() {
-    let line = std::io::stdin().lines().next().unwrap().unwrap();
-    let program: Vec<isize> = line.split(',').map(|v| v.parse().unwrap()).collect();
+    aoc_common::run::<Day5>();
+}
+
+struct Day5;
+
+impl Solution for Day5 {
+    type Parsed = Program;
+
+    fn parse(input: &str) -> Self::Parsed {
+        input.parse().unwrap()
+    }
+
+    fn part1(program: &Self::Parsed) -> impl Display {
+        part1(program)
+    }
 
-    part1(&program);
-    part2(&program);
+    fn part2(program: &Self::Parsed) -> impl Display {
+        part2(program)
+    }
 }
 
-fn part1(program: &[isize]) {
-    let mut program = program.to_vec();
-    let mut program = ProgramState::new(1, &mut program);
-    program.run_to_exit();
-    let result = program.output();
-    println!("Part 1: {}", result);
+fn part1(program: &[isize]) -> isize {
+    let mut program = ProgramState::new(1, program);
+    program.run_to_exit().unwrap();
+    program.drain_outputs().last().unwrap()
 }
 
-fn part2(program: &[isize]) {
-    let mut program = program.to_vec();
-    let mut program = ProgramState::new(5, &mut program);
-    program.run_to_exit();
-    let result = program.output();
-    println!("Part 1: {}", result);
+fn part2(program: &[isize]) -> isize {
+    let mut program = ProgramState::new(5, program);
+    program.run_to_exit().unwrap();
+    program.drain_outputs().last().unwrap()
 }
@@ -1,45 +1,67 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt::Display};
+
+use aoc_common::Solution;
 
 fn main() {
-    let lines: Vec<_> = std::io::stdin().lines().map(|v| v.unwrap()).collect();
+    aoc_common::run::<Day6>();
+}
+
+struct Day6;
 
-    let mut sattelite_map = HashMap::new();
+impl Solution for Day6 {
+    /// The satellites orbiting each body.
+    type Parsed = HashMap<String, Vec<String>>;
 
-    for line in lines {
-        let (body, sattelite) = line.split_once(')').unwrap();
+    fn parse(input: &str) -> Self::Parsed {
+        let mut sattelite_map = HashMap::new();
 
-        let body = sattelite_map.entry(body.to_string()).or_insert(Vec::new());
-        body.push(sattelite.to_string());
+        for line in input.lines() {
+            let (body, sattelite) = line.split_once(')').unwrap();
+
+            let body = sattelite_map
+                .entry(body.to_string())
+                .or_insert_with(Vec::new);
+            body.push(sattelite.to_string());
+
+            sattelite_map
+                .entry(sattelite.to_string())
+                .or_insert_with(Vec::new);
+        }
 
         sattelite_map
-            .entry(sattelite.to_string())
-            .or_insert(Vec::new());
     }
 
-    part1(&sattelite_map);
-    part2(&sattelite_map);
+    fn part1(map: &Self::Parsed) -> impl Display {
+        part1(map)
+    }
+
+    fn part2(map: &Self::Parsed) -> impl Display {
+        part2(map)
+    }
 }
 
-fn part1(map: &HashMap<String, Vec<String>>) {
+fn part1(map: &HashMap<String, Vec<String>>) -> usize {
     let mut sum = 0;
     for planet in map.keys() {
         let mut count = 0;
 
-        let mut parent = map.iter().find_map(|(p, s)| s.contains(planet).then(|| p));
+        let mut parent = map
+            .iter()
+            .find_map(|(p, s)| s.contains(planet).then_some(p));
 
         while let Some(planet_parent) = parent {
             count += 1;
             parent = map
                 .iter()
-                .find_map(|(p, s)| s.contains(planet_parent).then(|| p))
+                .find_map(|(p, s)| s.contains(planet_parent).then_some(p))
         }
 
         sum += count;
     }
-    println!("Part 1: {sum}");
+    sum
 }
 
-fn part2(map: &HashMap<String, Vec<String>>) {
+fn part2(map: &HashMap<String, Vec<String>>) -> usize {
     let you = "YOU".to_string();
     let santa = "SAN".to_string();
 
@@ -56,12 +78,12 @@ fn part2(map: &HashMap<String, Vec<String>>) {
     let path = &mut Vec::new();
     path_between(start, end, path, map);
 
-    println!("{:?}", path.len());
+    path.len()
 }
 
 fn path_between(
-    start: &String,
-    end: &String,
+    start: &str,
+    end: &str,
     path: &mut Vec<String>,
     map: &HashMap<String, Vec<String>>,
 ) -> bool {
@@ -70,11 +92,12 @@ fn path_between(
     }
 
     path.push(start.to_string());
-    if let Some(parent) = map.iter().find_map(|(k, p)| p.contains(start).then_some(k)) {
-        if !path.contains(parent) {
-            if path_between(parent, end, path, map) {
-                return true;
-            }
+    if let Some(parent) = map
+        .iter()
+        .find_map(|(k, p)| p.iter().any(|s| s == start).then_some(k))
+    {
+        if !path.contains(parent) && path_between(parent, end, path, map) {
+            return true;
         }
     }
 
@@ -89,5 +112,5 @@ fn path_between(
     }
 
     path.pop();
-    return false;
+    false
 }
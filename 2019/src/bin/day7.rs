@@ -1,73 +1,59 @@
-use std::{isize, ops::Range};
+use std::{fmt::Display, ops::Range};
 
-use aoc2019::ProgramState;
+use aoc2019::{
+    network::{Network, NetworkStop, Topology},
+    Program, ProgramState,
+};
+use aoc_common::Solution;
 
 fn main() {
-    let line = std::io::stdin().lines().next().unwrap().unwrap();
-    let program: Vec<isize> = line.split(',').map(|v| v.parse().unwrap()).collect();
-
-    part1(&program);
-    part2(&program);
+    aoc_common::run::<Day7>();
 }
 
-fn part1(program: &[isize]) {
-    let mut max_thruster_value = isize::MIN;
-
-    for settings in settings(0..5) {
-        let mut input = 0;
+struct Day7;
 
-        for setting in settings {
-            let mut program = ProgramState::new_multi_input(vec![setting, input], program.to_vec());
-            program.run_to_exit();
-            input = program.output();
-        }
+impl Solution for Day7 {
+    type Parsed = Program;
 
-        max_thruster_value = max_thruster_value.max(input);
+    fn parse(input: &str) -> Self::Parsed {
+        input.parse().unwrap()
     }
-    println!("Part 1: {max_thruster_value}");
-}
 
-fn part2(program: &[isize]) {
-    let mut max_thruster_value = isize::MIN;
-
-    for setting in settings(5..10) {
-        let mut amplifiers = [
-            ProgramState::new_multi_input(vec![setting[0]], program.to_vec()),
-            ProgramState::new_multi_input(vec![setting[1]], program.to_vec()),
-            ProgramState::new_multi_input(vec![setting[2]], program.to_vec()),
-            ProgramState::new_multi_input(vec![setting[3]], program.to_vec()),
-            ProgramState::new_multi_input(vec![setting[4]], program.to_vec()),
-        ];
-
-        amplifiers.iter_mut().for_each(|a| {
-            assert!(a.poll().is_pending());
-        });
-
-        let mut input = 0;
+    fn part1(program: &Self::Parsed) -> impl Display {
+        part1(program)
+    }
 
-        loop {
-            let mut any_done = false;
-            for amplifier in amplifiers.iter_mut() {
-                assert_eq!(amplifier.input_len(), 0);
+    fn part2(program: &Self::Parsed) -> impl Display {
+        part2(program)
+    }
+}
 
-                amplifier.add_input(input);
+fn part1(program: &[isize]) -> isize {
+    settings(0..5)
+        .map(|settings| run_amplifiers(program, settings, Topology::Pipeline))
+        .max()
+        .unwrap()
+}
 
-                if amplifier.poll().is_ready() {
-                    any_done = true;
-                }
+fn part2(program: &[isize]) -> isize {
+    settings(5..10)
+        .map(|settings| run_amplifiers(program, settings, Topology::Ring))
+        .max()
+        .unwrap()
+}
 
-                input = amplifier.output();
-            }
+fn run_amplifiers(program: &[isize], settings: [isize; 5], topology: Topology) -> isize {
+    let amplifiers = settings
+        .iter()
+        .map(|setting| ProgramState::new(*setting, program))
+        .collect();
 
-            max_thruster_value = max_thruster_value.max(input);
+    let mut network = Network::new(amplifiers, topology);
+    network.send(0, 0);
 
-            if any_done {
-                break;
-            }
-        }
-    }
+    assert_eq!(network.run().unwrap(), NetworkStop::Halted);
 
-    println!("Part 2: {max_thruster_value}");
+    *network.outputs().last().unwrap()
 }
 
 fn settings(range: Range<isize>) -> impl Iterator<Item = [isize; 5]> {
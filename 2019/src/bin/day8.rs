@@ -1,12 +1,31 @@
+use std::fmt::Display;
+
+use aoc_common::Solution;
+
 fn main() {
-    let line = std::io::stdin().lines().next().unwrap().unwrap();
-    let pixels: Vec<_> = line.chars().map(|v| v as usize - '0' as usize).collect();
+    aoc_common::run::<Day8>();
+}
 
-    part1(&pixels);
-    part2(&pixels);
+struct Day8;
+
+impl Solution for Day8 {
+    type Parsed = Vec<usize>;
+
+    fn parse(input: &str) -> Self::Parsed {
+        let line = input.lines().next().unwrap();
+        line.chars().map(|v| v as usize - '0' as usize).collect()
+    }
+
+    fn part1(pixels: &Self::Parsed) -> impl Display {
+        part1(pixels)
+    }
+
+    fn part2(pixels: &Self::Parsed) -> impl Display {
+        part2(pixels)
+    }
 }
 
-fn part1(pixels: &[usize]) {
+fn part1(pixels: &[usize]) -> usize {
     let width = 25;
     let height = 6;
 
@@ -34,10 +53,11 @@ fn part1(pixels: &[usize]) {
             }
         });
 
-    println!("Part 1: {}", ones * twos);
+    ones * twos
 }
 
-fn part2(pixels: &[usize]) {
+/// The decoded image, with a line per row.
+fn part2(pixels: &[usize]) -> String {
     let width = 25;
     let height = 6;
 
@@ -52,16 +72,17 @@ fn part2(pixels: &[usize]) {
         }
     }
 
-    println!("Part 2:");
+    let mut image = String::new();
     for row in output.chunks(width) {
         for value in row {
             if *value == 0 {
-                print!(" ");
+                image.push(' ');
             } else {
-                print!("█");
+                image.push('█');
             }
         }
-        println!();
+        image.push('\n');
     }
-    println!();
+
+    image
 }
@@ -1,23 +1,38 @@
-use aoc2019::ProgramState;
+use std::fmt::Display;
+
+use aoc2019::{Program, ProgramState};
+use aoc_common::Solution;
 
 fn main() {
-    let line = std::io::stdin().lines().next().unwrap().unwrap();
-    let program: Vec<isize> = line.split(',').map(|v| v.parse().unwrap()).collect();
+    aoc_common::run::<Day9>();
+}
+
+struct Day9;
+
+impl Solution for Day9 {
+    type Parsed = Program;
 
-    part1(&program);
-    part2(&program);
+    fn parse(input: &str) -> Self::Parsed {
+        input.parse().unwrap()
+    }
+
+    fn part1(program: &Self::Parsed) -> impl Display {
+        part1(program)
+    }
+
+    fn part2(program: &Self::Parsed) -> impl Display {
+        part2(program)
+    }
 }
 
-fn part1(program: &[isize]) {
+fn part1(program: &[isize]) -> isize {
     let mut program = ProgramState::new(1, program);
-    program.run_to_exit();
-
-    println!("Part 1: {}", program.output());
+    program.run_to_exit().unwrap();
+    program.drain_outputs().last().unwrap()
 }
 
-fn part2(program: &[isize]) {
+fn part2(program: &[isize]) -> isize {
     let mut program = ProgramState::new(2, program);
-    program.run_to_exit();
-
-    println!("Part 2: {}", program.output());
+    program.run_to_exit().unwrap();
+    program.drain_outputs().last().unwrap()
 }
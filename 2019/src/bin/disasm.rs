@@ -0,0 +1,9 @@
+use aoc2019::{disasm::disassemble, Program};
+
+fn main() {
+    let program = Program::from_stdin().unwrap();
+
+    for line in disassemble(&program) {
+        println!("{line}");
+    }
+}
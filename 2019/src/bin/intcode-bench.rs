@@ -0,0 +1,70 @@
+use std::time::Instant;
+
+use aoc2019::{
+    asm::assemble,
+    memory::{GrowableMemory, MemoryBackend, SparseMemory},
+    Interpreter, Program, ProgramState,
+};
+
+/// Counts to `ITERATIONS`, keeping its counter and scratch values in memory
+/// past the end of the program.
+const SYNTHETIC: &str = "
+        ARB #5000
+loop:   ADD @rb+0, #1, @rb+0
+        MUL @rb+0, #3, @rb+1
+        ADD @rb+1, @rb+2, @rb+2
+        LT @rb+0, #5000000, @rb+3
+        JNZ @rb+3, #loop
+        OUT @rb+2
+        HLT
+";
+
+fn main() {
+    let synthetic = Program::from(assemble(SYNTHETIC).unwrap());
+
+    let boost = std::env::args()
+        .nth(1)
+        .map(|path| Program::from_file(path).unwrap());
+
+    let mut benches = Vec::new();
+    if let Some(boost) = &boost {
+        benches.push(("BOOST self-test", 1, boost));
+        benches.push(("BOOST sensor boost", 2, boost));
+    } else {
+        println!("No day 9 program given, skipping BOOST (usage: intcode-bench [day9 input])");
+    }
+    benches.push(("synthetic", 0, &synthetic));
+
+    for (name, input, program) in benches {
+        for interpreter in [Interpreter::Decoding, Interpreter::Cached] {
+            bench::<SparseMemory>(name, "sparse", interpreter, input, program);
+            bench::<GrowableMemory>(name, "growable", interpreter, input, program);
+        }
+    }
+}
+
+fn bench<M: MemoryBackend<isize>>(
+    name: &str,
+    backend: &str,
+    interpreter: Interpreter,
+    input: isize,
+    program: &[isize],
+) {
+    let mut state = ProgramState::<isize, M>::with_memory(vec![input], program.to_vec());
+    state.set_interpreter(interpreter);
+
+    let start = Instant::now();
+    state.run_to_exit().unwrap();
+    let elapsed = start.elapsed();
+
+    let instructions = state.instructions();
+    let per_second = instructions as f64 / elapsed.as_secs_f64();
+    let output = state.drain_outputs().last();
+
+    println!(
+        "{name:<20} {backend:<10} {:<10} {instructions:>12} instructions in {:>8.2} ms, {:>8.2} M instructions/s (output: {output:?})",
+        format!("{interpreter:?}").to_lowercase(),
+        elapsed.as_secs_f64() * 1000.0,
+        per_second / 1_000_000.0
+    );
+}
@@ -0,0 +1,56 @@
+use aoc2019::{
+    analysis::{analyze, Terminator},
+    Program,
+};
+
+/// Print the control-flow graph of a program, or with `--dot`, render it in
+/// Graphviz DOT format. Warnings go to stderr.
+fn main() {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let dot = args.iter().any(|a| a == "--dot");
+    args.retain(|a| a != "--dot");
+
+    let [path] = &args[..] else {
+        eprintln!("Usage: intcode-cfg [--dot] <program file>");
+        std::process::exit(1);
+    };
+
+    let program = Program::from_file(path).unwrap();
+    let cfg = analyze(&program);
+
+    if dot {
+        print!("{}", cfg.to_dot());
+    } else {
+        for block in cfg.blocks.values() {
+            if cfg.functions.contains(&block.start) {
+                println!("function {}:", block.start);
+            }
+
+            println!("block {}:", block.start);
+            for (address, instruction) in &block.instructions {
+                println!("{address:>6}: {instruction}");
+            }
+
+            let terminator = match &block.terminator {
+                Terminator::Fallthrough(next) => format!("falls through to {next}"),
+                Terminator::Jump(target) => format!("jumps to {target}"),
+                Terminator::Branch { taken, not_taken } => {
+                    format!("branches to {taken} or {not_taken}")
+                }
+                Terminator::Call { target, ret } => format!("calls {target}, returns to {ret}"),
+                Terminator::Return => "returns".to_string(),
+                Terminator::Indirect { not_taken: None } => "jumps indirectly".to_string(),
+                Terminator::Indirect {
+                    not_taken: Some(next),
+                } => format!("jumps indirectly or continues at {next}"),
+                Terminator::Halt => "halts".to_string(),
+                Terminator::Invalid { address, error } => format!("invalid at {address}: {error}"),
+            };
+            println!("        -> {terminator}\n");
+        }
+    }
+
+    for warning in &cfg.warnings {
+        eprintln!("warning: {warning}");
+    }
+}
@@ -0,0 +1,176 @@
+use std::io::Write;
+
+use aoc2019::{
+    debug::{Debugger, Stop},
+    disasm::decode,
+    Program, ProgramState,
+};
+
+const HELP: &str = "\
+commands:
+  s, step [n]          execute n instructions (default 1)
+  c, continue          run until a breakpoint, watchpoint, input request or halt
+  b, break <ip>        set a breakpoint
+  db <ip>              delete a breakpoint
+  w, watch <addr>      set a watchpoint
+  dw <addr>            delete a watchpoint
+  r, regs              show registers
+  x <addr> [len]       examine memory
+  set <addr> <value>   patch memory
+  i, input <values..>  queue input values
+  l, list [addr] [n]   disassemble n instructions (default: 8 at ip)
+  q, quit              exit";
+
+fn main() {
+    let path = std::env::args()
+        .nth(1)
+        .expect("Usage: intcode-dbg <program file>");
+    let program = Program::from_file(path).unwrap();
+
+    let mut dbg = Debugger::new(ProgramState::new_empty(&program));
+
+    list(&dbg, dbg.state().ip(), 1);
+
+    let mut lines = std::io::stdin().lines().map(|v| v.unwrap());
+    loop {
+        print!("(dbg) ");
+        std::io::stdout().flush().unwrap();
+
+        let Some(line) = lines.next() else {
+            break;
+        };
+
+        let mut words = line.split_whitespace();
+        let Some(command) = words.next() else {
+            continue;
+        };
+        let args: Vec<isize> = match words.map(|v| v.parse()).collect() {
+            Ok(args) => args,
+            Err(e) => {
+                println!("invalid argument: {e}");
+                continue;
+            }
+        };
+        let arg = |idx: usize| args.get(idx).copied();
+        let addr = |idx: usize| arg(idx).and_then(|v| usize::try_from(v).ok());
+
+        match (command, addr(0)) {
+            ("s" | "step", _) => {
+                for _ in 0..arg(0).unwrap_or(1) {
+                    let result = dbg.step();
+                    print_outputs(&mut dbg);
+
+                    match result {
+                        Ok(None) => {}
+                        Ok(Some(stop)) => {
+                            print_stop(stop);
+                            break;
+                        }
+                        Err(e) => {
+                            println!("error: {e}");
+                            break;
+                        }
+                    }
+                }
+                list(&dbg, dbg.state().ip(), 1);
+            }
+            ("c" | "continue", _) => {
+                let result = dbg.resume();
+                print_outputs(&mut dbg);
+
+                match result {
+                    Ok(stop) => print_stop(stop),
+                    Err(e) => println!("error: {e}"),
+                }
+                list(&dbg, dbg.state().ip(), 1);
+            }
+            ("b" | "break", Some(ip)) => {
+                dbg.add_breakpoint(ip);
+            }
+            ("db", Some(ip)) => {
+                if !dbg.remove_breakpoint(ip) {
+                    println!("no breakpoint at {ip}");
+                }
+            }
+            ("w" | "watch", Some(address)) => {
+                dbg.add_watchpoint(address);
+            }
+            ("dw", Some(address)) => {
+                if !dbg.remove_watchpoint(address) {
+                    println!("no watchpoint on {address}");
+                }
+            }
+            ("r" | "regs", _) => {
+                let state = dbg.state();
+                println!("ip: {}", state.ip());
+                println!("rb: {}", state.relative_base());
+                println!("pending inputs: {}", state.input_len());
+
+                let breakpoints: Vec<_> = dbg.breakpoints().collect();
+                let watchpoints: Vec<_> = dbg.watchpoints().collect();
+                println!("breakpoints: {breakpoints:?}");
+                println!("watchpoints: {watchpoints:?}");
+            }
+            ("x", Some(address)) => {
+                let len = addr(1).unwrap_or(1);
+                let values: Vec<_> = (address..address + len)
+                    .map(|a| dbg.state().read(a).to_string())
+                    .collect();
+                println!("{address}: {}", values.join(", "));
+            }
+            ("set", Some(address)) if args.len() == 2 => {
+                dbg.state_mut().write(address, args[1]);
+            }
+            ("i" | "input", _) => {
+                args.iter().for_each(|v| dbg.state_mut().add_input(*v));
+            }
+            ("l" | "list", _) => {
+                list(
+                    &dbg,
+                    addr(0).unwrap_or(dbg.state().ip()),
+                    addr(1).unwrap_or(8),
+                );
+            }
+            ("q" | "quit", _) => break,
+            ("h" | "help", _) => println!("{HELP}"),
+            _ => println!("unknown command or missing argument, try `help`"),
+        }
+    }
+}
+
+fn print_outputs(dbg: &mut Debugger) {
+    for output in dbg.take_outputs() {
+        println!("output: {output}");
+    }
+}
+
+fn print_stop(stop: Stop) {
+    match stop {
+        Stop::Breakpoint(ip) => println!("breakpoint at {ip}"),
+        Stop::Watchpoint { address, old, new } => {
+            println!("watchpoint: [{address}] {old} -> {new}")
+        }
+        Stop::NeedsInput => println!("program needs input"),
+        Stop::Halted => println!("program halted"),
+    }
+}
+
+fn list(dbg: &Debugger, mut address: usize, count: usize) {
+    let state = dbg.state();
+
+    for _ in 0..count {
+        let marker = if address == state.ip() { "=>" } else { "  " };
+
+        match decode(state.program(), address) {
+            Ok(instruction) => {
+                println!("{marker} {address:>6}: {instruction}");
+                address += instruction.size();
+            }
+            Err(_) if address < state.program().len() => {
+                println!("{marker} {address:>6}: data {}", state.read(address));
+                address += 1;
+            }
+            Err(_) => break,
+        }
+    }
+}
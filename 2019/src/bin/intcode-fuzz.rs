@@ -0,0 +1,368 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use aoc2019::{IntcodeError, Interpreter, Opcode, ProgramState, RunState};
+
+/// The maximum number of instructions a generated program may execute.
+const FUEL: usize = 10_000;
+
+/// The number of data cells placed after the code of a generated program.
+const DATA_CELLS: usize = 8;
+
+/// Generate random programs and run them on [`ProgramState`], with both
+/// interpreters, in lockstep with [`Reference`]. Stops at the first
+/// divergence and prints the seed and program that caused it.
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let usage = "Usage: intcode-fuzz [iterations] [seed]";
+
+    let iterations: u64 = args.next().map_or(10_000, |v| v.parse().expect(usage));
+    let seed: u64 = args.next().map_or_else(
+        || {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos() as u64
+        },
+        |v| v.parse().expect(usage),
+    );
+
+    println!("Fuzzing {iterations} programs, seed {seed}");
+
+    let mut rng = Rng::new(seed);
+    let mut steps = 0;
+
+    for iteration in 0..iterations {
+        let (program, inputs) = generate(&mut rng);
+
+        for interpreter in [Interpreter::Decoding, Interpreter::Cached] {
+            match check(&program, &inputs, interpreter) {
+                Ok(n) => steps += n,
+                Err(divergence) => {
+                    let join = |values: &[isize]| {
+                        values
+                            .iter()
+                            .map(|v| v.to_string())
+                            .collect::<Vec<_>>()
+                            .join(",")
+                    };
+
+                    println!("Divergence in program {iteration} (seed {seed}), {interpreter:?} interpreter:");
+                    println!("  program: {}", join(&program));
+                    println!("  inputs:  {}", join(&inputs));
+                    println!("  {divergence}");
+                    std::process::exit(1);
+                }
+            }
+        }
+    }
+
+    println!("No divergences, {steps} instructions executed");
+}
+
+/// Run `program` on a [`ProgramState`] and on a [`Reference`], comparing
+/// them after every instruction. Returns the number of steps taken.
+fn check(program: &[isize], inputs: &[isize], interpreter: Interpreter) -> Result<usize, String> {
+    let mut state = ProgramState::new_multi_input(inputs.to_vec(), program.to_vec());
+    state.set_interpreter(interpreter);
+    let mut reference = Reference::new(program, inputs);
+
+    let mut steps = FUEL;
+    for step in 0..FUEL {
+        let (ip, relative_base) = (state.ip(), *state.relative_base());
+        let expected = reference.step();
+        let actual = state.step();
+
+        if actual != expected {
+            return Err(format!(
+                "step {step} at ip {ip}: expected {expected:?}, got {actual:?}"
+            ));
+        }
+
+        if (state.ip(), *state.relative_base()) != (reference.ip, reference.relative_base) {
+            return Err(format!(
+                "step {step} at ip {ip} (relative base {relative_base}): expected ip {} and relative base {}, got {} and {}",
+                reference.ip,
+                reference.relative_base,
+                state.ip(),
+                state.relative_base()
+            ));
+        }
+
+        match actual {
+            Ok(None | Some(RunState::Output(_))) => {}
+            Ok(Some(RunState::NeedsInput | RunState::Halted)) | Err(_) => {
+                steps = step + 1;
+                break;
+            }
+        }
+    }
+
+    let snapshot = state.snapshot();
+    if snapshot.program != reference.program {
+        return Err(format!(
+            "after {steps} steps: expected program memory {:?}, got {:?}",
+            reference.program, snapshot.program
+        ));
+    }
+
+    let extra: BTreeMap<usize, isize> = reference
+        .extra_memory
+        .iter()
+        .filter(|(_, v)| **v != 0)
+        .map(|(a, v)| (*a, *v))
+        .collect();
+
+    if snapshot.extra_memory != extra {
+        return Err(format!(
+            "after {steps} steps: expected memory past the program {extra:?}, got {:?}",
+            snapshot.extra_memory
+        ));
+    }
+
+    Ok(steps)
+}
+
+/// A deliberately simple Intcode interpreter, written straight from the
+/// puzzle descriptions, that [`ProgramState`] is checked against.
+///
+/// Arithmetic wraps, like in [`ProgramState`]'s unchecked mode, and errors
+/// are reported the way [`ProgramState::step`] reports them.
+struct Reference {
+    program: Vec<isize>,
+    extra_memory: BTreeMap<usize, isize>,
+    ip: usize,
+    relative_base: isize,
+    inputs: VecDeque<isize>,
+}
+
+impl Reference {
+    fn new(program: &[isize], inputs: &[isize]) -> Self {
+        Self {
+            program: program.to_vec(),
+            extra_memory: BTreeMap::new(),
+            ip: 0,
+            relative_base: 0,
+            inputs: inputs.iter().copied().collect(),
+        }
+    }
+
+    fn load(&self, address: usize) -> isize {
+        match self.program.get(address) {
+            Some(value) => *value,
+            None => self.extra_memory.get(&address).copied().unwrap_or(0),
+        }
+    }
+
+    fn store(&mut self, address: usize, value: isize) {
+        match self.program.get_mut(address) {
+            Some(cell) => *cell = value,
+            None => {
+                self.extra_memory.insert(address, value);
+            }
+        }
+    }
+
+    /// The address parameter `n` of the current instruction refers to, or
+    /// `None` for an immediate parameter.
+    fn address(&self, n: usize) -> Result<Option<usize>, IntcodeError> {
+        let ip = self.ip;
+        let mode = self.load(ip) / 10isize.pow(n as u32 + 1) % 10;
+        let value = self.load(ip + n);
+
+        let address = match mode {
+            0 => value,
+            1 => return Ok(None),
+            2 => self.relative_base.wrapping_add(value),
+            mode => return Err(IntcodeError::InvalidParamMode { ip, mode }),
+        };
+
+        usize::try_from(address)
+            .map(Some)
+            .map_err(|_| IntcodeError::NegativeAddress { ip, address })
+    }
+
+    fn read(&self, n: usize) -> Result<isize, IntcodeError> {
+        Ok(match self.address(n)? {
+            Some(address) => self.load(address),
+            None => self.load(self.ip + n),
+        })
+    }
+
+    fn write(&mut self, n: usize, value: isize) -> Result<(), IntcodeError> {
+        let address = self
+            .address(n)?
+            .ok_or(IntcodeError::ImmediateWrite { ip: self.ip })?;
+        self.store(address, value);
+        Ok(())
+    }
+
+    fn step(&mut self) -> Result<Option<RunState>, IntcodeError> {
+        let ip = self.ip;
+        if ip >= self.program.len() {
+            return Err(IntcodeError::IpOutOfRange { ip: ip as isize });
+        }
+
+        let instruction = self.load(ip);
+        let opcode = if instruction < 0 {
+            -1
+        } else {
+            instruction % 100
+        };
+
+        match opcode {
+            1 | 2 => {
+                let (a, b) = (self.read(1)?, self.read(2)?);
+                let value = if opcode == 1 {
+                    a.wrapping_add(b)
+                } else {
+                    a.wrapping_mul(b)
+                };
+                self.write(3, value)?;
+                self.ip += 4;
+            }
+            3 => {
+                let Some(input) = self.inputs.pop_front() else {
+                    return Ok(Some(RunState::NeedsInput));
+                };
+                self.write(1, input)?;
+                self.ip += 2;
+            }
+            4 => {
+                let value = self.read(1)?;
+                self.ip += 2;
+                return Ok(Some(RunState::Output(value)));
+            }
+            5 | 6 => {
+                let condition = self.read(1)?;
+                if (condition != 0) == (opcode == 5) {
+                    let target = self.read(2)?;
+                    self.ip = usize::try_from(target)
+                        .map_err(|_| IntcodeError::IpOutOfRange { ip: target })?;
+                } else {
+                    self.ip += 3;
+                }
+            }
+            7 | 8 => {
+                let (a, b) = (self.read(1)?, self.read(2)?);
+                let result = if opcode == 7 { a < b } else { a == b };
+                self.write(3, result as isize)?;
+                self.ip += 4;
+            }
+            9 => {
+                self.relative_base = self.relative_base.wrapping_add(self.read(1)?);
+                self.ip += 2;
+            }
+            99 => return Ok(Some(RunState::Halted)),
+            _ => {
+                return Err(IntcodeError::UnknownOpcode {
+                    ip,
+                    opcode: instruction,
+                })
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+/// Generate a program of valid instructions followed by a few data cells,
+/// and the inputs to run it with.
+///
+/// Every instruction is valid when the program starts, but parameters point
+/// all over the program, so it will often overwrite its own code.
+fn generate(rng: &mut Rng) -> (Vec<isize>, Vec<isize>) {
+    let opcodes: Vec<Opcode> = (0..rng.below(16) + 1)
+        .map(|_| Opcode::ALL[rng.below(Opcode::ALL.len())])
+        .collect();
+
+    let starts: Vec<usize> = opcodes
+        .iter()
+        .scan(0, |address, op| {
+            let start = *address;
+            *address += op.arity() + 1;
+            Some(start)
+        })
+        .collect();
+    let len = starts.last().unwrap() + opcodes.last().unwrap().arity() + 1 + DATA_CELLS;
+
+    let mut program = Vec::with_capacity(len);
+    for op in &opcodes {
+        let mut modes = 0;
+        let mut params = Vec::new();
+
+        for idx in 0..op.arity() {
+            let writes = op.write_param() == Some(idx);
+            let jump_target = matches!(op, Opcode::JumpIfTrue | Opcode::JumpIfFalse) && idx == 1;
+
+            let mode = if writes && rng.below(2) == 0 {
+                2
+            } else if writes {
+                0
+            } else {
+                rng.below(3)
+            };
+
+            let value = match mode {
+                // Mostly inside the program, sometimes just past its end.
+                0 => rng.below(len + 4) as isize,
+                1 if jump_target => match rng.below(8) {
+                    0 => rng.range(-2, len as isize + 2),
+                    _ => starts[rng.below(starts.len())] as isize,
+                },
+                1 if *op == Opcode::AdjustRelativeBase => rng.range(-8, 8),
+                1 => rng.value(),
+                _ => rng.range(-8, 8),
+            };
+
+            modes += mode as isize * 10isize.pow(idx as u32);
+            params.push(value);
+        }
+
+        program.push(modes * 100 + op.code());
+        program.extend(params);
+    }
+
+    program.extend((0..DATA_CELLS).map(|_| rng.value()));
+
+    let inputs = (0..rng.below(4)).map(|_| rng.value()).collect();
+
+    (program, inputs)
+}
+
+/// A xorshift64* generator, so runs can be reproduced from their seed.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        // The state must not be zero.
+        Self(seed ^ 0x9e37_79b9_7f4a_7c15)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// A number in `0..n`.
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    /// A number in `low..high`.
+    fn range(&mut self, low: isize, high: isize) -> isize {
+        low + self.below((high - low) as usize) as isize
+    }
+
+    /// A value to compute with: usually small, sometimes at the edges of
+    /// the word size.
+    fn value(&mut self) -> isize {
+        match self.below(16) {
+            0 => isize::MAX - self.range(0, 4),
+            1 => isize::MIN + self.range(0, 4),
+            2 => self.next() as isize,
+            _ => self.range(-20, 100),
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use aoc2019::{Program, ProgramState};
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let path = args
+        .next()
+        .expect("Usage: intcode-prof <program file> [inputs..]");
+    let program = Program::from_file(path).unwrap();
+    let inputs: Vec<isize> = args.map(|v| v.parse().unwrap()).collect();
+
+    let mut state = ProgramState::new_multi_input(inputs, program);
+    state.set_profiling(true);
+
+    if let Err(e) = state.run_to_exit() {
+        println!("error: {e}");
+    }
+
+    let outputs: Vec<_> = state.drain_outputs().map(|v| v.to_string()).collect();
+    println!("outputs: {}\n", outputs.join(","));
+
+    let profile = state.profile().unwrap();
+    print!("{}", profile.report(state.program().len(), 20));
+}
@@ -0,0 +1,218 @@
+use std::io::{BufRead, Write};
+
+use aoc2019::{
+    ascii::AsciiMachine, snapshot::Snapshot, IntcodeError, Program, ProgramState, RunState,
+};
+
+const HELP: &str = "\
+Lines that don't start with `:` are sent to the program as input. In numeric
+mode, a line holds one or more numbers separated by commas or spaces. In ASCII
+mode, the line is sent as text followed by a newline.
+
+commands:
+  :ascii               switch to ASCII input and output
+  :numeric             switch to numeric input and output
+  :save <path>         save the machine state to a snapshot file
+  :load <path>         restore the machine state from a snapshot file
+  :transcript <path>   write every input line so far to a file
+  :replay <path>       send every line of a transcript as input
+  :quit                exit
+  :help                show this message";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mode {
+    Numeric,
+    Ascii,
+}
+
+struct Repl {
+    machine: AsciiMachine,
+    mode: Mode,
+    /// Input lines and mode switches since the program was started or
+    /// loaded, in the order they were entered.
+    transcript: Vec<String>,
+    halted: bool,
+}
+
+fn main() {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let ascii = args.iter().any(|a| a == "--ascii");
+    args.retain(|a| a != "--ascii");
+
+    let [path] = &args[..] else {
+        eprintln!("Usage: intcode [--ascii] <program file>");
+        std::process::exit(1);
+    };
+
+    let program = Program::from_file(path).unwrap();
+
+    let mut repl = Repl {
+        machine: AsciiMachine::new(ProgramState::new_empty(&program)),
+        mode: if ascii { Mode::Ascii } else { Mode::Numeric },
+        transcript: Vec::new(),
+        halted: false,
+    };
+
+    repl.run();
+
+    let mut lines = std::io::stdin().lock().lines().map(|v| v.unwrap());
+    loop {
+        repl.prompt();
+
+        let Some(line) = lines.next() else {
+            break;
+        };
+
+        if !repl.handle(&line) {
+            break;
+        }
+    }
+}
+
+impl Repl {
+    /// Handle one line of user input. Returns `false` to quit.
+    fn handle(&mut self, line: &str) -> bool {
+        let Some(command) = line.strip_prefix(':') else {
+            self.input(line);
+            return true;
+        };
+
+        let (command, arg) = match command.split_once(char::is_whitespace) {
+            Some((command, arg)) => (command, Some(arg.trim())),
+            None => (command.trim(), None),
+        };
+
+        match (command, arg) {
+            ("ascii", _) => {
+                self.mode = Mode::Ascii;
+                self.transcript.push(line.to_string());
+            }
+            ("numeric", _) => {
+                self.mode = Mode::Numeric;
+                self.transcript.push(line.to_string());
+            }
+            ("save", Some(path)) => match self.machine.machine().snapshot().save(path) {
+                Ok(()) => println!("saved to {path}"),
+                Err(e) => println!("error: {e}"),
+            },
+            ("load", Some(path)) => match Snapshot::load(path) {
+                Ok(snapshot) => {
+                    let mut state = self.machine.machine().clone();
+                    state.restore(snapshot);
+                    self.machine = AsciiMachine::new(state);
+                    self.transcript.clear();
+                    self.halted = false;
+                    println!("loaded {path}");
+                    self.run();
+                }
+                Err(e) => println!("error: {e}"),
+            },
+            ("transcript", Some(path)) => {
+                let mut text = self.transcript.join("\n");
+                text.push('\n');
+
+                match std::fs::write(path, text) {
+                    Ok(()) => println!("wrote {} lines to {path}", self.transcript.len()),
+                    Err(e) => println!("error: {e}"),
+                }
+            }
+            ("replay", Some(path)) => match std::fs::read_to_string(path) {
+                Ok(text) => {
+                    for line in text.lines() {
+                        self.prompt();
+                        println!("{line}");
+
+                        if !self.handle(line) {
+                            return false;
+                        }
+                    }
+                }
+                Err(e) => println!("error: {e}"),
+            },
+            ("quit" | "q", _) => return false,
+            ("help" | "h", _) => println!("{HELP}"),
+            _ => println!("unknown command or missing argument, try `:help`"),
+        }
+
+        true
+    }
+
+    fn input(&mut self, line: &str) {
+        if self.halted {
+            println!("the program has halted, use `:load` or `:quit`");
+            return;
+        }
+
+        match self.mode {
+            Mode::Numeric => {
+                let values: Result<Vec<isize>, _> = line
+                    .split([',', ' '])
+                    .filter(|v| !v.is_empty())
+                    .map(|v| v.parse())
+                    .collect();
+
+                match values {
+                    Ok(values) => values
+                        .into_iter()
+                        .for_each(|v| self.machine.machine_mut().add_input(v)),
+                    Err(e) => {
+                        println!("invalid input: {e}");
+                        return;
+                    }
+                }
+            }
+            Mode::Ascii => self.machine.send(line),
+        }
+
+        self.transcript.push(line.to_string());
+        self.run();
+    }
+
+    /// Run until the program needs input or halts, printing output as it is
+    /// produced.
+    fn run(&mut self) {
+        let result = match self.mode {
+            // Without further input, this stops as soon as the program needs
+            // some.
+            Mode::Ascii => self
+                .machine
+                .run_interactive(std::io::empty(), std::io::stdout().lock())
+                .map_err(|e| e.to_string()),
+            Mode::Numeric => self.run_numeric().map_err(|e| e.to_string()),
+        };
+
+        match result {
+            Ok(RunState::Halted) => {
+                println!("[program halted]");
+                self.halted = true;
+            }
+            Ok(_) => {}
+            Err(e) => {
+                println!("[error: {e}]");
+                self.halted = true;
+            }
+        }
+    }
+
+    fn run_numeric(&mut self) -> Result<RunState, IntcodeError> {
+        let mut stdout = std::io::stdout().lock();
+
+        loop {
+            match self.machine.machine_mut().poll()? {
+                RunState::Output(value) => {
+                    writeln!(stdout, "{value}").unwrap();
+                    stdout.flush().unwrap();
+                }
+                state => break Ok(state),
+            }
+        }
+    }
+
+    fn prompt(&self) {
+        // ASCII programs print their own prompts.
+        if self.mode == Mode::Numeric && !self.halted {
+            print!("> ");
+            std::io::stdout().flush().unwrap();
+        }
+    }
+}
@@ -0,0 +1,235 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{IntcodeError, ProgramState, RunState};
+
+/// The reason a [`Debugger`] stopped.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Stop {
+    /// The instruction pointer reached a breakpoint. The instruction at the
+    /// breakpoint has not been executed yet.
+    Breakpoint(usize),
+    /// A watched memory cell changed value.
+    Watchpoint {
+        address: usize,
+        old: isize,
+        new: isize,
+    },
+    NeedsInput,
+    Halted,
+}
+
+/// A [`ProgramState`] with breakpoints on instruction pointers and
+/// watchpoints on memory addresses.
+///
+/// Outputs produced while debugging are collected and can be taken with
+/// [`Debugger::take_outputs`].
+#[derive(Debug, Clone)]
+pub struct Debugger {
+    state: ProgramState,
+    breakpoints: BTreeSet<usize>,
+    watchpoints: BTreeSet<usize>,
+    outputs: Vec<isize>,
+}
+
+impl Debugger {
+    pub fn new(state: ProgramState) -> Self {
+        Self {
+            state,
+            breakpoints: BTreeSet::new(),
+            watchpoints: BTreeSet::new(),
+            outputs: Vec::new(),
+        }
+    }
+
+    pub fn state(&self) -> &ProgramState {
+        &self.state
+    }
+
+    pub fn state_mut(&mut self) -> &mut ProgramState {
+        &mut self.state
+    }
+
+    pub fn into_state(self) -> ProgramState {
+        self.state
+    }
+
+    pub fn breakpoints(&self) -> impl Iterator<Item = usize> + '_ {
+        self.breakpoints.iter().copied()
+    }
+
+    /// Returns `false` if a breakpoint at `ip` already existed.
+    pub fn add_breakpoint(&mut self, ip: usize) -> bool {
+        self.breakpoints.insert(ip)
+    }
+
+    /// Returns `false` if there was no breakpoint at `ip`.
+    pub fn remove_breakpoint(&mut self, ip: usize) -> bool {
+        self.breakpoints.remove(&ip)
+    }
+
+    pub fn watchpoints(&self) -> impl Iterator<Item = usize> + '_ {
+        self.watchpoints.iter().copied()
+    }
+
+    /// Watch `address` for changes. This works for any address, including
+    /// those past the end of the program.
+    ///
+    /// Returns `false` if `address` was already watched.
+    pub fn add_watchpoint(&mut self, address: usize) -> bool {
+        self.watchpoints.insert(address)
+    }
+
+    /// Returns `false` if `address` was not watched.
+    pub fn remove_watchpoint(&mut self, address: usize) -> bool {
+        self.watchpoints.remove(&address)
+    }
+
+    pub fn take_outputs(&mut self) -> Vec<isize> {
+        std::mem::take(&mut self.outputs)
+    }
+
+    /// Execute a single instruction, ignoring breakpoints.
+    ///
+    /// Returns `None` if execution can continue and no watched cell changed.
+    pub fn step(&mut self) -> Result<Option<Stop>, IntcodeError> {
+        let watched: BTreeMap<_, _> = self
+            .watchpoints
+            .iter()
+            .map(|addr| (*addr, self.state.read(*addr)))
+            .collect();
+
+        match self.state.step()? {
+            None => {}
+            Some(RunState::Output(value)) => self.outputs.push(value),
+            Some(RunState::NeedsInput) => return Ok(Some(Stop::NeedsInput)),
+            Some(RunState::Halted) => return Ok(Some(Stop::Halted)),
+        }
+
+        let changed = watched.into_iter().find_map(|(address, old)| {
+            let new = self.state.read(address);
+            (old != new).then_some(Stop::Watchpoint { address, old, new })
+        });
+
+        Ok(changed)
+    }
+
+    /// Run until a breakpoint or watchpoint is hit, the program needs input or
+    /// the program halts.
+    ///
+    /// The current instruction is always executed, so resuming from a
+    /// breakpoint does not stop at that same breakpoint again.
+    pub fn resume(&mut self) -> Result<Stop, IntcodeError> {
+        if let Some(stop) = self.step()? {
+            return Ok(stop);
+        }
+
+        loop {
+            let ip = self.state.ip();
+            if self.breakpoints.contains(&ip) {
+                return Ok(Stop::Breakpoint(ip));
+            }
+
+            if let Some(stop) = self.step()? {
+                return Ok(stop);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Adds 5 to every input, keeping the result at 1000.
+    const ADD_FIVE: [isize; 12] = [3, 20, 1001, 20, 5, 1000, 4, 1000, 1105, 1, 0, 99];
+
+    fn debugger(inputs: &[isize]) -> Debugger {
+        Debugger::new(ProgramState::new_multi_input(
+            inputs.to_vec(),
+            ADD_FIVE.to_vec(),
+        ))
+    }
+
+    #[test]
+    fn breakpoints() {
+        let mut debugger = debugger(&[1, 2]);
+        assert!(debugger.add_breakpoint(6));
+        assert!(!debugger.add_breakpoint(6));
+        assert!(debugger.add_breakpoint(0));
+
+        // The instruction at the current breakpoint is executed first.
+        assert_eq!(debugger.resume(), Ok(Stop::Breakpoint(6)));
+        assert_eq!(debugger.state().ip(), 6);
+        assert!(debugger.take_outputs().is_empty());
+
+        assert_eq!(debugger.resume(), Ok(Stop::Breakpoint(0)));
+        assert_eq!(debugger.take_outputs(), [6]);
+
+        assert!(debugger.remove_breakpoint(6));
+        assert!(!debugger.remove_breakpoint(6));
+        assert_eq!(debugger.breakpoints().collect::<Vec<_>>(), [0]);
+
+        assert_eq!(debugger.resume(), Ok(Stop::Breakpoint(0)));
+        assert_eq!(debugger.take_outputs(), [7]);
+        assert_eq!(debugger.resume(), Ok(Stop::NeedsInput));
+        assert_eq!(debugger.state().ip(), 0);
+    }
+
+    #[test]
+    fn watchpoints() {
+        let mut debugger = debugger(&[1, 1, 2]);
+        assert!(debugger.add_watchpoint(1000));
+        assert!(!debugger.add_watchpoint(1000));
+
+        assert_eq!(
+            debugger.resume(),
+            Ok(Stop::Watchpoint {
+                address: 1000,
+                old: 0,
+                new: 6
+            })
+        );
+        assert_eq!(debugger.state().ip(), 6);
+
+        // Writing the same value again is not a change.
+        assert_eq!(
+            debugger.resume(),
+            Ok(Stop::Watchpoint {
+                address: 1000,
+                old: 6,
+                new: 7
+            })
+        );
+        assert_eq!(debugger.take_outputs(), [6, 6]);
+
+        assert!(debugger.remove_watchpoint(1000));
+        assert_eq!(debugger.watchpoints().count(), 0);
+        assert_eq!(debugger.resume(), Ok(Stop::NeedsInput));
+        assert_eq!(debugger.take_outputs(), [7]);
+    }
+
+    #[test]
+    fn step() {
+        let mut debugger = debugger(&[1]);
+        debugger.add_breakpoint(2);
+
+        // Stepping ignores breakpoints.
+        let mut ips = Vec::new();
+        while debugger.step() == Ok(None) {
+            ips.push(debugger.state().ip());
+        }
+        assert_eq!(ips, [2, 6, 8, 0]);
+        assert_eq!(debugger.take_outputs(), [6]);
+        assert_eq!(debugger.state().ip(), 0);
+
+        let mut halted = Debugger::new(ProgramState::new_empty(&[99]));
+        assert_eq!(halted.step(), Ok(Some(Stop::Halted)));
+        assert_eq!(halted.resume(), Ok(Stop::Halted));
+
+        let mut invalid = Debugger::new(ProgramState::new_empty(&[42]));
+        assert_eq!(
+            invalid.resume(),
+            Err(IntcodeError::UnknownOpcode { ip: 0, opcode: 42 })
+        );
+    }
+}
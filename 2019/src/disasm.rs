@@ -0,0 +1,285 @@
+use crate::{param_modes, IntcodeError, Opcode, ParamMode};
+
+/// A decoded instruction parameter.
+///
+/// Displayed as `12` for position mode, `#12` for immediate mode and
+/// `@rb+12` for relative mode.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Operand {
+    pub mode: ParamMode,
+    pub value: isize,
+}
+
+impl std::fmt::Display for Operand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.mode {
+            ParamMode::Position => write!(f, "{}", self.value),
+            ParamMode::Immediate => write!(f, "#{}", self.value),
+            ParamMode::Relative if self.value < 0 => write!(f, "@rb-{}", self.value.unsigned_abs()),
+            ParamMode::Relative => write!(f, "@rb+{}", self.value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Instruction {
+    pub opcode: Opcode,
+    pub operands: Vec<Operand>,
+}
+
+impl Instruction {
+    /// The number of memory cells this instruction occupies.
+    pub fn size(&self) -> usize {
+        1 + self.operands.len()
+    }
+
+    /// Encode this instruction back into memory cells.
+    pub fn encode(&self) -> Vec<isize> {
+        let mut op = self.opcode.code();
+        let mut scale = 100;
+
+        for operand in &self.operands {
+            op += operand.mode.digit() * scale;
+            scale *= 10;
+        }
+
+        std::iter::once(op)
+            .chain(self.operands.iter().map(|o| o.value))
+            .collect()
+    }
+}
+
+impl std::fmt::Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.opcode.mnemonic())?;
+
+        for (idx, operand) in self.operands.iter().enumerate() {
+            let sep = if idx == 0 { " " } else { ", " };
+            write!(f, "{sep}{operand}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DecodeError {
+    /// The cell does not start a valid instruction.
+    Invalid(IntcodeError),
+    /// The instruction's operands extend past the end of the program.
+    Truncated,
+}
+
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Invalid(e) => write!(f, "{e}"),
+            Self::Truncated => write!(f, "instruction extends past the end of the program"),
+        }
+    }
+}
+
+impl From<IntcodeError> for DecodeError {
+    fn from(value: IntcodeError) -> Self {
+        Self::Invalid(value)
+    }
+}
+
+/// Decode the instruction starting at `address`.
+pub fn decode(program: &[isize], address: usize) -> Result<Instruction, DecodeError> {
+    let op_in = *program.get(address).ok_or(DecodeError::Truncated)?;
+    let opcode = Opcode::decode(address, &op_in)?;
+
+    let modes = param_modes(address, op_in as usize).take(opcode.arity());
+
+    let mut operands = Vec::with_capacity(opcode.arity());
+    for (idx, mode) in modes.enumerate() {
+        let mode = mode?;
+
+        if mode == ParamMode::Immediate && opcode.write_param() == Some(idx) {
+            return Err(IntcodeError::ImmediateWrite { ip: address }.into());
+        }
+
+        let value = *program
+            .get(address + 1 + idx)
+            .ok_or(DecodeError::Truncated)?;
+
+        operands.push(Operand { mode, value });
+    }
+
+    Ok(Instruction { opcode, operands })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Line {
+    Instruction {
+        address: usize,
+        instruction: Instruction,
+    },
+    /// A cell that does not decode as an instruction.
+    Data {
+        address: usize,
+        value: isize,
+        error: DecodeError,
+    },
+}
+
+impl Line {
+    pub fn address(&self) -> usize {
+        match self {
+            Self::Instruction { address, .. } | Self::Data { address, .. } => *address,
+        }
+    }
+}
+
+impl std::fmt::Display for Line {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Instruction {
+                address,
+                instruction,
+            } => write!(f, "{address:>6}: {instruction}"),
+            Self::Data {
+                address,
+                value,
+                error,
+            } => {
+                let data = format!("data {value}");
+                write!(f, "{address:>6}: {data:<24} ; {error}")
+            }
+        }
+    }
+}
+
+/// Linearly sweep `program`, decoding an instruction at every address that
+/// follows a decoded instruction and marking cells that don't decode as data.
+pub fn disassemble(program: &[isize]) -> Vec<Line> {
+    let mut lines = Vec::new();
+    let mut address = 0;
+
+    while address < program.len() {
+        match decode(program, address) {
+            Ok(instruction) => {
+                let size = instruction.size();
+                lines.push(Line::Instruction {
+                    address,
+                    instruction,
+                });
+                address += size;
+            }
+            Err(error) => {
+                lines.push(Line::Data {
+                    address,
+                    value: program[address],
+                    error,
+                });
+                address += 1;
+            }
+        }
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every opcode once, using every parameter mode, followed by cells that
+    /// don't decode.
+    const PROGRAM: [isize; 32] = [
+        1, 1, 2, 3, // ADD
+        1102, 4, 5, 6, // MUL
+        203, -1, // IN
+        104, 7, // OUT
+        1005, 1, 0, // JNZ
+        2106, 0, 9, // JZ
+        21107, 1, 2, 3, // LT
+        8, 1, 2, 3, // EQ
+        209, 4,  // ARB
+        99, // HLT
+        98, 103, 5, // data
+    ];
+
+    #[test]
+    fn instructions() {
+        let lines: Vec<_> = disassemble(&PROGRAM)
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+
+        assert_eq!(
+            lines[..10],
+            [
+                "     0: ADD 1, 2, 3",
+                "     4: MUL #4, #5, 6",
+                "     8: IN @rb-1",
+                "    10: OUT #7",
+                "    12: JNZ 1, #0",
+                "    15: JZ #0, @rb+9",
+                "    18: LT #1, #2, @rb+3",
+                "    22: EQ 1, 2, 3",
+                "    26: ARB @rb+4",
+                "    28: HLT",
+            ]
+        );
+
+        for line in &disassemble(&PROGRAM)[..10] {
+            let Line::Instruction {
+                address,
+                instruction,
+            } = line
+            else {
+                panic!("{line} is not an instruction");
+            };
+
+            let size = instruction.size();
+            assert_eq!(instruction.encode(), PROGRAM[*address..*address + size]);
+        }
+    }
+
+    #[test]
+    fn data() {
+        let lines = disassemble(&PROGRAM);
+        assert_eq!(lines.len(), 13);
+
+        assert_eq!(
+            lines[10],
+            Line::Data {
+                address: 29,
+                value: 98,
+                error: DecodeError::Invalid(IntcodeError::UnknownOpcode { ip: 29, opcode: 98 }),
+            }
+        );
+        assert_eq!(
+            lines[11],
+            Line::Data {
+                address: 30,
+                value: 103,
+                error: DecodeError::Invalid(IntcodeError::ImmediateWrite { ip: 30 }),
+            }
+        );
+        assert_eq!(
+            lines[11].to_string(),
+            "    30: data 103                 ; immediate-mode write at ip 30"
+        );
+    }
+
+    #[test]
+    fn truncated() {
+        // The JNZ at 31 is missing both of its operands.
+        let lines = disassemble(&PROGRAM);
+        assert_eq!(
+            lines[12],
+            Line::Data {
+                address: 31,
+                value: 5,
+                error: DecodeError::Truncated,
+            }
+        );
+
+        assert_eq!(decode(&[1, 0, 0], 0), Err(DecodeError::Truncated));
+        assert_eq!(decode(&[99], 1), Err(DecodeError::Truncated));
+        assert_eq!(decode(&[1, 0, 0, 0], 0).map(|i| i.size()), Ok(4));
+    }
+}
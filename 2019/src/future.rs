@@ -0,0 +1,499 @@
+//! Running Intcode machines as [`Future`]s.
+//!
+//! A [`MachineFuture`] reads its input from a [`Receiver`] and writes its
+//! output to a [`Sender`], waiting whenever it runs out of input. Machines
+//! and host-side logic can then be combined as ordinary async code and
+//! driven by the single-threaded [`Executor`] in this module.
+
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Wake, Waker},
+};
+
+use crate::{memory::MemoryBackend, word::Word, FuelState, IntcodeError, ProgramState, RunState};
+
+#[derive(Debug)]
+struct Channel<T> {
+    values: VecDeque<T>,
+    senders: usize,
+    waker: Option<Waker>,
+}
+
+/// Create an unbounded channel for passing values between machines and the
+/// host.
+pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
+    let channel = Rc::new(RefCell::new(Channel {
+        values: VecDeque::new(),
+        senders: 1,
+        waker: None,
+    }));
+
+    (Sender(channel.clone()), Receiver(channel))
+}
+
+/// The sending half of a [`channel`]. The channel is closed once every
+/// sender has been dropped.
+#[derive(Debug)]
+pub struct Sender<T>(Rc<RefCell<Channel<T>>>);
+
+impl<T> Sender<T> {
+    pub fn send(&self, value: T) {
+        let mut channel = self.0.borrow_mut();
+        channel.values.push_back(value);
+
+        if let Some(waker) = channel.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.0.borrow_mut().senders += 1;
+        Self(self.0.clone())
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let mut channel = self.0.borrow_mut();
+        channel.senders -= 1;
+
+        if channel.senders == 0 {
+            if let Some(waker) = channel.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+/// The receiving half of a [`channel`].
+///
+/// This is a stream of values: [`Receiver::poll_recv`] yields
+/// `Poll::Ready(None)` once the channel is closed and empty.
+#[derive(Debug)]
+pub struct Receiver<T>(Rc<RefCell<Channel<T>>>);
+
+impl<T> Receiver<T> {
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let mut channel = self.0.borrow_mut();
+
+        if let Some(value) = channel.values.pop_front() {
+            Poll::Ready(Some(value))
+        } else if channel.senders == 0 {
+            Poll::Ready(None)
+        } else {
+            channel.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+
+    /// Wait for the next value, or `None` if the channel is closed.
+    pub fn recv(&mut self) -> Recv<'_, T> {
+        Recv(self)
+    }
+
+    /// Take the next value if one is available, without waiting.
+    pub fn try_recv(&mut self) -> Option<T> {
+        self.0.borrow_mut().values.pop_front()
+    }
+}
+
+/// The future returned by [`Receiver::recv`].
+#[derive(Debug)]
+pub struct Recv<'a, T>(&'a mut Receiver<T>);
+
+impl<T> Future for Recv<'_, T> {
+    type Output = Option<T>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.0.poll_recv(cx)
+    }
+}
+
+/// The number of instructions a [`MachineFuture`] executes before it yields
+/// to other tasks.
+pub const TIME_SLICE: u64 = 10_000;
+
+/// A [`ProgramState`] that runs as a future.
+///
+/// Outputs are sent to `output` as they are produced. When the program needs
+/// input and none is queued, it waits for a value from `input`. A program
+/// that never waits yields after every [`TIME_SLICE`] instructions, so it
+/// cannot starve the other tasks on its executor. The future
+/// resolves to the halted machine, or fails with
+/// [`IntcodeError::MissingInput`] if `input` is closed while the program is
+/// waiting on it.
+#[derive(Debug)]
+pub struct MachineFuture<W: Word = isize, M: MemoryBackend<W> = crate::memory::GrowableMemory<W>> {
+    machine: Option<ProgramState<W, M>>,
+    input: Receiver<W>,
+    output: Sender<W>,
+}
+
+impl<W: Word, M: MemoryBackend<W>> MachineFuture<W, M> {
+    pub fn new(machine: ProgramState<W, M>, input: Receiver<W>, output: Sender<W>) -> Self {
+        Self {
+            machine: Some(machine),
+            input,
+            output,
+        }
+    }
+}
+
+// The machine is never pinned in place, so this holds even if `W` or `M`
+// is not `Unpin`.
+impl<W: Word, M: MemoryBackend<W>> Unpin for MachineFuture<W, M> {}
+
+impl<W: Word, M: MemoryBackend<W>> Future for MachineFuture<W, M> {
+    type Output = Result<ProgramState<W, M>, IntcodeError<W>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let machine = this
+            .machine
+            .as_mut()
+            .expect("MachineFuture polled after completion");
+
+        let limit = machine.instructions().saturating_add(TIME_SLICE);
+
+        loop {
+            let fuel = limit.saturating_sub(machine.instructions());
+
+            match machine.poll_with_fuel(fuel) {
+                Ok(FuelState::OutOfFuel) => {
+                    cx.waker().wake_by_ref();
+                    return Poll::Pending;
+                }
+                Ok(FuelState::Ran(RunState::Output(value))) => this.output.send(value),
+                Ok(FuelState::Ran(RunState::NeedsInput)) => match this.input.poll_recv(cx) {
+                    Poll::Ready(Some(value)) => machine.add_input(value),
+                    Poll::Ready(None) => {
+                        let ip = machine.ip();
+                        this.machine = None;
+                        return Poll::Ready(Err(IntcodeError::MissingInput { ip }));
+                    }
+                    Poll::Pending => return Poll::Pending,
+                },
+                Ok(FuelState::Ran(RunState::Halted)) => {
+                    return Poll::Ready(Ok(this.machine.take().unwrap()))
+                }
+                Err(e) => {
+                    this.machine = None;
+                    return Poll::Ready(Err(e));
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct JoinState<T> {
+    result: Option<T>,
+    waker: Option<Waker>,
+}
+
+/// A handle to a task spawned on an [`Executor`].
+///
+/// Awaiting the handle from another task waits for the task to complete.
+/// Outside of the executor, [`JoinHandle::take`] takes the result once
+/// [`Executor::run`] has returned.
+#[derive(Debug)]
+pub struct JoinHandle<T>(Rc<RefCell<JoinState<T>>>);
+
+impl<T> JoinHandle<T> {
+    /// Take the result of the task, if it completed.
+    pub fn take(&self) -> Option<T> {
+        self.0.borrow_mut().result.take()
+    }
+}
+
+impl<T> Future for JoinHandle<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let mut state = self.0.borrow_mut();
+
+        match state.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+struct TaskWaker {
+    task: usize,
+    ready: Arc<Mutex<VecDeque<usize>>>,
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.ready.lock().unwrap().push_back(self.task);
+    }
+}
+
+type Task = Pin<Box<dyn Future<Output = ()>>>;
+
+/// A minimal single-threaded executor.
+///
+/// Tasks are polled in the order in which they are woken. There is no I/O
+/// or timer support: a task can only be woken by another task, for example
+/// by sending to a [`channel`].
+#[derive(Default)]
+pub struct Executor {
+    tasks: Vec<Option<Task>>,
+    ready: Arc<Mutex<VecDeque<usize>>>,
+}
+
+impl Executor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `future` as a new task. It does not run until [`Executor::run`]
+    /// is called.
+    pub fn spawn<T: 'static>(
+        &mut self,
+        future: impl Future<Output = T> + 'static,
+    ) -> JoinHandle<T> {
+        let state = Rc::new(RefCell::new(JoinState {
+            result: None,
+            waker: None,
+        }));
+
+        let handle = JoinHandle(state.clone());
+        let task = async move {
+            let result = future.await;
+            let mut state = state.borrow_mut();
+            state.result = Some(result);
+
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        };
+
+        self.ready.lock().unwrap().push_back(self.tasks.len());
+        self.tasks.push(Some(Box::pin(task)));
+        handle
+    }
+
+    /// Poll tasks until all of them have completed, or none of them can make
+    /// progress.
+    ///
+    /// Returns the number of tasks that have not completed, which are
+    /// waiting on something that will never happen.
+    pub fn run(&mut self) -> usize {
+        loop {
+            let next = self.ready.lock().unwrap().pop_front();
+            let Some(idx) = next else {
+                break;
+            };
+
+            let Some(task) = self.tasks[idx].as_mut() else {
+                continue;
+            };
+
+            let waker = Waker::from(Arc::new(TaskWaker {
+                task: idx,
+                ready: self.ready.clone(),
+            }));
+
+            if task
+                .as_mut()
+                .poll(&mut Context::from_waker(&waker))
+                .is_ready()
+            {
+                self.tasks[idx] = None;
+            }
+        }
+
+        self.tasks.iter().filter(|t| t.is_some()).count()
+    }
+}
+
+impl std::fmt::Debug for Executor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Executor")
+            .field("tasks", &self.tasks.len())
+            .finish()
+    }
+}
+
+/// Run `future` to completion on a fresh [`Executor`].
+///
+/// Returns `None` if the future can never complete.
+pub fn block_on<T: 'static>(future: impl Future<Output = T> + 'static) -> Option<T> {
+    let mut executor = Executor::new();
+    let handle = executor.spawn(future);
+    executor.run();
+    handle.take()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::{Network, NetworkStop, Topology};
+
+    /// The feedback loop example of 2019 day 7.
+    const FEEDBACK: [isize; 29] = [
+        3, 26, 1001, 26, -4, 26, 3, 27, 1002, 27, 2, 27, 1, 27, 26, 27, 4, 27, 1001, 28, -1, 28,
+        1005, 28, 6, 99, 0, 0, 5,
+    ];
+    const SETTINGS: [isize; 5] = [9, 8, 7, 6, 5];
+
+    /// Reads a value, outputs it doubled, and starts over.
+    const DOUBLER: [isize; 12] = [3, 11, 1002, 11, 2, 11, 4, 11, 1105, 1, 0, 0];
+
+    /// Outputs 1 a hundred thousand times without ever reading input.
+    const CHATTER: [isize; 11] = [104, 1, 1001, 10, -1, 10, 1005, 10, 0, 99, 100_000];
+
+    #[test]
+    fn feedback_ring() {
+        let amplifiers = SETTINGS
+            .iter()
+            .map(|setting| ProgramState::new(*setting, &FEEDBACK))
+            .collect();
+        let mut network = Network::new(amplifiers, Topology::Ring);
+        network.send(0, 0);
+        assert_eq!(network.run(), Ok(NetworkStop::Halted));
+        let expected = *network.outputs().last().unwrap();
+
+        let mut executor = Executor::new();
+        let (first, mut input) = channel();
+        let (to_host, mut from_ring) = channel();
+
+        for (idx, setting) in SETTINGS.iter().enumerate() {
+            let machine = ProgramState::new(*setting, &FEEDBACK);
+            let (output, next) = if idx + 1 < SETTINGS.len() {
+                channel()
+            } else {
+                (to_host.clone(), channel().1)
+            };
+            executor.spawn(MachineFuture::new(machine, input, output));
+            input = next;
+        }
+        drop(to_host);
+
+        // The host closes the ring, and remembers the last value that went
+        // around it.
+        first.send(0);
+        let host = executor.spawn(async move {
+            let mut last = None;
+            while let Some(value) = from_ring.recv().await {
+                first.send(value);
+                last = Some(value);
+            }
+            last
+        });
+
+        assert_eq!(executor.run(), 0);
+        assert_eq!(host.take(), Some(Some(expected)));
+        assert_eq!(expected, 139629729);
+    }
+
+    #[test]
+    fn missing_input() {
+        let (sender, input) = channel();
+        let (output, mut outputs) = channel();
+        sender.send(5);
+        drop(sender);
+
+        let machine = MachineFuture::new(ProgramState::new_empty(&DOUBLER), input, output);
+
+        // The first value is doubled, then the program waits for the next.
+        assert_eq!(
+            block_on(machine).unwrap().map(|_| ()),
+            Err(IntcodeError::MissingInput { ip: 0 })
+        );
+        assert_eq!(outputs.try_recv(), Some(10));
+        assert_eq!(outputs.try_recv(), None);
+    }
+
+    #[test]
+    fn waits_for_input() {
+        let (_sender, input) = channel();
+        let (output, _outputs) = channel();
+        let machine = MachineFuture::new(ProgramState::new_empty(&DOUBLER), input, output);
+
+        let mut executor = Executor::new();
+        let handle = executor.spawn(machine);
+
+        assert_eq!(executor.run(), 1);
+        assert!(handle.take().is_none());
+    }
+
+    #[test]
+    fn wakes_on_output() {
+        let mut executor = Executor::new();
+        let (to_machine, input) = channel();
+        let (output, mut from_machine) = channel();
+
+        // The host waits for every output before sending the next input, so
+        // both tasks depend on being woken by the other.
+        let host = executor.spawn(async move {
+            let mut seen = Vec::new();
+            to_machine.send(1);
+            while seen.len() < 5 {
+                let value = from_machine.recv().await.unwrap();
+                seen.push(value);
+                to_machine.send(value);
+            }
+            seen
+        });
+        let machine = executor.spawn(MachineFuture::new(
+            ProgramState::new_empty(&DOUBLER),
+            input,
+            output,
+        ));
+
+        assert_eq!(executor.run(), 0);
+        assert_eq!(host.take(), Some(vec![2, 4, 8, 16, 32]));
+        // Dropping the host closed the machine's input.
+        assert!(matches!(
+            machine.take(),
+            Some(Err(IntcodeError::MissingInput { ip: 0 }))
+        ));
+    }
+
+    #[test]
+    fn yields_to_other_tasks() {
+        let mut executor = Executor::new();
+
+        let (chatter_out, mut chatter) = channel();
+        let machine = ProgramState::new_empty(&CHATTER);
+        let chatty = executor.spawn(MachineFuture::new(machine, channel().1, chatter_out));
+
+        let (to_doubler, doubler_in) = channel();
+        let (doubler_out, mut doubled) = channel();
+        to_doubler.send(21);
+        drop(to_doubler);
+        let machine = ProgramState::new_empty(&DOUBLER);
+        executor.spawn(MachineFuture::new(machine, doubler_in, doubler_out));
+
+        // Count how much the first machine has output by the time the
+        // second one answers.
+        let host = executor.spawn(async move {
+            let value = doubled.recv().await;
+            let mut chatted = 0;
+            while chatter.try_recv().is_some() {
+                chatted += 1;
+            }
+            (value, chatted)
+        });
+
+        assert_eq!(executor.run(), 0);
+        assert!(chatty.take().unwrap().is_ok());
+
+        let (value, chatted) = host.take().unwrap();
+        assert_eq!(value, Some(42));
+        assert!(chatted < 100_000, "{chatted} values were output first");
+    }
+}
@@ -1,58 +1,223 @@
-use std::{
-    collections::{HashMap, VecDeque},
-    task::Poll,
-};
-
-#[derive(Debug)]
-pub struct ProgramState {
-    inputs: VecDeque<isize>,
-    output: isize,
+use std::{collections::VecDeque, marker::PhantomData, rc::Rc};
+
+use memory::{GrowableMemory, MemoryBackend};
+use opcodes::{Action, Context, Opcodes};
+use profile::Profile;
+pub use program::{Program, ProgramError};
+use word::Word;
+
+pub mod analysis;
+pub mod ascii;
+pub mod asm;
+mod bigint;
+pub mod debug;
+pub mod disasm;
+pub mod future;
+pub mod memory;
+pub mod network;
+pub mod opcodes;
+pub mod profile;
+pub mod program;
+pub mod snapshot;
+pub mod symbolic;
+pub mod word;
+
+/// An error that stops an Intcode program, carrying the offending value as
+/// a word of type `W`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum IntcodeError<W = isize> {
+    /// The value at `ip` is not a known opcode.
+    UnknownOpcode { ip: usize, opcode: W },
+    /// The instruction at `ip` has a parameter mode digit other than 0, 1 or 2.
+    InvalidParamMode { ip: usize, mode: isize },
+    /// The instruction at `ip` tries to write to an immediate-mode parameter.
+    ImmediateWrite { ip: usize },
+    /// The instruction at `ip` addresses a negative memory location.
+    NegativeAddress { ip: usize, address: W },
+    /// The instruction at `ip` addresses a memory location that does not fit
+    /// in a `usize`.
+    AddressOutOfRange { ip: usize, address: W },
+    /// Execution jumped to an invalid address or ran past the end of the program.
+    IpOutOfRange { ip: W },
+    /// The program asked for input while none was left to run it to exit.
+    MissingInput { ip: usize },
+    /// The instruction at `ip` overflowed while running in checked mode.
+    Overflow { ip: usize },
+}
+
+impl<W: Word> std::fmt::Display for IntcodeError<W> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownOpcode { ip, opcode } => write!(f, "unknown opcode {opcode} at ip {ip}"),
+            Self::InvalidParamMode { ip, mode } => {
+                write!(f, "invalid parameter mode {mode} at ip {ip}")
+            }
+            Self::ImmediateWrite { ip } => write!(f, "immediate-mode write at ip {ip}"),
+            Self::NegativeAddress { ip, address } => {
+                write!(f, "negative address {address} at ip {ip}")
+            }
+            Self::AddressOutOfRange { ip, address } => {
+                write!(f, "address {address} out of range at ip {ip}")
+            }
+            Self::IpOutOfRange { ip } => write!(f, "instruction pointer {ip} is out of range"),
+            Self::MissingInput { ip } => write!(f, "program needs more input at ip {ip}"),
+            Self::Overflow { ip } => write!(f, "arithmetic overflow at ip {ip}"),
+        }
+    }
+}
+
+impl<W: Word> std::error::Error for IntcodeError<W> {}
+
+/// The reason a [`ProgramState`] stopped executing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RunState<W = isize> {
+    /// The program wants to read input, but the input queue is empty.
+    NeedsInput,
+    /// The program produced a value.
+    Output(W),
+    /// The program executed a halt instruction.
+    Halted,
+}
+
+/// The reason [`ProgramState::poll_with_fuel`] or
+/// [`ProgramState::run_with_fuel`] stopped.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FuelState<W = isize> {
+    /// The program stopped before the budget ran out, as it would have in
+    /// [`ProgramState::poll`] or [`ProgramState::run`].
+    Ran(RunState<W>),
+    /// The instruction budget ran out.
+    OutOfFuel,
+}
+
+/// How a [`ProgramState`] decodes the instructions it executes.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Interpreter {
+    /// Decode every instruction each time it is executed.
+    #[default]
+    Decoding,
+    /// Keep the decoded instruction at every address of the program, and
+    /// only decode it again after one of the cells it occupies is written.
+    Cached,
+}
+
+/// A running Intcode program, computing with words of type `W`.
+///
+/// The memory backend `M` can be swapped out, see [`memory`].
+///
+/// By default, arithmetic wraps on overflow. In checked mode, see
+/// [`ProgramState::set_checked`], overflow is reported as
+/// [`IntcodeError::Overflow`] instead.
+///
+/// Profiling can be enabled with [`ProgramState::set_profiling`], and the
+/// [`Interpreter`] can be selected with [`ProgramState::set_interpreter`].
+///
+/// The instructions it understands are given by its [`Opcodes`] table,
+/// which can be extended with [`ProgramState::register_opcode`].
+#[derive(Debug, Clone)]
+pub struct ProgramState<W: Word = isize, M: MemoryBackend<W> = GrowableMemory<W>> {
+    inputs: VecDeque<W>,
+    outputs: VecDeque<W>,
     ip: usize,
-    memory: Memory,
+    instructions: u64,
+    checked: bool,
+    profile: Option<Box<Profile>>,
+    opcodes: Rc<Opcodes<W, M>>,
+    memory: Memory<W, M>,
 }
 
-#[derive(Debug)]
-struct Memory {
-    program: Vec<isize>,
-    extra_memory: HashMap<usize, isize>,
-    relative_base: isize,
+#[derive(Debug, Clone)]
+struct Memory<W, M> {
+    cells: M,
+    relative_base: W,
+    /// Decoded instructions by address, for [`Interpreter::Cached`].
+    decoded: Option<Vec<Option<Decoded<W>>>>,
 }
 
-impl Memory {
-    fn addr(&self, mode: ParamMode, value: isize) -> Option<usize> {
-        match mode {
-            ParamMode::Position => Some(value as _),
-            ParamMode::Immediate => None,
-            ParamMode::Relative => Some((self.relative_base + value) as _),
-        }
+/// An instruction with its parameter modes and raw parameter values, which
+/// only depends on the memory cells it occupies and the opcodes.
+#[derive(Debug, Clone)]
+struct Decoded<W> {
+    code: u8,
+    params: [(ParamMode, W); opcodes::MAX_ARITY],
+}
+
+impl<W: Word> Decoded<W> {
+    fn decode<M: MemoryBackend<W>>(
+        ip: usize,
+        cells: &M,
+        opcodes: &Opcodes<W, M>,
+    ) -> Result<Self, IntcodeError<W>> {
+        let op_in = cells.load(ip);
+        let code = opcodes.decode(ip, &op_in)?;
+        let arity = opcodes.get(code).unwrap().arity;
+        let mut modes = param_modes(ip, op_in.to_isize().unwrap() as usize);
+
+        let mut param = |idx: usize| -> Result<(ParamMode, W), IntcodeError<W>> {
+            if idx < arity {
+                Ok((modes.next().unwrap()?, cells.load(ip + 1 + idx)))
+            } else {
+                Ok((ParamMode::Position, W::from_isize(0)))
+            }
+        };
+
+        Ok(Self {
+            code,
+            params: [param(0)?, param(1)?, param(2)?],
+        })
     }
+}
 
-    pub fn get(&self, mode: ParamMode, value: isize) -> isize {
-        let address = if let Some(addr) = self.addr(mode, value) {
-            addr
-        } else {
-            return value;
+impl<W: Word, M: MemoryBackend<W>> Memory<W, M> {
+    fn addr(
+        &self,
+        ip: usize,
+        mode: ParamMode,
+        value: &W,
+    ) -> Result<Option<usize>, IntcodeError<W>> {
+        let relative;
+        let address = match mode {
+            ParamMode::Position => value,
+            ParamMode::Immediate => return Ok(None),
+            ParamMode::Relative => {
+                relative = self.relative_base.wrapping_add(value);
+                &relative
+            }
         };
 
-        if let Some(value) = self.program.get(address) {
-            *value
-        } else {
-            self.extra_memory.get(&address).cloned().unwrap_or(0)
-        }
+        to_address(address).map(Some).map_err(|negative| {
+            let address = address.clone();
+            if negative {
+                IntcodeError::NegativeAddress { ip, address }
+            } else {
+                IntcodeError::AddressOutOfRange { ip, address }
+            }
+        })
+    }
+
+    fn load(&self, address: usize) -> W {
+        self.cells.load(address)
     }
 
-    pub fn set(&mut self, mode: ParamMode, address: isize, value: isize) {
-        let address = self
-            .addr(mode, address)
-            .expect("Cannot make immediate writes");
+    fn store(&mut self, address: usize, value: W) {
+        self.cells.store(address, value);
 
-        let dest = if let Some(destination) = self.program.get_mut(address) {
-            destination
-        } else {
-            self.extra_memory.entry(address).or_insert(0)
-        };
+        // Forget every cached instruction that covers `address`.
+        if let Some(cache) = &mut self.decoded {
+            let start = address.saturating_sub(opcodes::MAX_ARITY);
+            for entry in cache.iter_mut().take(address + 1).skip(start) {
+                *entry = None;
+            }
+        }
+    }
+}
 
-        *dest = value;
+/// Convert `value` to a memory address. On failure, returns whether `value`
+/// was negative.
+fn to_address<W: Word>(value: &W) -> Result<usize, bool> {
+    match value.to_isize() {
+        Some(value) => usize::try_from(value).map_err(|_| true),
+        None => Err(*value < W::from_isize(0)),
     }
 }
 
@@ -65,20 +230,104 @@ impl ProgramState {
         Self::new_multi_input(Vec::new(), program.to_vec())
     }
 
-    pub fn new_multi_input(inputs: Vec<isize>, program: Vec<isize>) -> Self {
+    pub fn new_multi_input(inputs: Vec<isize>, program: impl Into<Vec<isize>>) -> Self {
+        Self::with_memory(inputs, program)
+    }
+}
+
+impl<W: Word, M: MemoryBackend<W>> ProgramState<W, M> {
+    /// Like [`ProgramState::new_multi_input`], using word type `W` and memory
+    /// backend `M`.
+    pub fn with_memory(inputs: Vec<W>, program: impl Into<Vec<W>>) -> Self {
         Self {
             inputs: inputs.into_iter().collect(),
-            output: 0,
+            outputs: VecDeque::new(),
             ip: 0,
+            instructions: 0,
+            checked: false,
+            profile: None,
+            opcodes: Rc::default(),
             memory: Memory {
-                program: program.to_vec(),
-                extra_memory: HashMap::new(),
-                relative_base: 0,
+                cells: M::from_program(program.into()),
+                relative_base: W::from_isize(0),
+                decoded: None,
             },
         }
     }
 
-    pub fn add_input(&mut self, input: isize) {
+    /// Report arithmetic overflow as [`IntcodeError::Overflow`] instead of
+    /// wrapping around.
+    pub fn set_checked(&mut self, checked: bool) {
+        self.checked = checked;
+    }
+
+    pub fn is_checked(&self) -> bool {
+        self.checked
+    }
+
+    /// Select how instructions are decoded, see [`Interpreter`].
+    pub fn set_interpreter(&mut self, interpreter: Interpreter) {
+        self.memory.decoded = match interpreter {
+            Interpreter::Decoding => None,
+            Interpreter::Cached => Some(vec![None; self.memory.cells.program().len()]),
+        };
+    }
+
+    pub fn interpreter(&self) -> Interpreter {
+        if self.memory.decoded.is_some() {
+            Interpreter::Cached
+        } else {
+            Interpreter::Decoding
+        }
+    }
+
+    /// Start or stop recording a [`Profile`]. Enabling profiling while it
+    /// is already enabled keeps the current profile.
+    pub fn set_profiling(&mut self, enabled: bool) {
+        match (enabled, &self.profile) {
+            (true, None) => self.profile = Some(Box::default()),
+            (false, _) => self.profile = None,
+            _ => {}
+        }
+    }
+
+    /// The profile recorded so far, if profiling is enabled.
+    pub fn profile(&self) -> Option<&Profile> {
+        self.profile.as_deref()
+    }
+
+    /// The opcodes this program executes.
+    pub fn opcodes(&self) -> &Opcodes<W, M> {
+        &self.opcodes
+    }
+
+    /// Replace the opcodes this program executes.
+    pub fn set_opcodes(&mut self, opcodes: Opcodes<W, M>) {
+        self.opcodes = Rc::new(opcodes);
+        self.forget_decoded();
+    }
+
+    /// Add opcode `code`, or replace the opcode with that number, see
+    /// [`Opcodes::register`].
+    pub fn register_opcode(
+        &mut self,
+        code: u8,
+        mnemonic: &'static str,
+        arity: usize,
+        handler: impl Fn(&mut Context<'_, W, M>) -> Result<Action<W>, IntcodeError<W>> + 'static,
+    ) {
+        Rc::make_mut(&mut self.opcodes).register(code, mnemonic, arity, handler);
+        self.forget_decoded();
+    }
+
+    /// Clear the instruction cache, for when the opcodes change.
+    fn forget_decoded(&mut self) {
+        if let Some(cache) = &mut self.memory.decoded {
+            cache.fill(None);
+        }
+    }
+
+    pub fn add_input(&mut self, input: W) {
         self.inputs.push_back(input);
     }
 
@@ -86,140 +335,355 @@ impl ProgramState {
         self.inputs.len()
     }
 
-    pub fn poll(&mut self) -> std::task::Poll<()> {
-        loop {
-            let program = &mut self.memory;
+    /// Execute a single instruction.
+    ///
+    /// Returns `None` if execution can simply continue. An instruction that
+    /// needs input while none is available, or a halt instruction, is not
+    /// executed and leaves `ip` unchanged.
+    pub fn step(&mut self) -> Result<Option<RunState<W>>, IntcodeError<W>> {
+        let memory = &mut self.memory;
+        let opcodes = &*self.opcodes;
+
+        let ip = self.ip;
+
+        if ip >= memory.cells.program().len() {
+            return Err(IntcodeError::IpOutOfRange {
+                ip: W::from_isize(ip as isize),
+            });
+        }
 
-            let ip = self.ip;
+        // An instruction that fails to decode is executed without the cache,
+        // so its error is the same one the decoding interpreter reports.
+        let cached = match &mut memory.decoded {
+            Some(cache) => match &cache[ip] {
+                Some(instruction) => Some(instruction.clone()),
+                None => Decoded::decode(ip, &memory.cells, opcodes)
+                    .ok()
+                    .inspect(|instruction| {
+                        cache[ip] = Some(instruction.clone());
+                    }),
+            },
+            None => None,
+        };
+
+        // Without a cached instruction, parameter modes and values are
+        // decoded as they are used.
+        let (code, modes, params) = match &cached {
+            Some(instruction) => (instruction.code, 0, Some(&instruction.params)),
+            None => {
+                let op_in = memory.load(ip);
+                let code = opcodes.decode(ip, &op_in)?;
+                (code, op_in.to_isize().unwrap() as usize / 100, None)
+            }
+        };
 
-            let op_in = program.get(ParamMode::Position, ip as isize);
+        let definition = opcodes.get(code).unwrap();
+        let mut context = Context {
+            ip,
+            modes,
+            params,
+            memory,
+            inputs: &mut self.inputs,
+            profile: self.profile.as_deref_mut(),
+            checked: self.checked,
+        };
 
-            let op = op_in % 100;
-            let mut modes = param_modes(op_in as usize);
+        let size = 1 + definition.arity;
+        let action = match definition.builtin {
+            Some(op) => opcodes::execute(op, &mut context)?,
+            None => (definition.handler)(&mut context)?,
+        };
 
-            macro_rules! get {
-                ($ip_offset:literal) => {{
-                    let prm = modes.next().unwrap();
-                    let address_val = program.get(ParamMode::Position, (ip + $ip_offset) as _);
-                    program.get(prm, address_val)
-                }};
+        let (next, output) = match action {
+            Action::Next => (ip + size, None),
+            Action::Jump(target) => (target, None),
+            Action::Output(value) => (ip + size, Some(value)),
+            Action::NeedsInput => return Ok(Some(RunState::NeedsInput)),
+            Action::Halt => {
+                if let Some(profile) = &mut self.profile {
+                    profile.record_halt(ip);
+                }
+                return Ok(Some(RunState::Halted));
             }
+        };
 
-            macro_rules! set {
-                ($ip_offset:literal, $value:expr) => {{
-                    let prm = modes.next().unwrap();
-                    let address_val = program.get(ParamMode::Position, (ip + $ip_offset) as _);
-                    program.set(prm, address_val, $value);
-                }};
+        self.ip = next;
+        self.instructions += 1;
+        if let Some(profile) = &mut self.profile {
+            profile.record_instruction(ip, definition.mnemonic, definition.arity);
+        }
+        Ok(output.map(RunState::Output))
+    }
+
+    /// Run the program until it produces an output, needs input or halts.
+    ///
+    /// Values returned through [`RunState::Output`] are handed to the caller
+    /// directly and are not added to the output queue.
+    pub fn poll(&mut self) -> Result<RunState<W>, IntcodeError<W>> {
+        loop {
+            if let Some(state) = self.step()? {
+                break Ok(state);
             }
+        }
+    }
 
-            let size = match op {
-                1 => {
-                    let v1 = get!(1);
-                    let v2 = get!(2);
+    /// Run the program until it needs input or halts, collecting all
+    /// outputs in the output queue.
+    ///
+    /// Never returns [`RunState::Output`].
+    pub fn run(&mut self) -> Result<RunState<W>, IntcodeError<W>> {
+        loop {
+            match self.poll()? {
+                RunState::Output(value) => self.outputs.push_back(value),
+                state => break Ok(state),
+            }
+        }
+    }
 
-                    set!(3, v1 + v2);
-                    4
-                }
-                2 => {
-                    let v1 = get!(1);
-                    let v2 = get!(2);
-                    set!(3, v1 * v2);
-                    4
-                }
-                3 => {
-                    let input = self.inputs.pop_front();
-
-                    if let Some(input) = input {
-                        set!(1, input);
-                        2
-                    } else {
-                        break Poll::Pending;
-                    }
-                }
-                4 => {
-                    self.output = get!(1);
-                    2
-                }
-                5 | 6 => {
-                    let should_be_zero = op == 6;
-
-                    let is_zero = get!(1) == 0;
-                    let branch = !(should_be_zero ^ is_zero);
-
-                    if branch {
-                        self.ip = get!(2) as usize;
-                        0
-                    } else {
-                        3
-                    }
-                }
-                7 | 8 => {
-                    let check: fn(&isize, &isize) -> bool = if op == 7 {
-                        isize::lt as _
-                    } else {
-                        isize::eq as _
-                    };
-
-                    let v1 = get!(1);
-                    let v2 = get!(2);
-
-                    set!(3, check(&v1, &v2) as _);
-                    4
-                }
-                9 => {
-                    let rb_offset = get!(1);
-                    program.relative_base += rb_offset;
-                    2
-                }
-                99 => {
-                    break Poll::Ready(());
-                }
-                _ => todo!(),
-            };
+    /// Like [`ProgramState::poll`], but execute at most `fuel` instructions.
+    ///
+    /// Returns [`FuelState::OutOfFuel`] if the budget runs out first. Halting
+    /// or waiting for input does not take any fuel, so those are reported
+    /// even when the budget is exactly used up. Execution can be resumed by
+    /// calling this again.
+    pub fn poll_with_fuel(&mut self, fuel: u64) -> Result<FuelState<W>, IntcodeError<W>> {
+        let limit = self.instructions.saturating_add(fuel);
+
+        while self.instructions < limit {
+            if let Some(state) = self.step()? {
+                return Ok(FuelState::Ran(state));
+            }
+        }
 
-            self.ip += size;
+        // Only the built-in halt and input instructions are known not to
+        // change anything when they stop the program.
+        let op_in = self.memory.load(self.ip);
+        let builtin = self
+            .opcodes
+            .decode(self.ip, &op_in)
+            .ok()
+            .and_then(|code| self.opcodes.get(code)?.builtin);
+
+        match builtin {
+            Some(Opcode::Halt) => Ok(FuelState::Ran(RunState::Halted)),
+            Some(Opcode::Input) if self.inputs.is_empty() => {
+                Ok(FuelState::Ran(RunState::NeedsInput))
+            }
+            _ => Ok(FuelState::OutOfFuel),
         }
     }
 
-    pub fn run_to_exit(&mut self) {
+    /// Like [`ProgramState::run`], but execute at most `fuel` instructions.
+    ///
+    /// Returns [`FuelState::OutOfFuel`] if the budget runs out before the
+    /// program needs input or halts.
+    pub fn run_with_fuel(&mut self, fuel: u64) -> Result<FuelState<W>, IntcodeError<W>> {
+        let limit = self.instructions.saturating_add(fuel);
+
+        loop {
+            match self.poll_with_fuel(limit - self.instructions)? {
+                FuelState::Ran(RunState::Output(value)) => self.outputs.push_back(value),
+                state => break Ok(state),
+            }
+        }
+    }
+
+    pub fn run_to_exit(&mut self) -> Result<(), IntcodeError<W>> {
         self.ip = 0;
 
-        let run_once = self.poll();
-        assert!(run_once.is_ready());
+        match self.run()? {
+            RunState::Halted => Ok(()),
+            _ => Err(IntcodeError::MissingInput { ip: self.ip }),
+        }
+    }
+
+    /// The memory cells that held the program when it was loaded.
+    pub fn program(&self) -> &[W] {
+        self.memory.cells.program()
+    }
+
+    /// The number of instructions executed so far.
+    pub fn instructions(&self) -> u64 {
+        self.instructions
+    }
+
+    pub fn ip(&self) -> usize {
+        self.ip
+    }
+
+    pub fn relative_base(&self) -> &W {
+        &self.memory.relative_base
+    }
+
+    /// Read the value at `address`, including addresses past the end of the
+    /// program.
+    pub fn read(&self, address: usize) -> W {
+        self.memory.load(address)
+    }
+
+    /// Overwrite the value at `address`, including addresses past the end of
+    /// the program.
+    pub fn write(&mut self, address: usize, value: W) {
+        self.memory.store(address, value);
     }
 
-    pub fn program(&self) -> &[isize] {
-        &self.memory.program
+    /// Take the oldest value from the output queue.
+    pub fn take_output(&mut self) -> Option<W> {
+        self.outputs.pop_front()
     }
 
-    pub fn output(&self) -> isize {
-        self.output
+    /// Take all values from the output queue, oldest first.
+    pub fn drain_outputs(&mut self) -> impl Iterator<Item = W> + '_ {
+        self.outputs.drain(..)
+    }
+
+    pub fn output_len(&self) -> usize {
+        self.outputs.len()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Opcode {
+    Add,
+    Mul,
+    Input,
+    Output,
+    JumpIfTrue,
+    JumpIfFalse,
+    LessThan,
+    Equals,
+    AdjustRelativeBase,
+    Halt,
+}
+
+impl Opcode {
+    pub const ALL: [Opcode; 10] = [
+        Self::Add,
+        Self::Mul,
+        Self::Input,
+        Self::Output,
+        Self::JumpIfTrue,
+        Self::JumpIfFalse,
+        Self::LessThan,
+        Self::Equals,
+        Self::AdjustRelativeBase,
+        Self::Halt,
+    ];
+
+    /// Decode the opcode of the instruction `op_in` found at `ip`,
+    /// ignoring its parameter modes.
+    pub fn decode<W: Word>(ip: usize, op_in: &W) -> Result<Self, IntcodeError<W>> {
+        let opcode = match op_in.to_isize() {
+            Some(op_in) if op_in >= 0 => Self::ALL.into_iter().find(|op| op.code() == op_in % 100),
+            _ => None,
+        };
+
+        opcode.ok_or_else(|| IntcodeError::UnknownOpcode {
+            ip,
+            opcode: op_in.clone(),
+        })
+    }
+
+    pub fn code(&self) -> isize {
+        match self {
+            Self::Add => 1,
+            Self::Mul => 2,
+            Self::Input => 3,
+            Self::Output => 4,
+            Self::JumpIfTrue => 5,
+            Self::JumpIfFalse => 6,
+            Self::LessThan => 7,
+            Self::Equals => 8,
+            Self::AdjustRelativeBase => 9,
+            Self::Halt => 99,
+        }
+    }
+
+    /// The number of parameters this instruction takes.
+    pub fn arity(&self) -> usize {
+        match self {
+            Self::Add | Self::Mul | Self::LessThan | Self::Equals => 3,
+            Self::JumpIfTrue | Self::JumpIfFalse => 2,
+            Self::Input | Self::Output | Self::AdjustRelativeBase => 1,
+            Self::Halt => 0,
+        }
+    }
+
+    /// The index of the parameter this instruction writes to, if any.
+    pub fn write_param(&self) -> Option<usize> {
+        match self {
+            Self::Add | Self::Mul | Self::LessThan | Self::Equals => Some(2),
+            Self::Input => Some(0),
+            _ => None,
+        }
+    }
+
+    pub fn mnemonic(&self) -> &'static str {
+        match self {
+            Self::Add => "ADD",
+            Self::Mul => "MUL",
+            Self::Input => "IN",
+            Self::Output => "OUT",
+            Self::JumpIfTrue => "JNZ",
+            Self::JumpIfFalse => "JZ",
+            Self::LessThan => "LT",
+            Self::Equals => "EQ",
+            Self::AdjustRelativeBase => "ARB",
+            Self::Halt => "HLT",
+        }
     }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
-enum ParamMode {
+pub enum ParamMode {
     Position,
     Immediate,
     Relative,
 }
 
-fn param_modes(op: usize) -> impl Iterator<Item = ParamMode> + Clone {
-    #[derive(Clone)]
-    struct Iter {
+impl ParamMode {
+    /// The digit that selects this mode in an instruction.
+    pub fn digit(&self) -> isize {
+        match self {
+            Self::Position => 0,
+            Self::Immediate => 1,
+            Self::Relative => 2,
+        }
+    }
+}
+
+pub(crate) fn param_modes<W>(
+    ip: usize,
+    op: usize,
+) -> impl Iterator<Item = Result<ParamMode, IntcodeError<W>>> + Clone {
+    struct Iter<W> {
+        ip: usize,
         value: usize,
+        _word: PhantomData<W>,
+    }
+
+    impl<W> Clone for Iter<W> {
+        fn clone(&self) -> Self {
+            Self {
+                ip: self.ip,
+                value: self.value,
+                _word: PhantomData,
+            }
+        }
     }
 
-    impl Iterator for Iter {
-        type Item = ParamMode;
+    impl<W> Iterator for Iter<W> {
+        type Item = Result<ParamMode, IntcodeError<W>>;
 
         fn next(&mut self) -> Option<Self::Item> {
             let mode = match self.value % 10 {
-                2 => ParamMode::Relative,
-                1 => ParamMode::Immediate,
-                0 => ParamMode::Position,
-                _ => panic!(),
+                2 => Ok(ParamMode::Relative),
+                1 => Ok(ParamMode::Immediate),
+                0 => Ok(ParamMode::Position),
+                mode => Err(IntcodeError::InvalidParamMode {
+                    ip: self.ip,
+                    mode: mode as isize,
+                }),
             };
 
             self.value /= 10;
@@ -228,5 +692,83 @@ fn param_modes(op: usize) -> impl Iterator<Item = ParamMode> + Clone {
         }
     }
 
-    Iter { value: op / 100 }
+    Iter {
+        ip,
+        value: op / 100,
+        _word: PhantomData,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Jumps back to itself forever.
+    const SPIN: [isize; 3] = [1105, 1, 0];
+
+    /// Outputs 1 and then 2, then halts.
+    const ONE_TWO: [isize; 5] = [104, 1, 104, 2, 99];
+
+    /// Outputs 1, then reads a value into cell 0 and halts.
+    const ONE_READ: [isize; 5] = [104, 1, 3, 0, 99];
+
+    #[test]
+    fn out_of_fuel() {
+        let mut state = ProgramState::new_empty(&SPIN);
+        assert_eq!(state.poll_with_fuel(10), Ok(FuelState::OutOfFuel));
+        assert_eq!(state.instructions(), 10);
+        assert_eq!(state.ip(), 0);
+
+        assert_eq!(state.run_with_fuel(5), Ok(FuelState::OutOfFuel));
+        assert_eq!(state.instructions(), 15);
+    }
+
+    #[test]
+    fn resume_with_more_fuel() {
+        let mut state = ProgramState::new_empty(&ONE_TWO);
+        assert_eq!(state.run_with_fuel(1), Ok(FuelState::OutOfFuel));
+        assert_eq!(state.ip(), 2);
+
+        assert_eq!(
+            state.run_with_fuel(10),
+            Ok(FuelState::Ran(RunState::Halted))
+        );
+        assert_eq!(state.drain_outputs().collect::<Vec<_>>(), [1, 2]);
+        assert_eq!(state.instructions(), 2);
+    }
+
+    #[test]
+    fn stop_reported_when_fuel_runs_out() {
+        let mut state = ProgramState::new_empty(&ONE_TWO);
+        assert_eq!(state.run_with_fuel(2), Ok(FuelState::Ran(RunState::Halted)));
+        assert_eq!(state.drain_outputs().collect::<Vec<_>>(), [1, 2]);
+
+        let mut state = ProgramState::new_empty(&ONE_READ);
+        assert_eq!(
+            state.run_with_fuel(1),
+            Ok(FuelState::Ran(RunState::NeedsInput))
+        );
+        assert_eq!(state.ip(), 2);
+
+        // With input queued the read would still have to run.
+        let mut state = ProgramState::new(5, &ONE_READ);
+        assert_eq!(state.run_with_fuel(1), Ok(FuelState::OutOfFuel));
+        assert_eq!(state.run_with_fuel(1), Ok(FuelState::Ran(RunState::Halted)));
+        assert_eq!(state.read(0), 5);
+    }
+
+    #[test]
+    fn zero_fuel() {
+        let mut state = ProgramState::new_empty(&SPIN);
+        assert_eq!(state.poll_with_fuel(0), Ok(FuelState::OutOfFuel));
+        assert_eq!(state.run_with_fuel(0), Ok(FuelState::OutOfFuel));
+        assert_eq!(state.instructions(), 0);
+        assert_eq!(state.ip(), 0);
+
+        let mut state = ProgramState::new_empty(&[99]);
+        assert_eq!(
+            state.poll_with_fuel(0),
+            Ok(FuelState::Ran(RunState::Halted))
+        );
+    }
 }
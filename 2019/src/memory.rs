@@ -0,0 +1,239 @@
+//! Storage backends for the memory of a [`ProgramState`](crate::ProgramState).
+
+use std::collections::HashMap;
+
+use crate::word::Word;
+
+/// Storage for Intcode memory.
+///
+/// Memory is an infinite tape of cells, all of which are zero unless written
+/// to. The cells starting at address 0 initially hold the loaded program.
+pub trait MemoryBackend<W: Word>: Clone + std::fmt::Debug {
+    fn from_program(program: Vec<W>) -> Self;
+
+    fn load(&self, address: usize) -> W;
+
+    fn store(&mut self, address: usize, value: W);
+
+    /// The cells that held the program when it was loaded.
+    fn program(&self) -> &[W];
+
+    /// All non-zero cells past the end of the program, in no particular
+    /// order.
+    fn extra_cells(&self) -> Vec<(usize, W)>;
+}
+
+/// The program in a `Vec`, and every cell past its end in a `HashMap`.
+#[derive(Debug, Clone)]
+pub struct SparseMemory<W = isize> {
+    program: Vec<W>,
+    extra_memory: HashMap<usize, W>,
+}
+
+impl<W: Word> MemoryBackend<W> for SparseMemory<W> {
+    fn from_program(program: Vec<W>) -> Self {
+        Self {
+            program,
+            extra_memory: HashMap::new(),
+        }
+    }
+
+    fn load(&self, address: usize) -> W {
+        if let Some(value) = self.program.get(address) {
+            value.clone()
+        } else {
+            self.extra_memory
+                .get(&address)
+                .cloned()
+                .unwrap_or_else(|| W::from_isize(0))
+        }
+    }
+
+    fn store(&mut self, address: usize, value: W) {
+        if let Some(destination) = self.program.get_mut(address) {
+            *destination = value;
+        } else {
+            self.extra_memory.insert(address, value);
+        }
+    }
+
+    fn program(&self) -> &[W] {
+        &self.program
+    }
+
+    fn extra_cells(&self) -> Vec<(usize, W)> {
+        self.extra_memory
+            .iter()
+            .filter(|(_, v)| !v.is_zero())
+            .map(|(a, v)| (*a, v.clone()))
+            .collect()
+    }
+}
+
+/// A single `Vec` that grows to cover every written address below
+/// [`GrowableMemory::DENSE_LIMIT`]. Cells at higher addresses fall back to a
+/// `HashMap`, so a stray write to a huge address doesn't allocate gigabytes.
+#[derive(Debug, Clone)]
+pub struct GrowableMemory<W = isize> {
+    cells: Vec<W>,
+    program_len: usize,
+    sparse: HashMap<usize, W>,
+}
+
+impl<W> GrowableMemory<W> {
+    pub const DENSE_LIMIT: usize = 1 << 20;
+}
+
+impl<W: Word> MemoryBackend<W> for GrowableMemory<W> {
+    fn from_program(program: Vec<W>) -> Self {
+        Self {
+            program_len: program.len(),
+            cells: program,
+            sparse: HashMap::new(),
+        }
+    }
+
+    #[inline]
+    fn load(&self, address: usize) -> W {
+        if let Some(value) = self.cells.get(address) {
+            value.clone()
+        } else if address < Self::DENSE_LIMIT {
+            W::from_isize(0)
+        } else {
+            self.sparse
+                .get(&address)
+                .cloned()
+                .unwrap_or_else(|| W::from_isize(0))
+        }
+    }
+
+    #[inline]
+    fn store(&mut self, address: usize, value: W) {
+        if let Some(dest) = self.cells.get_mut(address) {
+            *dest = value;
+        } else if address < Self::DENSE_LIMIT {
+            let new_len = (address + 1)
+                .max(self.cells.len() * 2)
+                .min(Self::DENSE_LIMIT);
+            self.cells.resize(new_len, W::from_isize(0));
+            self.cells[address] = value;
+        } else {
+            self.sparse.insert(address, value);
+        }
+    }
+
+    fn program(&self) -> &[W] {
+        &self.cells[..self.program_len]
+    }
+
+    fn extra_cells(&self) -> Vec<(usize, W)> {
+        let dense = self.cells[self.program_len..]
+            .iter()
+            .enumerate()
+            .map(|(idx, v)| (self.program_len + idx, v));
+        let sparse = self.sparse.iter().map(|(a, v)| (*a, v));
+
+        dense
+            .chain(sparse)
+            .filter(|(_, v)| !v.is_zero())
+            .map(|(a, v)| (a, v.clone()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ProgramState;
+
+    const FAR: usize = GrowableMemory::<isize>::DENSE_LIMIT + 5;
+
+    fn sorted(mut cells: Vec<(usize, isize)>) -> Vec<(usize, isize)> {
+        cells.sort_unstable();
+        cells
+    }
+
+    #[test]
+    fn growable_grows() {
+        let mut memory: GrowableMemory = GrowableMemory::from_program(vec![1, 2, 3]);
+        assert_eq!(memory.load(3), 0);
+        assert_eq!(memory.load(1000), 0);
+        assert_eq!(memory.cells.len(), 3);
+
+        memory.store(10, 7);
+        assert_eq!(memory.cells.len(), 11);
+        assert_eq!(memory.load(10), 7);
+        assert_eq!(memory.load(11), 0);
+
+        // Small writes past the end at least double the storage.
+        memory.store(11, 8);
+        assert_eq!(memory.cells.len(), 22);
+
+        assert_eq!(memory.program(), [1, 2, 3]);
+        assert_eq!(sorted(memory.extra_cells()), [(10, 7), (11, 8)]);
+    }
+
+    #[test]
+    fn growable_far_addresses() {
+        let mut memory: GrowableMemory = GrowableMemory::from_program(vec![99]);
+        memory.store(FAR, 5);
+        assert_eq!(memory.cells.len(), 1);
+        assert_eq!(memory.load(FAR), 5);
+        assert_eq!(memory.load(FAR + 1), 0);
+
+        // Dense storage never grows past the limit.
+        memory.store(GrowableMemory::<isize>::DENSE_LIMIT - 1, 6);
+        assert_eq!(memory.cells.len(), GrowableMemory::<isize>::DENSE_LIMIT);
+        assert_eq!(
+            sorted(memory.extra_cells()),
+            [(GrowableMemory::<isize>::DENSE_LIMIT - 1, 6), (FAR, 5)]
+        );
+    }
+
+    #[test]
+    fn sparse() {
+        let mut memory: SparseMemory = SparseMemory::from_program(vec![1, 2, 3]);
+        assert_eq!(memory.load(3), 0);
+
+        memory.store(1, 5);
+        memory.store(FAR, 6);
+        memory.store(4, 0);
+        assert_eq!(memory.load(FAR), 6);
+        assert_eq!(memory.program(), [1, 5, 3]);
+        assert_eq!(memory.extra_cells(), [(FAR, 6)]);
+    }
+
+    #[test]
+    fn backends_agree() {
+        // The day 9 quine, which prints its first 16 cells, followed by a write
+        // to a far address.
+        let mut program = vec![
+            109, 1, 204, -1, 1001, 100, 1, 100, 1008, 100, 16, 101, 1006, 101, 0,
+        ];
+        program.extend([1101, 1, 2, FAR as isize, 4, FAR as isize, 99]);
+
+        let mut growable =
+            ProgramState::<isize, GrowableMemory>::with_memory(vec![], program.clone());
+        let mut sparse = ProgramState::<isize, SparseMemory>::with_memory(vec![], program.clone());
+        growable.run_to_exit().unwrap();
+        sparse.run_to_exit().unwrap();
+
+        let outputs: Vec<_> = growable.drain_outputs().collect();
+        assert_eq!(outputs[..16], program[..16]);
+        assert_eq!(outputs[outputs.len() - 1], 3);
+        assert_eq!(outputs, sparse.drain_outputs().collect::<Vec<_>>());
+
+        assert_eq!(
+            growable.memory.cells.program(),
+            sparse.memory.cells.program()
+        );
+        assert_eq!(
+            sorted(growable.memory.cells.extra_cells()),
+            sorted(sparse.memory.cells.extra_cells())
+        );
+        assert_eq!(
+            sorted(sparse.memory.cells.extra_cells()),
+            [(100, 16), (101, 1), (FAR, 3)]
+        );
+    }
+}
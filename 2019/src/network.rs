@@ -0,0 +1,347 @@
+//! Several Intcode machines connected to each other.
+
+use std::collections::VecDeque;
+
+use crate::{IntcodeError, ProgramState, RunState};
+
+/// How the machines in a [`Network`] are connected.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Topology {
+    /// Every value output by machine `n` is sent to machine `n + 1`. Values
+    /// output by the last machine leave the network.
+    Pipeline,
+    /// Like [`Topology::Pipeline`], but values output by the last machine are
+    /// also sent back to the first machine.
+    Ring,
+    /// Machines output packets as `address, x, y` triples, which are sent to
+    /// the machine with that address. Every machine receives its own address
+    /// as its first input, and reads `-1` when no packet is waiting.
+    Packet,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Packet {
+    pub address: isize,
+    pub x: isize,
+    pub y: isize,
+}
+
+/// The reason [`Network::run`] stopped.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NetworkStop {
+    /// All machines halted.
+    Halted,
+    /// No machine can make progress: every machine is halted or waiting for
+    /// input, and no values or packets are in flight. In a packet network,
+    /// machines reading `-1` do not count as progress.
+    Idle,
+    /// A packet was sent to an address that is not part of the network.
+    Unroutable(Packet),
+    /// The network was idle, so the NAT sent its last received packet to
+    /// machine 0.
+    NatSent(Packet),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetworkError {
+    pub machine: usize,
+    pub error: IntcodeError,
+}
+
+impl std::fmt::Display for NetworkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "machine {}: {}", self.machine, self.error)
+    }
+}
+
+impl std::error::Error for NetworkError {}
+
+#[derive(Debug, Clone)]
+struct Node {
+    machine: ProgramState,
+    waiting: bool,
+    halted: bool,
+    inbox: VecDeque<Packet>,
+    partial: Vec<isize>,
+}
+
+#[derive(Debug, Clone)]
+struct Nat {
+    address: isize,
+    last: Option<Packet>,
+}
+
+/// A set of Intcode machines, scheduled round-robin.
+///
+/// On its turn, a machine runs until it needs input or halts.
+#[derive(Debug, Clone)]
+pub struct Network {
+    nodes: Vec<Node>,
+    topology: Topology,
+    nat: Option<Nat>,
+    outputs: Vec<isize>,
+    unroutable: VecDeque<Packet>,
+}
+
+impl Network {
+    pub fn new(machines: Vec<ProgramState>, topology: Topology) -> Self {
+        let nodes = machines
+            .into_iter()
+            .enumerate()
+            .map(|(address, mut machine)| {
+                if topology == Topology::Packet {
+                    machine.add_input(address as isize);
+                }
+
+                Node {
+                    machine,
+                    waiting: false,
+                    halted: false,
+                    inbox: VecDeque::new(),
+                    partial: Vec::new(),
+                }
+            })
+            .collect();
+
+        Self {
+            nodes,
+            topology,
+            nat: None,
+            outputs: Vec::new(),
+            unroutable: VecDeque::new(),
+        }
+    }
+
+    /// Add a NAT that monitors packets sent to `address`. Whenever the network
+    /// is idle, the NAT sends the last packet it received to machine 0.
+    ///
+    /// Only meaningful for [`Topology::Packet`].
+    pub fn with_nat(mut self, address: isize) -> Self {
+        self.nat = Some(Nat {
+            address,
+            last: None,
+        });
+        self
+    }
+
+    pub fn machines(&self) -> impl Iterator<Item = &ProgramState> {
+        self.nodes.iter().map(|n| &n.machine)
+    }
+
+    /// Values output by the last machine of a pipeline or ring.
+    pub fn outputs(&self) -> &[isize] {
+        &self.outputs
+    }
+
+    /// Send `value` to `machine` from outside the network.
+    pub fn send(&mut self, machine: usize, value: isize) {
+        self.nodes[machine].machine.add_input(value);
+    }
+
+    /// Run the machines until one of the conditions in [`NetworkStop`] is met.
+    ///
+    /// Calling `run` again after it returned continues where it left off.
+    pub fn run(&mut self) -> Result<NetworkStop, NetworkError> {
+        loop {
+            let mut progressed = false;
+
+            for idx in 0..self.nodes.len() {
+                if let Some(packet) = self.unroutable.pop_front() {
+                    return Ok(NetworkStop::Unroutable(packet));
+                }
+
+                progressed |= self.run_node(idx)?;
+            }
+
+            if let Some(packet) = self.unroutable.pop_front() {
+                return Ok(NetworkStop::Unroutable(packet));
+            }
+
+            if progressed {
+                continue;
+            }
+
+            if self.nodes.iter().all(|n| n.halted) {
+                return Ok(NetworkStop::Halted);
+            }
+
+            let nat_packet = self.nat.as_ref().and_then(|nat| nat.last);
+            if let (Some(packet), Some(node)) = (nat_packet, self.nodes.first_mut()) {
+                node.inbox.push_back(packet);
+                return Ok(NetworkStop::NatSent(packet));
+            }
+
+            return Ok(NetworkStop::Idle);
+        }
+    }
+
+    /// Give machine `idx` its turn. Returns whether it made any progress.
+    fn run_node(&mut self, idx: usize) -> Result<bool, NetworkError> {
+        let node = &mut self.nodes[idx];
+
+        if node.halted {
+            return Ok(false);
+        }
+
+        let mut progressed = !node.waiting;
+
+        if node.waiting {
+            if self.topology == Topology::Packet {
+                if let Some(packet) = node.inbox.pop_front() {
+                    node.machine.add_input(packet.x);
+                    node.machine.add_input(packet.y);
+                    progressed = true;
+                } else {
+                    node.machine.add_input(-1);
+                }
+            } else if node.machine.input_len() == 0 {
+                return Ok(false);
+            } else {
+                progressed = true;
+            }
+        }
+
+        loop {
+            let error = |error| NetworkError {
+                machine: idx,
+                error,
+            };
+
+            match self.nodes[idx].machine.poll().map_err(error)? {
+                RunState::Output(value) => {
+                    progressed = true;
+                    self.route(idx, value);
+                }
+                RunState::NeedsInput => {
+                    self.nodes[idx].waiting = true;
+                    break;
+                }
+                RunState::Halted => {
+                    self.nodes[idx].halted = true;
+                    break;
+                }
+            }
+        }
+
+        Ok(progressed)
+    }
+
+    fn route(&mut self, from: usize, value: isize) {
+        let last = self.nodes.len() - 1;
+
+        match self.topology {
+            Topology::Pipeline | Topology::Ring if from < last => {
+                self.nodes[from + 1].machine.add_input(value);
+            }
+            Topology::Pipeline => self.outputs.push(value),
+            Topology::Ring => {
+                self.outputs.push(value);
+                self.nodes[0].machine.add_input(value);
+            }
+            Topology::Packet => {
+                let partial = &mut self.nodes[from].partial;
+                partial.push(value);
+
+                if let [address, x, y] = partial[..] {
+                    partial.clear();
+                    self.send_packet(Packet { address, x, y });
+                }
+            }
+        }
+    }
+
+    /// Send `packet` to the machine or NAT with its address. Packets that
+    /// can't be delivered are reported by the next call to [`Network::run`].
+    pub fn send_packet(&mut self, packet: Packet) {
+        if let Some(nat) = self
+            .nat
+            .as_mut()
+            .filter(|nat| nat.address == packet.address)
+        {
+            nat.last = Some(packet);
+            return;
+        }
+
+        let node = usize::try_from(packet.address)
+            .ok()
+            .and_then(|address| self.nodes.get_mut(address));
+
+        if let Some(node) = node {
+            node.inbox.push_back(packet);
+        } else {
+            self.unroutable.push_back(packet);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A NIC that sends one packet, then reads input forever.
+    fn sender(address: isize, x: isize, y: isize) -> ProgramState {
+        ProgramState::new_empty(&[3, 100, 104, address, 104, x, 104, y, 3, 100, 1105, 1, 8])
+    }
+
+    /// A NIC that waits for a packet, forwards it to `address` with `x` and
+    /// `y` swapped, and halts.
+    fn forwarder(address: isize) -> ProgramState {
+        ProgramState::new_empty(&[
+            3, 100, 3, 101, 1008, 101, -1, 102, 1005, 102, 2, 3, 103, 104, address, 4, 103, 4, 101,
+            99,
+        ])
+    }
+
+    fn packet(address: isize, x: isize, y: isize) -> Packet {
+        Packet { address, x, y }
+    }
+
+    #[test]
+    fn routing() {
+        let machines = vec![sender(1, 7, 8), forwarder(2), forwarder(99)];
+        let mut network = Network::new(machines, Topology::Packet);
+
+        assert_eq!(network.run(), Ok(NetworkStop::Unroutable(packet(99, 7, 8))));
+        // The sender keeps reading -1, which is not progress.
+        assert_eq!(network.run(), Ok(NetworkStop::Idle));
+        assert_eq!(network.run(), Ok(NetworkStop::Idle));
+
+        network.send_packet(packet(-3, 1, 2));
+        assert_eq!(network.run(), Ok(NetworkStop::Unroutable(packet(-3, 1, 2))));
+    }
+
+    #[test]
+    fn nat() {
+        let machines = vec![forwarder(255), sender(255, 3, 4)];
+        let mut network = Network::new(machines, Topology::Packet).with_nat(255);
+
+        assert_eq!(network.run(), Ok(NetworkStop::NatSent(packet(255, 3, 4))));
+        // Machine 0 woke up and sent the packet back, swapped.
+        assert_eq!(network.run(), Ok(NetworkStop::NatSent(packet(255, 4, 3))));
+    }
+
+    #[test]
+    fn idle_without_nat() {
+        let machines = vec![forwarder(1), forwarder(0)];
+        let mut network = Network::new(machines, Topology::Packet);
+
+        assert_eq!(network.run(), Ok(NetworkStop::Idle));
+
+        network.send_packet(packet(0, 5, 6));
+        assert_eq!(network.run(), Ok(NetworkStop::Halted));
+    }
+
+    #[test]
+    fn errors() {
+        let machines = vec![sender(1, 0, 0), ProgramState::new_empty(&[3, 100, 42])];
+        let mut network = Network::new(machines, Topology::Packet);
+
+        assert_eq!(
+            network.run(),
+            Err(NetworkError {
+                machine: 1,
+                error: IntcodeError::UnknownOpcode { ip: 2, opcode: 42 },
+            })
+        );
+    }
+}
@@ -0,0 +1,401 @@
+//! The opcodes a [`ProgramState`](crate::ProgramState) executes.
+//!
+//! Every opcode, including the ten from the puzzles, is an entry in an
+//! [`Opcodes`] table that gives its mnemonic, its arity and a handler that
+//! executes it. Registering new entries, or replacing built-in ones, turns a
+//! [`ProgramState`](crate::ProgramState) into a variant machine, see
+//! [`ProgramState::register_opcode`](crate::ProgramState::register_opcode).
+//!
+//! The static tools, like the disassembler and [`analysis`](crate::analysis),
+//! only know the built-in opcodes.
+
+use std::{collections::VecDeque, rc::Rc};
+
+use crate::{
+    memory::{GrowableMemory, MemoryBackend},
+    profile::Profile,
+    to_address,
+    word::Word,
+    IntcodeError, Memory, Opcode, ParamMode,
+};
+
+/// The largest number of parameters an instruction can take.
+pub const MAX_ARITY: usize = 3;
+
+/// The number of distinct opcodes: the last two digits of an instruction.
+const OPCODES: usize = 100;
+
+/// Executes a single instruction.
+pub type Handler<W, M> = dyn Fn(&mut Context<'_, W, M>) -> Result<Action<W>, IntcodeError<W>>;
+
+/// What a [`Handler`] did with its instruction.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Action<W = isize> {
+    /// Continue with the instruction after this one.
+    Next,
+    /// Continue at the given address.
+    Jump(usize),
+    /// Produce a value, then continue with the instruction after this one.
+    Output(W),
+    /// Wait for input. The instruction is executed again once input is
+    /// available, so it must not have changed anything.
+    NeedsInput,
+    /// Stop the program. The instruction must not have changed anything.
+    Halt,
+}
+
+/// The machine state a [`Handler`] can access while it executes one
+/// instruction.
+///
+/// Parameters are numbered from 0. Their modes are only decoded when they
+/// are used, so an instruction only fails on an invalid mode if it uses
+/// that parameter.
+pub struct Context<'a, W: Word, M: MemoryBackend<W>> {
+    pub(crate) ip: usize,
+    /// The parameter mode digits of the instruction, if `params` is `None`.
+    pub(crate) modes: usize,
+    /// The already decoded parameters of a cached instruction.
+    pub(crate) params: Option<&'a [(ParamMode, W); MAX_ARITY]>,
+    pub(crate) memory: &'a mut Memory<W, M>,
+    pub(crate) inputs: &'a mut VecDeque<W>,
+    pub(crate) profile: Option<&'a mut Profile>,
+    pub(crate) checked: bool,
+}
+
+impl<W: Word, M: MemoryBackend<W>> Context<'_, W, M> {
+    /// The address of the instruction being executed.
+    pub fn ip(&self) -> usize {
+        self.ip
+    }
+
+    /// Whether overflow should be reported instead of wrapping around, see
+    /// [`ProgramState::set_checked`](crate::ProgramState::set_checked).
+    pub fn is_checked(&self) -> bool {
+        self.checked
+    }
+
+    /// The mode and raw value of parameter `idx`.
+    #[inline(always)]
+    pub fn param(&self, idx: usize) -> Result<(ParamMode, W), IntcodeError<W>> {
+        if let Some(param) = self.params.and_then(|params| params.get(idx)) {
+            return Ok(param.clone());
+        }
+
+        let digit = 10usize
+            .checked_pow(idx as u32)
+            .map_or(0, |scale| self.modes / scale % 10);
+
+        let mode = match digit {
+            0 => ParamMode::Position,
+            1 => ParamMode::Immediate,
+            2 => ParamMode::Relative,
+            mode => {
+                return Err(IntcodeError::InvalidParamMode {
+                    ip: self.ip,
+                    mode: mode as isize,
+                })
+            }
+        };
+
+        Ok((mode, self.memory.load(self.ip + 1 + idx)))
+    }
+
+    /// The value of parameter `idx`.
+    #[inline(always)]
+    pub fn get(&mut self, idx: usize) -> Result<W, IntcodeError<W>> {
+        let (mode, value) = self.param(idx)?;
+
+        match self.memory.addr(self.ip, mode, &value)? {
+            Some(address) => {
+                if let Some(profile) = &mut self.profile {
+                    profile.record_read(address);
+                }
+                Ok(self.memory.load(address))
+            }
+            None => Ok(value),
+        }
+    }
+
+    /// Write `value` to the address parameter `idx` refers to.
+    #[inline(always)]
+    pub fn set(&mut self, idx: usize, value: W) -> Result<(), IntcodeError<W>> {
+        let (mode, address) = self.param(idx)?;
+        let address = self
+            .memory
+            .addr(self.ip, mode, &address)?
+            .ok_or(IntcodeError::ImmediateWrite { ip: self.ip })?;
+
+        self.store(address, value);
+        Ok(())
+    }
+
+    /// Take the next input, if there is one.
+    pub fn input(&mut self) -> Option<W> {
+        self.inputs.pop_front()
+    }
+
+    pub fn relative_base(&self) -> &W {
+        &self.memory.relative_base
+    }
+
+    pub fn set_relative_base(&mut self, relative_base: W) {
+        self.memory.relative_base = relative_base;
+    }
+
+    /// Read the value at `address`, independent of any parameter.
+    pub fn load(&self, address: usize) -> W {
+        self.memory.load(address)
+    }
+
+    /// Write the value at `address`, independent of any parameter.
+    #[inline(always)]
+    pub fn store(&mut self, address: usize, value: W) {
+        if let Some(profile) = &mut self.profile {
+            profile.record_write(address);
+        }
+        self.memory.store(address, value);
+    }
+}
+
+/// An entry in an [`Opcodes`] table.
+pub struct Definition<W: Word = isize, M: MemoryBackend<W> = GrowableMemory<W>> {
+    pub mnemonic: &'static str,
+    /// The number of parameters, at most [`MAX_ARITY`].
+    pub arity: usize,
+    /// The built-in opcode this entry implements, if any.
+    pub(crate) builtin: Option<Opcode>,
+    pub(crate) handler: Rc<Handler<W, M>>,
+}
+
+impl<W: Word, M: MemoryBackend<W>> Clone for Definition<W, M> {
+    fn clone(&self) -> Self {
+        Self {
+            mnemonic: self.mnemonic,
+            arity: self.arity,
+            builtin: self.builtin,
+            handler: self.handler.clone(),
+        }
+    }
+}
+
+impl<W: Word, M: MemoryBackend<W>> std::fmt::Debug for Definition<W, M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Definition")
+            .field("mnemonic", &self.mnemonic)
+            .field("arity", &self.arity)
+            .field("builtin", &self.builtin)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The opcodes a machine executes, by number.
+pub struct Opcodes<W: Word = isize, M: MemoryBackend<W> = GrowableMemory<W>> {
+    table: Vec<Option<Definition<W, M>>>,
+}
+
+impl<W: Word, M: MemoryBackend<W>> Clone for Opcodes<W, M> {
+    fn clone(&self) -> Self {
+        Self {
+            table: self.table.clone(),
+        }
+    }
+}
+
+impl<W: Word, M: MemoryBackend<W>> std::fmt::Debug for Opcodes<W, M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map()
+            .entries(self.iter().map(|(code, def)| (code, def.mnemonic)))
+            .finish()
+    }
+}
+
+impl<W: Word, M: MemoryBackend<W>> Default for Opcodes<W, M> {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl<W: Word, M: MemoryBackend<W>> Opcodes<W, M> {
+    /// A table without any opcodes.
+    pub fn empty() -> Self {
+        Self {
+            table: vec![None; OPCODES],
+        }
+    }
+
+    /// The ten opcodes from the puzzles.
+    pub fn builtin() -> Self {
+        let mut opcodes = Self::empty();
+
+        for op in Opcode::ALL {
+            let handler: Rc<Handler<W, M>> = Rc::new(move |ctx| execute(op, ctx));
+
+            opcodes.table[op.code() as usize] = Some(Definition {
+                mnemonic: op.mnemonic(),
+                arity: op.arity(),
+                builtin: Some(op),
+                handler,
+            });
+        }
+
+        opcodes
+    }
+
+    /// Add opcode `code`, replacing the opcode with that number if there is
+    /// one.
+    ///
+    /// # Panics
+    ///
+    /// If `code` is 100 or more, or `arity` is more than [`MAX_ARITY`].
+    pub fn register(
+        &mut self,
+        code: u8,
+        mnemonic: &'static str,
+        arity: usize,
+        handler: impl Fn(&mut Context<'_, W, M>) -> Result<Action<W>, IntcodeError<W>> + 'static,
+    ) {
+        assert!(
+            (code as usize) < OPCODES,
+            "opcode {code} has more than two digits"
+        );
+        assert!(
+            arity <= MAX_ARITY,
+            "{mnemonic} takes {arity} parameters, at most {MAX_ARITY} are supported"
+        );
+
+        self.table[code as usize] = Some(Definition {
+            mnemonic,
+            arity,
+            builtin: None,
+            handler: Rc::new(handler),
+        });
+    }
+
+    /// Remove opcode `code`. Returns whether it was present.
+    pub fn remove(&mut self, code: u8) -> bool {
+        self.table
+            .get_mut(code as usize)
+            .and_then(Option::take)
+            .is_some()
+    }
+
+    pub fn get(&self, code: u8) -> Option<&Definition<W, M>> {
+        self.table.get(code as usize)?.as_ref()
+    }
+
+    /// All opcodes, by number.
+    pub fn iter(&self) -> impl Iterator<Item = (u8, &Definition<W, M>)> {
+        self.table
+            .iter()
+            .enumerate()
+            .filter_map(|(code, def)| Some((code as u8, def.as_ref()?)))
+    }
+
+    /// Look up the opcode of the instruction `op_in` found at `ip`, ignoring
+    /// its parameter modes.
+    pub(crate) fn decode(&self, ip: usize, op_in: &W) -> Result<u8, IntcodeError<W>> {
+        match op_in.to_isize() {
+            Some(op_in) if op_in >= 0 && self.table[(op_in % 100) as usize].is_some() => {
+                Ok((op_in % 100) as u8)
+            }
+            _ => Err(IntcodeError::UnknownOpcode {
+                ip,
+                opcode: op_in.clone(),
+            }),
+        }
+    }
+}
+
+/// Execute the built-in opcode `op`.
+///
+/// This is the handler of every built-in entry, which
+/// [`ProgramState::step`](crate::ProgramState::step) also calls directly for
+/// them to save the indirect call.
+#[inline(always)]
+pub(crate) fn execute<W: Word, M: MemoryBackend<W>>(
+    op: Opcode,
+    ctx: &mut Context<'_, W, M>,
+) -> Result<Action<W>, IntcodeError<W>> {
+    match op {
+        Opcode::Add => {
+            let value = arith(ctx, W::checked_add, W::wrapping_add)?;
+            ctx.set(2, value)?;
+            Ok(Action::Next)
+        }
+        Opcode::Mul => {
+            let value = arith(ctx, W::checked_mul, W::wrapping_mul)?;
+            ctx.set(2, value)?;
+            Ok(Action::Next)
+        }
+        // The input is only taken once it is stored, so an instruction that
+        // fails keeps it queued.
+        Opcode::Input => match ctx.inputs.front() {
+            Some(input) => {
+                ctx.set(0, input.clone())?;
+                ctx.inputs.pop_front();
+                Ok(Action::Next)
+            }
+            None => Ok(Action::NeedsInput),
+        },
+        Opcode::Output => Ok(Action::Output(ctx.get(0)?)),
+        Opcode::JumpIfTrue => jump(ctx, false),
+        Opcode::JumpIfFalse => jump(ctx, true),
+        Opcode::LessThan => compare(ctx, W::lt),
+        Opcode::Equals => compare(ctx, W::eq),
+        Opcode::AdjustRelativeBase => {
+            let offset = ctx.get(0)?;
+            let relative_base = if ctx.is_checked() {
+                ctx.relative_base()
+                    .checked_add(&offset)
+                    .ok_or(IntcodeError::Overflow { ip: ctx.ip() })?
+            } else {
+                ctx.relative_base().wrapping_add(&offset)
+            };
+            ctx.set_relative_base(relative_base);
+            Ok(Action::Next)
+        }
+        Opcode::Halt => Ok(Action::Halt),
+    }
+}
+
+#[inline(always)]
+fn arith<W: Word, M: MemoryBackend<W>>(
+    ctx: &mut Context<'_, W, M>,
+    checked: fn(&W, &W) -> Option<W>,
+    wrapping: fn(&W, &W) -> W,
+) -> Result<W, IntcodeError<W>> {
+    let v1 = ctx.get(0)?;
+    let v2 = ctx.get(1)?;
+
+    if ctx.is_checked() {
+        checked(&v1, &v2).ok_or(IntcodeError::Overflow { ip: ctx.ip() })
+    } else {
+        Ok(wrapping(&v1, &v2))
+    }
+}
+
+#[inline(always)]
+fn jump<W: Word, M: MemoryBackend<W>>(
+    ctx: &mut Context<'_, W, M>,
+    if_zero: bool,
+) -> Result<Action<W>, IntcodeError<W>> {
+    if ctx.get(0)?.is_zero() != if_zero {
+        return Ok(Action::Next);
+    }
+
+    let target = ctx.get(1)?;
+    let target = to_address(&target).map_err(|_| IntcodeError::IpOutOfRange { ip: target })?;
+    Ok(Action::Jump(target))
+}
+
+#[inline(always)]
+fn compare<W: Word, M: MemoryBackend<W>>(
+    ctx: &mut Context<'_, W, M>,
+    check: fn(&W, &W) -> bool,
+) -> Result<Action<W>, IntcodeError<W>> {
+    let v1 = ctx.get(0)?;
+    let v2 = ctx.get(1)?;
+
+    ctx.set(2, W::from_isize(check(&v1, &v2) as isize))?;
+    Ok(Action::Next)
+}
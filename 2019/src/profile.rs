@@ -0,0 +1,254 @@
+//! Execution counts and memory coverage of a [`ProgramState`](crate::ProgramState).
+//!
+//! Profiling is off by default, see
+//! [`ProgramState::set_profiling`](crate::ProgramState::set_profiling).
+
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    fmt::Write,
+    ops::RangeInclusive,
+};
+
+use crate::Opcode;
+
+/// Everything a profiled [`ProgramState`](crate::ProgramState) has executed
+/// and accessed so far.
+///
+/// Only instructions that completed are counted: an input instruction that
+/// is waiting for input is not, and a halt instruction is marked as executed
+/// without being counted.
+#[derive(Debug, Clone, Default)]
+pub struct Profile {
+    opcodes: HashMap<&'static str, u64>,
+    addresses: HashMap<usize, u64>,
+    executed: HashSet<usize>,
+    read: HashSet<usize>,
+    written: HashSet<usize>,
+}
+
+impl Profile {
+    pub(crate) fn record_instruction(&mut self, ip: usize, mnemonic: &'static str, arity: usize) {
+        *self.opcodes.entry(mnemonic).or_default() += 1;
+        *self.addresses.entry(ip).or_default() += 1;
+        self.executed.extend(ip..=ip + arity);
+    }
+
+    pub(crate) fn record_halt(&mut self, ip: usize) {
+        self.executed.insert(ip);
+    }
+
+    pub(crate) fn record_read(&mut self, address: usize) {
+        self.read.insert(address);
+    }
+
+    pub(crate) fn record_write(&mut self, address: usize) {
+        self.written.insert(address);
+    }
+
+    /// The number of times each opcode was executed, by mnemonic.
+    pub fn opcode_counts(&self) -> &HashMap<&'static str, u64> {
+        &self.opcodes
+    }
+
+    /// The number of times an instruction starting at each address was
+    /// executed.
+    pub fn address_counts(&self) -> &HashMap<usize, u64> {
+        &self.addresses
+    }
+
+    /// Every address that was part of an executed instruction, including its
+    /// parameters.
+    pub fn executed(&self) -> &HashSet<usize> {
+        &self.executed
+    }
+
+    /// Every address read by a position or relative mode parameter.
+    pub fn read(&self) -> &HashSet<usize> {
+        &self.read
+    }
+
+    /// Every address written by an instruction.
+    pub fn written(&self) -> &HashSet<usize> {
+        &self.written
+    }
+
+    /// The `n` most executed instruction addresses, most executed first.
+    pub fn hottest(&self, n: usize) -> Vec<(usize, u64)> {
+        let mut addresses: Vec<_> = self.addresses.iter().map(|(a, c)| (*a, *c)).collect();
+        addresses.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        addresses.truncate(n);
+        addresses
+    }
+
+    /// The ranges of the first `program_len` addresses that were never
+    /// executed, read or written.
+    pub fn uncovered(&self, program_len: usize) -> Vec<RangeInclusive<usize>> {
+        let mut ranges: Vec<RangeInclusive<usize>> = Vec::new();
+
+        let untouched = (0..program_len).filter(|a| {
+            !self.executed.contains(a) && !self.read.contains(a) && !self.written.contains(a)
+        });
+
+        for address in untouched {
+            match ranges.last_mut() {
+                Some(range) if *range.end() + 1 == address => *range = *range.start()..=address,
+                _ => ranges.push(address..=address),
+            }
+        }
+
+        ranges
+    }
+
+    /// Addresses that were both written and executed, i.e. code that the
+    /// program modified.
+    pub fn self_modified(&self) -> BTreeSet<usize> {
+        self.written.intersection(&self.executed).copied().collect()
+    }
+
+    /// A human-readable summary: opcode counts, the `top` hottest addresses,
+    /// uncovered parts of the first `program_len` addresses and self-modified
+    /// cells.
+    pub fn report(&self, program_len: usize, top: usize) -> String {
+        let mut out = String::new();
+        let total: u64 = self.opcodes.values().sum();
+        let percent = |count: u64| 100.0 * count as f64 / total.max(1) as f64;
+
+        writeln!(out, "instructions executed: {total}").unwrap();
+
+        writeln!(out, "\nopcodes:").unwrap();
+        // The built-in opcodes, then any custom ones that were executed.
+        let mut custom: Vec<_> = self
+            .opcodes
+            .keys()
+            .filter(|m| Opcode::ALL.iter().all(|op| op.mnemonic() != **m))
+            .copied()
+            .collect();
+        custom.sort();
+
+        let mnemonics = Opcode::ALL.iter().map(|op| op.mnemonic()).chain(custom);
+        for mnemonic in mnemonics {
+            let count = self.opcodes.get(mnemonic).copied().unwrap_or(0);
+            writeln!(out, "  {mnemonic:<4} {count:>12} {:>6.2}%", percent(count)).unwrap();
+        }
+
+        writeln!(out, "\nhottest addresses:").unwrap();
+        for (address, count) in self.hottest(top) {
+            writeln!(out, "  {address:>6} {count:>12} {:>6.2}%", percent(count)).unwrap();
+        }
+
+        let uncovered = self.uncovered(program_len);
+        let uncovered_cells: usize = uncovered.iter().map(|r| r.clone().count()).sum();
+        writeln!(
+            out,
+            "\nuncovered: {uncovered_cells} of {program_len} program cells"
+        )
+        .unwrap();
+        for range in uncovered {
+            if range.start() == range.end() {
+                writeln!(out, "  {}", range.start()).unwrap();
+            } else {
+                writeln!(out, "  {}..={}", range.start(), range.end()).unwrap();
+            }
+        }
+
+        let modified = self.self_modified();
+        writeln!(out, "\nself-modified cells: {}", modified.len()).unwrap();
+        for address in modified {
+            writeln!(out, "  {address}").unwrap();
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ProgramState;
+
+    /// Counts cell 20 down from 3, then patches the unknown opcode at 11 into
+    /// an output of cell 21 and halts. Cells 14 to 19 are never touched.
+    const PATCH: [isize; 22] = [
+        1001, 20, -1, 20, // ADD 20, #-1, 20
+        1005, 20, 0, // JNZ 20, #0
+        1101, 4, 0, 11, // ADD #4, #0, 11
+        0, 21, // OUT 21, once patched
+        99, 0, 0, 0, 0, 0, 0, 3, 42,
+    ];
+
+    fn profiled() -> ProgramState {
+        let mut state = ProgramState::new_empty(&PATCH);
+        state.set_profiling(true);
+        state.run_to_exit().unwrap();
+        assert_eq!(state.drain_outputs().collect::<Vec<_>>(), [42]);
+        state
+    }
+
+    #[test]
+    fn counts() {
+        let state = profiled();
+        let profile = state.profile().unwrap();
+
+        let mut addresses: Vec<_> = profile.address_counts().iter().collect();
+        addresses.sort_unstable();
+        assert_eq!(addresses, [(&0, &3), (&4, &3), (&7, &1), (&11, &1)]);
+
+        assert_eq!(profile.opcode_counts()["ADD"], 4);
+        assert_eq!(profile.opcode_counts()["JNZ"], 3);
+        assert_eq!(profile.opcode_counts()["OUT"], 1);
+        assert_eq!(profile.opcode_counts().len(), 3);
+
+        assert_eq!(profile.hottest(3), [(0, 3), (4, 3), (7, 1)]);
+    }
+
+    #[test]
+    fn coverage() {
+        let state = profiled();
+        let profile = state.profile().unwrap();
+
+        assert_eq!(profile.executed().len(), 14);
+        assert!((0..=13).all(|a| profile.executed().contains(&a)));
+        assert_eq!(profile.read().len(), 2);
+        assert!(profile.read().contains(&20) && profile.read().contains(&21));
+
+        assert_eq!(profile.uncovered(PATCH.len()), [14..=19]);
+        assert_eq!(profile.uncovered(30), [14..=19, 22..=29]);
+        assert_eq!(
+            profile.self_modified().into_iter().collect::<Vec<_>>(),
+            [11]
+        );
+    }
+
+    #[test]
+    fn report() {
+        let state = profiled();
+        let report = state.profile().unwrap().report(PATCH.len(), 2);
+
+        assert_eq!(
+            report,
+            "instructions executed: 8
+
+opcodes:
+  ADD             4  50.00%
+  MUL             0   0.00%
+  IN              0   0.00%
+  OUT             1  12.50%
+  JNZ             3  37.50%
+  JZ              0   0.00%
+  LT              0   0.00%
+  EQ              0   0.00%
+  ARB             0   0.00%
+  HLT             0   0.00%
+
+hottest addresses:
+       0            3  37.50%
+       4            3  37.50%
+
+uncovered: 6 of 22 program cells
+  14..=19
+
+self-modified cells: 1
+  11
+"
+        );
+    }
+}
@@ -0,0 +1,188 @@
+//! Loading Intcode programs from puzzle input.
+
+use std::{
+    io::Read,
+    ops::{Deref, DerefMut},
+    path::Path,
+    str::FromStr,
+};
+
+use crate::word::Word;
+
+/// A comma-separated Intcode program.
+///
+/// Whitespace, including newlines, around the values is ignored, as is a
+/// trailing comma. A `Program` derefs to a slice of its values and converts
+/// into a `Vec`, so it can be handed directly to [`ProgramState`](crate::ProgramState)
+/// constructors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Program<W = isize>(Vec<W>);
+
+#[derive(Debug)]
+pub enum ProgramError {
+    Io(std::io::Error),
+    /// The value at the given 0-based index is not a valid word.
+    Parse {
+        index: usize,
+        token: String,
+    },
+}
+
+impl std::fmt::Display for ProgramError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "{e}"),
+            Self::Parse { index, token } => {
+                write!(f, "invalid value {token:?} at index {index}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ProgramError {}
+
+impl From<std::io::Error> for ProgramError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl<W: Word> Program<W> {
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ProgramError> {
+        std::fs::read_to_string(path)?.parse()
+    }
+
+    pub fn from_reader(mut reader: impl Read) -> Result<Self, ProgramError> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text)?;
+        text.parse()
+    }
+
+    /// Read a program from all of stdin.
+    pub fn from_stdin() -> Result<Self, ProgramError> {
+        Self::from_reader(std::io::stdin().lock())
+    }
+}
+
+impl<W> Program<W> {
+    pub fn into_vec(self) -> Vec<W> {
+        self.0
+    }
+}
+
+impl<W: Word> FromStr for Program<W> {
+    type Err = ProgramError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let s = s.strip_suffix(',').unwrap_or(s);
+
+        s.split(',')
+            .map(str::trim)
+            .enumerate()
+            .map(|(index, token)| {
+                token.parse().map_err(|_| ProgramError::Parse {
+                    index,
+                    token: token.to_string(),
+                })
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+impl<W> Deref for Program<W> {
+    type Target = [W];
+
+    fn deref(&self) -> &[W] {
+        &self.0
+    }
+}
+
+impl<W> DerefMut for Program<W> {
+    fn deref_mut(&mut self) -> &mut [W] {
+        &mut self.0
+    }
+}
+
+impl<W> From<Vec<W>> for Program<W> {
+    fn from(value: Vec<W>) -> Self {
+        Self(value)
+    }
+}
+
+impl<W> From<Program<W>> for Vec<W> {
+    fn from(value: Program<W>) -> Self {
+        value.0
+    }
+}
+
+impl<W: Word> std::fmt::Display for Program<W> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (idx, value) in self.0.iter().enumerate() {
+            if idx != 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{value}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bigint::BigInt;
+
+    fn parse_error(text: &str) -> (usize, String) {
+        match text.parse::<Program>() {
+            Err(ProgramError::Parse { index, token }) => (index, token),
+            other => panic!("expected a parse error for {text:?}, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn whitespace() {
+        let expected = Program::from(vec![1, -2, 3]);
+
+        assert_eq!("1,-2,3".parse::<Program>().unwrap(), expected);
+        assert_eq!("1,-2,3\n".parse::<Program>().unwrap(), expected);
+        assert_eq!("1,-2,3,\r\n".parse::<Program>().unwrap(), expected);
+        assert_eq!(" 1 ,\n-2,\t3 \n\n".parse::<Program>().unwrap(), expected);
+        assert_eq!(expected.to_string(), "1,-2,3");
+    }
+
+    #[test]
+    fn empty() {
+        // An empty input is a missing value, not an empty program.
+        assert_eq!(parse_error(""), (0, String::new()));
+        assert_eq!(parse_error(" \n"), (0, String::new()));
+        assert_eq!(parse_error(","), (0, String::new()));
+    }
+
+    #[test]
+    fn bad_token() {
+        assert_eq!(parse_error("1,2,x,4"), (2, "x".into()));
+        assert_eq!(parse_error("1,,3"), (1, String::new()));
+        assert_eq!(parse_error("1,2,3,,"), (3, String::new()));
+        assert_eq!(parse_error("1, 2 3"), (1, "2 3".into()));
+
+        let error = "99,1.5".parse::<Program>().unwrap_err();
+        assert_eq!(error.to_string(), "invalid value \"1.5\" at index 1");
+    }
+
+    #[test]
+    fn words() {
+        let text = "1,99999999999999999999";
+
+        assert!(matches!(
+            text.parse::<Program<i64>>(),
+            Err(ProgramError::Parse { index: 1, .. })
+        ));
+
+        let program: Program<i128> = text.parse().unwrap();
+        assert_eq!(&program[..], [1, 99999999999999999999]);
+        let program: Program<BigInt> = text.parse().unwrap();
+        assert_eq!(program.to_string(), text);
+    }
+}
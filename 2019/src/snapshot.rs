@@ -0,0 +1,338 @@
+//! Saving and restoring the complete state of a [`ProgramState`].
+//!
+//! Snapshots are stored as a small line-based text format:
+//!
+//! ```text
+//! intcode-snapshot 1
+//! ip 12
+//! relative_base 5
+//! inputs 1,2
+//! outputs
+//! program 3,20,1001,20,5,1000,4,1000,1105,1,0,99
+//! extra_memory 1000=12,2000=-1
+//! ```
+
+use std::{
+    collections::{BTreeMap, VecDeque},
+    path::Path,
+    str::FromStr,
+};
+
+use crate::{memory::MemoryBackend, word::Word, ProgramState};
+
+const HEADER: &str = "intcode-snapshot 1";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot<W = isize> {
+    pub program: Vec<W>,
+    pub extra_memory: BTreeMap<usize, W>,
+    pub ip: usize,
+    pub relative_base: W,
+    pub inputs: Vec<W>,
+    pub outputs: Vec<W>,
+}
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    Io(std::io::Error),
+    /// The snapshot text is malformed on the given 1-based line.
+    Parse {
+        line: usize,
+        message: String,
+    },
+}
+
+impl std::fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "{e}"),
+            Self::Parse { line, message } => write!(f, "line {line}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl From<std::io::Error> for SnapshotError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl<W: Word> Snapshot<W> {
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SnapshotError> {
+        Ok(std::fs::write(path, self.to_string())?)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SnapshotError> {
+        std::fs::read_to_string(path)?.parse()
+    }
+}
+
+fn join<T: ToString>(values: impl Iterator<Item = T>) -> String {
+    values.map(|v| v.to_string()).collect::<Vec<_>>().join(",")
+}
+
+impl<W: Word> std::fmt::Display for Snapshot<W> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let extra_memory = join(self.extra_memory.iter().map(|(a, v)| format!("{a}={v}")));
+
+        let fields = [
+            ("ip", self.ip.to_string()),
+            ("relative_base", self.relative_base.to_string()),
+            ("inputs", join(self.inputs.iter())),
+            ("outputs", join(self.outputs.iter())),
+            ("program", join(self.program.iter())),
+            ("extra_memory", extra_memory),
+        ];
+
+        writeln!(f, "{HEADER}")?;
+        for (key, value) in fields {
+            if value.is_empty() {
+                writeln!(f, "{key}")?;
+            } else {
+                writeln!(f, "{key} {value}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<W: Word> FromStr for Snapshot<W> {
+    type Err = SnapshotError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        fn list<T: FromStr>(value: &str) -> Result<Vec<T>, String> {
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(|v| v.parse().map_err(|_| format!("invalid value `{v}`")))
+                .collect()
+        }
+
+        fn number<T: FromStr>(value: &str) -> Result<T, String> {
+            value
+                .parse()
+                .map_err(|_| format!("invalid value `{value}`"))
+        }
+
+        let mut lines = s.lines().enumerate().map(|(idx, l)| (idx + 1, l.trim()));
+
+        if lines.next().map(|(_, l)| l) != Some(HEADER) {
+            return Err(SnapshotError::Parse {
+                line: 1,
+                message: format!("expected `{HEADER}`"),
+            });
+        }
+
+        let mut snapshot = Snapshot {
+            program: Vec::new(),
+            extra_memory: BTreeMap::new(),
+            ip: 0,
+            relative_base: W::from_isize(0),
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+        };
+
+        for (line, text) in lines.filter(|(_, l)| !l.is_empty()) {
+            let (key, value) = text.split_once(' ').unwrap_or((text, ""));
+            let value = value.trim();
+
+            let result = match key {
+                "ip" => number(value).map(|v| snapshot.ip = v),
+                "relative_base" => number(value).map(|v| snapshot.relative_base = v),
+                "inputs" => list(value).map(|v| snapshot.inputs = v),
+                "outputs" => list(value).map(|v| snapshot.outputs = v),
+                "program" => list(value).map(|v| snapshot.program = v),
+                "extra_memory" => list::<String>(value).and_then(|cells| {
+                    for cell in cells {
+                        let (address, value) = cell
+                            .split_once('=')
+                            .ok_or_else(|| format!("expected `address=value`, found `{cell}`"))?;
+                        snapshot
+                            .extra_memory
+                            .insert(number(address)?, number(value)?);
+                    }
+                    Ok(())
+                }),
+                _ => Err(format!("unknown key `{key}`")),
+            };
+
+            result.map_err(|message| SnapshotError::Parse { line, message })?;
+        }
+
+        Ok(snapshot)
+    }
+}
+
+impl ProgramState {
+    pub fn from_snapshot(snapshot: Snapshot) -> Self {
+        Self::with_memory_from_snapshot(snapshot)
+    }
+}
+
+impl<W: Word, M: MemoryBackend<W>> ProgramState<W, M> {
+    /// Capture the complete state of this program.
+    pub fn snapshot(&self) -> Snapshot<W> {
+        Snapshot {
+            program: self.memory.cells.program().to_vec(),
+            extra_memory: self.memory.cells.extra_cells().into_iter().collect(),
+            ip: self.ip,
+            relative_base: self.memory.relative_base.clone(),
+            inputs: self.inputs.iter().cloned().collect(),
+            outputs: self.outputs.iter().cloned().collect(),
+        }
+    }
+
+    /// Like [`ProgramState::from_snapshot`], using memory backend `M`.
+    pub fn with_memory_from_snapshot(snapshot: Snapshot<W>) -> Self {
+        let mut state = Self::with_memory(snapshot.inputs, snapshot.program);
+
+        for (address, value) in snapshot.extra_memory {
+            state.memory.store(address, value);
+        }
+
+        state.outputs = VecDeque::from(snapshot.outputs);
+        state.ip = snapshot.ip;
+        state.memory.relative_base = snapshot.relative_base;
+        state
+    }
+
+    /// Replace the state of this program with `snapshot`, keeping how it
+    /// runs: its opcodes, checked mode, [`Interpreter`](crate::Interpreter)
+    /// and profile.
+    pub fn restore(&mut self, snapshot: Snapshot<W>) {
+        let mut state = Self::with_memory_from_snapshot(snapshot);
+
+        state.opcodes = self.opcodes.clone();
+        state.checked = self.checked;
+        state.profile = self.profile.take();
+        // The instruction cache is rebuilt for the restored memory.
+        state.set_interpreter(self.interpreter());
+
+        *self = state;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{IntcodeError, Interpreter, RunState};
+
+    #[test]
+    fn restore_keeps_configuration() {
+        // Doubles the value at 5, which overflows.
+        let program = vec![1, 5, 5, 5, 99, isize::MAX];
+
+        let mut state = ProgramState::new_empty(&program);
+        let snapshot = state.snapshot();
+
+        state.set_checked(true);
+        state.set_interpreter(Interpreter::Cached);
+        state.set_profiling(true);
+        state.restore(snapshot);
+
+        assert!(state.is_checked());
+        assert_eq!(state.interpreter(), Interpreter::Cached);
+        assert!(state.profile().is_some());
+        assert!(matches!(state.run(), Err(IntcodeError::Overflow { ip: 0 })));
+    }
+
+    /// Adds 5 to every input, keeping the result at 1000.
+    const ADD_FIVE: [isize; 12] = [3, 20, 1001, 20, 5, 1000, 4, 1000, 1105, 1, 0, 99];
+
+    fn parse_error(text: &str) -> (usize, String) {
+        match text.parse::<Snapshot>() {
+            Err(SnapshotError::Parse { line, message }) => (line, message),
+            other => panic!("expected a parse error for {text:?}, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn text_round_trip() {
+        let mut state = ProgramState::new_multi_input(vec![1, 2, 3], ADD_FIVE.to_vec());
+        state.memory.relative_base = -5;
+        state.poll().unwrap();
+        state.write(2000, -1);
+
+        let snapshot = state.snapshot();
+        assert_eq!(snapshot.inputs, [2, 3]);
+        assert_eq!(snapshot.outputs, [] as [isize; 0]);
+        assert_eq!(
+            snapshot.extra_memory,
+            BTreeMap::from([(20, 1), (1000, 6), (2000, -1)])
+        );
+
+        let text = snapshot.to_string();
+        assert_eq!(
+            text,
+            "intcode-snapshot 1
+ip 8
+relative_base -5
+inputs 2,3
+outputs
+program 3,20,1001,20,5,1000,4,1000,1105,1,0,99
+extra_memory 20=1,1000=6,2000=-1
+"
+        );
+        assert_eq!(text.parse::<Snapshot>().unwrap(), snapshot);
+
+        // Empty lists are written as just their key.
+        let empty = ProgramState::new_empty(&[99]).snapshot();
+        assert_eq!(empty.to_string().parse::<Snapshot>().unwrap(), empty);
+    }
+
+    #[test]
+    fn save_and_load() {
+        let path = std::env::temp_dir().join(format!("intcode-snapshot-{}", std::process::id()));
+
+        let mut state = ProgramState::new_multi_input(vec![1, 2], ADD_FIVE.to_vec());
+        assert_eq!(state.poll(), Ok(RunState::Output(6)));
+        state.snapshot().save(&path).unwrap();
+
+        let loaded = Snapshot::load(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        // The loaded machine continues exactly like the original.
+        let mut resumed = ProgramState::from_snapshot(loaded.unwrap());
+        assert_eq!(resumed.snapshot(), state.snapshot());
+        for machine in [&mut state, &mut resumed] {
+            machine.add_input(3);
+            assert_eq!(machine.run(), Ok(RunState::NeedsInput));
+            assert_eq!(machine.drain_outputs().collect::<Vec<_>>(), [7, 8]);
+        }
+
+        assert!(matches!(
+            Snapshot::<isize>::load(&path),
+            Err(SnapshotError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn malformed() {
+        assert_eq!(parse_error("").0, 1);
+        assert_eq!(parse_error("intcode-snapshot 2\nip 0").0, 1);
+        assert_eq!(
+            parse_error("intcode-snapshot 1\nip 0\n\nspeed 3"),
+            (4, "unknown key `speed`".to_string())
+        );
+        assert_eq!(
+            parse_error("intcode-snapshot 1\nip -1"),
+            (2, "invalid value `-1`".to_string())
+        );
+        assert_eq!(
+            parse_error("intcode-snapshot 1\nprogram 1,x,3"),
+            (2, "invalid value `x`".to_string())
+        );
+        assert_eq!(
+            parse_error("intcode-snapshot 1\nextra_memory 1000"),
+            (2, "expected `address=value`, found `1000`".to_string())
+        );
+        assert_eq!(
+            parse_error("intcode-snapshot 1\nextra_memory -1=4"),
+            (2, "invalid value `-1`".to_string())
+        );
+    }
+}
@@ -0,0 +1,578 @@
+//! Symbolic execution of Intcode programs.
+//!
+//! Chosen memory cells or inputs are replaced by [`Symbol`]s, and every
+//! value the program computes is tracked as a linear expression over those
+//! symbols. For straight-line programs, this yields a formula for every
+//! output and memory cell, which can be solved directly instead of running
+//! the program for every possible input.
+//!
+//! Values that are not linear in the symbols, such as the product of two
+//! symbolic values or a value read from a symbolic address, become
+//! [`Value::Unknown`]. Execution stops with a [`SymbolicError`] as soon as
+//! control flow or a write address depends on a symbolic value; callers are
+//! expected to fall back to concrete execution then.
+
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    ops::RangeInclusive,
+};
+
+use crate::{param_modes, IntcodeError, Opcode, ParamMode};
+
+/// An unknown value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Symbol {
+    /// The initial value of a memory cell.
+    Cell(usize),
+    /// The input value with the given 0-based index.
+    Input(usize),
+}
+
+impl std::fmt::Display for Symbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Cell(address) => write!(f, "[{address}]"),
+            Self::Input(idx) => write!(f, "in{idx}"),
+        }
+    }
+}
+
+/// `constant + sum(coefficient * symbol)`, with wrapping arithmetic like
+/// [`ProgramState`](crate::ProgramState).
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Linear {
+    pub constant: isize,
+    /// Non-zero coefficients by symbol.
+    pub terms: BTreeMap<Symbol, isize>,
+}
+
+impl Linear {
+    pub fn constant(value: isize) -> Self {
+        Self {
+            constant: value,
+            terms: BTreeMap::new(),
+        }
+    }
+
+    pub fn symbol(symbol: Symbol) -> Self {
+        Self {
+            constant: 0,
+            terms: BTreeMap::from([(symbol, 1)]),
+        }
+    }
+
+    /// The value of this expression, if it does not depend on any symbol.
+    pub fn as_constant(&self) -> Option<isize> {
+        self.terms.is_empty().then_some(self.constant)
+    }
+
+    fn add(&self, rhs: &Self) -> Self {
+        let mut result = self.clone();
+        result.constant = result.constant.wrapping_add(rhs.constant);
+
+        for (symbol, coefficient) in &rhs.terms {
+            let entry = result.terms.entry(*symbol).or_default();
+            *entry = entry.wrapping_add(*coefficient);
+        }
+
+        result.terms.retain(|_, c| *c != 0);
+        result
+    }
+
+    fn scale(&self, factor: isize) -> Self {
+        let mut terms = self.terms.clone();
+        terms.values_mut().for_each(|c| *c = c.wrapping_mul(factor));
+        terms.retain(|_, c| *c != 0);
+
+        Self {
+            constant: self.constant.wrapping_mul(factor),
+            terms,
+        }
+    }
+
+    /// The value of this expression when every symbol has the value given in
+    /// `values`. Missing symbols are zero.
+    pub fn evaluate(&self, values: &BTreeMap<Symbol, isize>) -> isize {
+        self.terms.iter().fold(self.constant, |acc, (symbol, c)| {
+            let value = values.get(symbol).copied().unwrap_or(0);
+            acc.wrapping_add(c.wrapping_mul(value))
+        })
+    }
+
+    /// Find values for all symbols in `domain` for which this expression
+    /// equals `target`.
+    ///
+    /// Every symbol but the last is enumerated; the last one is solved for
+    /// directly.
+    pub fn solve(
+        &self,
+        target: isize,
+        domain: RangeInclusive<isize>,
+    ) -> Option<BTreeMap<Symbol, isize>> {
+        let symbols: Vec<Symbol> = self.terms.keys().copied().collect();
+        let mut values = BTreeMap::new();
+        self.solve_from(&symbols, target, &domain, &mut values)
+            .then_some(values)
+    }
+
+    fn solve_from(
+        &self,
+        symbols: &[Symbol],
+        target: isize,
+        domain: &RangeInclusive<isize>,
+        values: &mut BTreeMap<Symbol, isize>,
+    ) -> bool {
+        match symbols {
+            [] => self.evaluate(values) == target,
+            [last] => {
+                let coefficient = self.terms[last];
+                values.insert(*last, 0);
+                let rest = target.wrapping_sub(self.evaluate(values));
+
+                let value = match (rest.checked_rem(coefficient), rest.checked_div(coefficient)) {
+                    (Some(0), Some(value)) if domain.contains(&value) => value,
+                    _ => return false,
+                };
+
+                values.insert(*last, value);
+                self.evaluate(values) == target
+            }
+            [first, rest @ ..] => domain.clone().any(|value| {
+                values.insert(*first, value);
+                self.solve_from(rest, target, domain, values)
+            }),
+        }
+    }
+}
+
+impl std::fmt::Display for Linear {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (symbol, coefficient) in &self.terms {
+            write!(f, "{coefficient} * {symbol} + ")?;
+        }
+        write!(f, "{}", self.constant)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Linear(Linear),
+    /// A value that is not a linear expression of the symbols.
+    Unknown,
+}
+
+impl Value {
+    fn constant(value: isize) -> Self {
+        Self::Linear(Linear::constant(value))
+    }
+
+    pub fn as_constant(&self) -> Option<isize> {
+        match self {
+            Self::Linear(linear) => linear.as_constant(),
+            Self::Unknown => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Linear(linear) => write!(f, "{linear}"),
+            Self::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// The reason symbolic execution could not continue.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SymbolicError {
+    /// The program failed, just as it would when executed concretely.
+    Intcode(IntcodeError),
+    /// The instruction at `ip` is not a constant.
+    SymbolicOpcode { ip: usize },
+    /// Whether the jump at `ip` is taken depends on a symbolic value.
+    SymbolicBranch { ip: usize },
+    /// The write, jump target or relative base adjustment of the
+    /// instruction at `ip` depends on a symbolic value.
+    SymbolicAddress { ip: usize },
+    /// The instruction budget ran out.
+    OutOfFuel,
+}
+
+impl std::fmt::Display for SymbolicError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Intcode(e) => write!(f, "{e}"),
+            Self::SymbolicOpcode { ip } => write!(f, "instruction at ip {ip} is symbolic"),
+            Self::SymbolicBranch { ip } => {
+                write!(f, "branch at ip {ip} depends on a symbolic value")
+            }
+            Self::SymbolicAddress { ip } => {
+                write!(f, "address used at ip {ip} depends on a symbolic value")
+            }
+            Self::OutOfFuel => write!(f, "ran out of fuel"),
+        }
+    }
+}
+
+impl std::error::Error for SymbolicError {}
+
+impl From<IntcodeError> for SymbolicError {
+    fn from(value: IntcodeError) -> Self {
+        Self::Intcode(value)
+    }
+}
+
+/// An Intcode machine whose memory and inputs hold [`Value`]s.
+#[derive(Debug, Clone)]
+pub struct SymbolicMachine {
+    memory: Vec<Value>,
+    extra_memory: HashMap<usize, Value>,
+    ip: usize,
+    relative_base: isize,
+    inputs: VecDeque<Value>,
+    next_input_symbol: usize,
+    outputs: Vec<Value>,
+}
+
+impl SymbolicMachine {
+    pub fn new(program: &[isize]) -> Self {
+        Self {
+            memory: program.iter().map(|v| Value::constant(*v)).collect(),
+            extra_memory: HashMap::new(),
+            ip: 0,
+            relative_base: 0,
+            inputs: VecDeque::new(),
+            next_input_symbol: 0,
+            outputs: Vec::new(),
+        }
+    }
+
+    /// Replace the value at `address` with [`Symbol::Cell`].
+    pub fn set_symbolic(&mut self, address: usize) {
+        self.write(
+            address,
+            Value::Linear(Linear::symbol(Symbol::Cell(address))),
+        );
+    }
+
+    pub fn add_input(&mut self, value: isize) {
+        self.inputs.push_back(Value::constant(value));
+        self.next_input_symbol += 1;
+    }
+
+    /// Queue the next input as a [`Symbol::Input`].
+    pub fn add_symbolic_input(&mut self) {
+        let symbol = Symbol::Input(self.next_input_symbol);
+        self.inputs.push_back(Value::Linear(Linear::symbol(symbol)));
+        self.next_input_symbol += 1;
+    }
+
+    pub fn read(&self, address: usize) -> Value {
+        match self.memory.get(address) {
+            Some(value) => value.clone(),
+            None => self
+                .extra_memory
+                .get(&address)
+                .cloned()
+                .unwrap_or(Value::constant(0)),
+        }
+    }
+
+    pub fn write(&mut self, address: usize, value: Value) {
+        match self.memory.get_mut(address) {
+            Some(cell) => *cell = value,
+            None => {
+                self.extra_memory.insert(address, value);
+            }
+        }
+    }
+
+    pub fn outputs(&self) -> &[Value] {
+        &self.outputs
+    }
+
+    /// Run the program until it halts, executing at most `fuel`
+    /// instructions.
+    pub fn run(&mut self, fuel: u64) -> Result<(), SymbolicError> {
+        for _ in 0..fuel {
+            if !self.step()? {
+                return Ok(());
+            }
+        }
+
+        Err(SymbolicError::OutOfFuel)
+    }
+
+    fn address(&self, ip: usize, mode: ParamMode, value: isize) -> Result<usize, IntcodeError> {
+        let address = match mode {
+            ParamMode::Relative => self.relative_base.wrapping_add(value),
+            _ => value,
+        };
+
+        usize::try_from(address).map_err(|_| IntcodeError::NegativeAddress { ip, address })
+    }
+
+    /// Execute one instruction. Returns `false` if the program halted.
+    fn step(&mut self) -> Result<bool, SymbolicError> {
+        let ip = self.ip;
+
+        if ip >= self.memory.len() {
+            return Err(IntcodeError::IpOutOfRange { ip: ip as isize }.into());
+        }
+
+        let op_in = self
+            .read(ip)
+            .as_constant()
+            .ok_or(SymbolicError::SymbolicOpcode { ip })?;
+        let op = Opcode::decode(ip, &op_in)?;
+        let mut modes = param_modes::<isize>(ip, op_in as usize);
+
+        let mut params = Vec::with_capacity(op.arity());
+        for idx in 0..op.arity() {
+            params.push((modes.next().unwrap()?, self.read(ip + 1 + idx)));
+        }
+
+        let get = |idx: usize| -> Result<Value, SymbolicError> {
+            let (mode, raw) = &params[idx];
+            if *mode == ParamMode::Immediate {
+                return Ok(raw.clone());
+            }
+
+            match raw.as_constant() {
+                Some(raw) => Ok(self.read(self.address(ip, *mode, raw)?)),
+                None => Ok(Value::Unknown),
+            }
+        };
+
+        let set_address = |idx: usize| -> Result<usize, SymbolicError> {
+            let (mode, raw) = &params[idx];
+            if *mode == ParamMode::Immediate {
+                return Err(IntcodeError::ImmediateWrite { ip }.into());
+            }
+
+            let raw = raw
+                .as_constant()
+                .ok_or(SymbolicError::SymbolicAddress { ip })?;
+            Ok(self.address(ip, *mode, raw)?)
+        };
+
+        let mut next = ip + 1 + op.arity();
+
+        match op {
+            Opcode::Add | Opcode::Mul | Opcode::LessThan | Opcode::Equals => {
+                let (a, b) = (get(0)?, get(1)?);
+                let address = set_address(2)?;
+
+                let result = match (op, a, b) {
+                    (_, Value::Unknown, _) | (_, _, Value::Unknown) => Value::Unknown,
+                    (Opcode::Add, Value::Linear(a), Value::Linear(b)) => Value::Linear(a.add(&b)),
+                    (Opcode::Mul, Value::Linear(a), Value::Linear(b)) => {
+                        match (a.as_constant(), b.as_constant()) {
+                            (Some(a), _) => Value::Linear(b.scale(a)),
+                            (_, Some(b)) => Value::Linear(a.scale(b)),
+                            _ => Value::Unknown,
+                        }
+                    }
+                    (_, Value::Linear(a), Value::Linear(b)) => {
+                        // Only decidable if the symbols cancel out.
+                        match a.add(&b.scale(-1)).as_constant() {
+                            Some(diff) if op == Opcode::LessThan => {
+                                Value::constant((diff < 0) as isize)
+                            }
+                            Some(diff) => Value::constant((diff == 0) as isize),
+                            None => Value::Unknown,
+                        }
+                    }
+                };
+
+                self.write(address, result);
+            }
+            Opcode::Input => {
+                let address = set_address(0)?;
+                let input = self
+                    .inputs
+                    .pop_front()
+                    .ok_or(IntcodeError::MissingInput { ip })?;
+                self.write(address, input);
+            }
+            Opcode::Output => {
+                let value = get(0)?;
+                self.outputs.push(value);
+            }
+            Opcode::JumpIfTrue | Opcode::JumpIfFalse => {
+                let condition = get(0)?
+                    .as_constant()
+                    .ok_or(SymbolicError::SymbolicBranch { ip })?;
+
+                if (condition != 0) == (op == Opcode::JumpIfTrue) {
+                    let target = get(1)?
+                        .as_constant()
+                        .ok_or(SymbolicError::SymbolicAddress { ip })?;
+                    next = usize::try_from(target)
+                        .map_err(|_| IntcodeError::IpOutOfRange { ip: target })?;
+                }
+            }
+            Opcode::AdjustRelativeBase => {
+                let offset = get(0)?
+                    .as_constant()
+                    .ok_or(SymbolicError::SymbolicAddress { ip })?;
+                self.relative_base = self.relative_base.wrapping_add(offset);
+            }
+            Opcode::Halt => return Ok(false),
+        }
+
+        self.ip = next;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ProgramState;
+
+    /// Like a day 2 program: computes `[0] = 5 * noun + verb + 1000`, where
+    /// the noun and verb are the cells at 1 and 2, after reading from the
+    /// addresses they point to.
+    const PROGRAM: [isize; 17] = [1, 0, 0, 3, 1002, 1, 5, 3, 1, 3, 2, 0, 1001, 0, 1000, 0, 99];
+
+    fn linear(constant: isize, terms: &[(Symbol, isize)]) -> Linear {
+        Linear {
+            constant,
+            terms: terms.iter().copied().collect(),
+        }
+    }
+
+    fn symbolic(program: &[isize], target: isize) -> Option<(isize, isize)> {
+        let mut machine = SymbolicMachine::new(program);
+        machine.set_symbolic(1);
+        machine.set_symbolic(2);
+        machine.run(100).unwrap();
+
+        let Value::Linear(result) = machine.read(0) else {
+            panic!("[0] is not linear: {}", machine.read(0));
+        };
+
+        let values = result.solve(target, 0..=99)?;
+        Some((values[&Symbol::Cell(1)], values[&Symbol::Cell(2)]))
+    }
+
+    fn brute_force(program: &[isize], target: isize) -> Option<(isize, isize)> {
+        (0..=99)
+            .flat_map(|noun| (0..=99).map(move |verb| (noun, verb)))
+            .find(|(noun, verb)| {
+                let mut program = program.to_vec();
+                program[1] = *noun;
+                program[2] = *verb;
+
+                let mut state = ProgramState::new_empty(&program);
+                state.run_to_exit().unwrap();
+                state.program()[0] == target
+            })
+    }
+
+    #[test]
+    fn solve() {
+        let (a, b) = (Symbol::Cell(1), Symbol::Input(0));
+
+        let expression = linear(1, &[(a, 3), (b, 2)]);
+        assert_eq!(
+            expression.solve(20, 0..=9),
+            Some(BTreeMap::from([(a, 1), (b, 8)]))
+        );
+        assert_eq!(expression.evaluate(&BTreeMap::from([(a, 1), (b, 8)])), 20);
+
+        // 2 * a is never odd, and a = 50 is outside of the domain.
+        assert_eq!(linear(0, &[(a, 2)]).solve(7, 0..=99), None);
+        assert_eq!(linear(0, &[(a, 1)]).solve(50, 0..=9), None);
+        assert_eq!(
+            linear(-4, &[(a, -1)]).solve(-9, 0..=9),
+            Some(BTreeMap::from([(a, 5)]))
+        );
+
+        assert_eq!(Linear::constant(5).solve(5, 0..=9), Some(BTreeMap::new()));
+        assert_eq!(Linear::constant(5).solve(6, 0..=9), None);
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        let mut machine = SymbolicMachine::new(&PROGRAM);
+        machine.set_symbolic(1);
+        machine.set_symbolic(2);
+        machine.run(100).unwrap();
+
+        let (noun, verb) = (Symbol::Cell(1), Symbol::Cell(2));
+        assert_eq!(
+            machine.read(0),
+            Value::Linear(linear(1000, &[(noun, 5), (verb, 1)]))
+        );
+        // Read from the addresses given by the noun and verb.
+        assert_eq!(machine.read(3), Value::Linear(linear(0, &[(noun, 5)])));
+
+        for target in [1000, 1227, 1594, 1595, 1596, 2000] {
+            assert_eq!(
+                symbolic(&PROGRAM, target),
+                brute_force(&PROGRAM, target),
+                "{target}"
+            );
+        }
+        assert_eq!(symbolic(&PROGRAM, 1227), Some((26, 97)));
+        assert_eq!(symbolic(&PROGRAM, 1594), Some((99, 99)));
+        assert_eq!(symbolic(&PROGRAM, 1595), None);
+    }
+
+    #[test]
+    fn symbolic_control_flow() {
+        let run = |program: &[isize], symbolic: usize| {
+            let mut machine = SymbolicMachine::new(program);
+            machine.set_symbolic(symbolic);
+            machine.run(100)
+        };
+
+        // Jump to 4 if [6] is non-zero.
+        let branch = [1005, 6, 4, 99, 99, 99, 1];
+        assert_eq!(
+            run(&branch, 6),
+            Err(SymbolicError::SymbolicBranch { ip: 0 })
+        );
+        assert_eq!(
+            run(&branch, 0),
+            Err(SymbolicError::SymbolicOpcode { ip: 0 })
+        );
+
+        // Jump to [6], or move the relative base by it.
+        let target = [105, 1, 6, 99, 99, 99, 3];
+        assert_eq!(
+            run(&target, 6),
+            Err(SymbolicError::SymbolicAddress { ip: 0 })
+        );
+        let base = [9, 3, 99, 1];
+        assert_eq!(run(&base, 3), Err(SymbolicError::SymbolicAddress { ip: 0 }));
+
+        // Without symbols, the same programs run as usual.
+        assert_eq!(SymbolicMachine::new(&branch).run(100), Ok(()));
+        assert_eq!(SymbolicMachine::new(&base).run(100), Ok(()));
+        assert_eq!(SymbolicMachine::new(&target).run(100), Ok(()));
+    }
+
+    #[test]
+    fn out_of_fuel() {
+        let forever = [1105, 1, 0];
+        assert_eq!(
+            SymbolicMachine::new(&forever).run(1000),
+            Err(SymbolicError::OutOfFuel)
+        );
+
+        // The halt instruction takes fuel too.
+        let add = [1101, 1, 2, 0, 99];
+        assert_eq!(
+            SymbolicMachine::new(&add).run(1),
+            Err(SymbolicError::OutOfFuel)
+        );
+
+        let mut machine = SymbolicMachine::new(&add);
+        assert_eq!(machine.run(2), Ok(()));
+        assert_eq!(machine.read(0).as_constant(), Some(3));
+    }
+}
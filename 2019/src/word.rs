@@ -0,0 +1,103 @@
+//! The values Intcode programs compute with.
+
+use std::{fmt::Debug, fmt::Display, str::FromStr};
+
+pub use crate::bigint::BigInt;
+
+/// A value stored in a single Intcode memory cell.
+///
+/// Unchecked arithmetic wraps on overflow; checked arithmetic returns `None`
+/// instead.
+pub trait Word: Clone + Debug + Display + FromStr + PartialEq + PartialOrd {
+    fn from_isize(value: isize) -> Self;
+
+    /// Convert to an `isize`, if the value fits.
+    fn to_isize(&self) -> Option<isize>;
+
+    fn is_zero(&self) -> bool;
+
+    fn wrapping_add(&self, rhs: &Self) -> Self;
+
+    fn wrapping_mul(&self, rhs: &Self) -> Self;
+
+    fn checked_add(&self, rhs: &Self) -> Option<Self>;
+
+    fn checked_mul(&self, rhs: &Self) -> Option<Self>;
+}
+
+macro_rules! impl_word {
+    ($($ty:ty),*) => {
+        $(
+            impl Word for $ty {
+                #[inline]
+                fn from_isize(value: isize) -> Self {
+                    value as _
+                }
+
+                #[inline]
+                fn to_isize(&self) -> Option<isize> {
+                    isize::try_from(*self).ok()
+                }
+
+                #[inline]
+                fn is_zero(&self) -> bool {
+                    *self == 0
+                }
+
+                #[inline]
+                fn wrapping_add(&self, rhs: &Self) -> Self {
+                    <$ty>::wrapping_add(*self, *rhs)
+                }
+
+                #[inline]
+                fn wrapping_mul(&self, rhs: &Self) -> Self {
+                    <$ty>::wrapping_mul(*self, *rhs)
+                }
+
+                #[inline]
+                fn checked_add(&self, rhs: &Self) -> Option<Self> {
+                    <$ty>::checked_add(*self, *rhs)
+                }
+
+                #[inline]
+                fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+                    <$ty>::checked_mul(*self, *rhs)
+                }
+            }
+        )*
+    };
+}
+
+impl_word!(isize, i64, i128);
+
+impl Word for BigInt {
+    fn from_isize(value: isize) -> Self {
+        BigInt::from(value as i128)
+    }
+
+    fn to_isize(&self) -> Option<isize> {
+        i128::try_from(self)
+            .ok()
+            .and_then(|v| isize::try_from(v).ok())
+    }
+
+    fn is_zero(&self) -> bool {
+        BigInt::is_zero(self)
+    }
+
+    fn wrapping_add(&self, rhs: &Self) -> Self {
+        self + rhs
+    }
+
+    fn wrapping_mul(&self, rhs: &Self) -> Self {
+        self * rhs
+    }
+
+    fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        Some(self + rhs)
+    }
+
+    fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+        Some(self * rhs)
+    }
+}
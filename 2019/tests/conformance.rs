@@ -0,0 +1,215 @@
+//! The example programs from the 2019 puzzle descriptions, and a few more
+//! covering the corners of the Intcode specification.
+
+use aoc2019::{
+    asm::{assemble, to_source},
+    word::BigInt,
+    IntcodeError, Program, ProgramState, RunState,
+};
+
+fn parse(program: &str) -> Program {
+    program.parse().unwrap()
+}
+
+/// Run `program` to completion with `inputs`, returning its outputs.
+fn outputs(program: &str, inputs: &[isize]) -> Vec<isize> {
+    let mut state = ProgramState::new_multi_input(inputs.to_vec(), parse(program));
+    state.run_to_exit().unwrap();
+    state.drain_outputs().collect()
+}
+
+/// Run `program` to completion, returning its final memory.
+fn memory(program: &str) -> Vec<isize> {
+    let mut state = ProgramState::new_empty(&parse(program));
+    state.run_to_exit().unwrap();
+    state.program().to_vec()
+}
+
+#[test]
+fn day2_arithmetic() {
+    assert_eq!(
+        memory("1,9,10,3,2,3,11,0,99,30,40,50"),
+        [3500, 9, 10, 70, 2, 3, 11, 0, 99, 30, 40, 50]
+    );
+    assert_eq!(memory("1,0,0,0,99"), [2, 0, 0, 0, 99]);
+    assert_eq!(memory("2,3,0,3,99"), [2, 3, 0, 6, 99]);
+    assert_eq!(memory("2,4,4,5,99,0"), [2, 4, 4, 5, 99, 9801]);
+    assert_eq!(memory("1,1,1,4,99,5,6,0,99"), [30, 1, 1, 4, 2, 5, 6, 0, 99]);
+}
+
+#[test]
+fn day5_io_and_modes() {
+    assert_eq!(outputs("3,0,4,0,99", &[1234]), [1234]);
+    assert_eq!(memory("1002,4,3,4,33"), [1002, 4, 3, 4, 99]);
+    assert_eq!(memory("1101,100,-1,4,0"), [1101, 100, -1, 4, 99]);
+}
+
+#[test]
+fn comparisons() {
+    let equal_position = "3,9,8,9,10,9,4,9,99,-1,8";
+    let less_position = "3,9,7,9,10,9,4,9,99,-1,8";
+    let equal_immediate = "3,3,1108,-1,8,3,4,3,99";
+    let less_immediate = "3,3,1107,-1,8,3,4,3,99";
+    // The same comparisons, reading their operands relative to base 100.
+    let equal_relative = "109,100,203,0,22208,0,1,2,204,2,99";
+    let less_relative = "109,100,203,0,22207,0,1,2,204,2,99";
+
+    for input in [-8, 7, 8, 9] {
+        let equal = (input == 8) as isize;
+        let less = (input < 8) as isize;
+
+        assert_eq!(outputs(equal_position, &[input]), [equal]);
+        assert_eq!(outputs(less_position, &[input]), [less]);
+        assert_eq!(outputs(equal_immediate, &[input]), [equal]);
+        assert_eq!(outputs(less_immediate, &[input]), [less]);
+
+        // The relative variants compare against the 8 stored at 101.
+        let with_eight = |program: &str| {
+            let mut state = ProgramState::new(input, &parse(program));
+            state.write(101, 8);
+            state.run_to_exit().unwrap();
+            state.drain_outputs().collect::<Vec<_>>()
+        };
+        assert_eq!(with_eight(equal_relative), [equal]);
+        assert_eq!(with_eight(less_relative), [less]);
+    }
+}
+
+#[test]
+fn jumps() {
+    let position = "3,12,6,12,15,1,13,14,13,4,13,99,-1,0,1,9";
+    let immediate = "3,3,1105,-1,9,1101,0,0,12,4,12,99,1";
+
+    for input in [-1, 0, 1, 42] {
+        let expected = (input != 0) as isize;
+        assert_eq!(outputs(position, &[input]), [expected]);
+        assert_eq!(outputs(immediate, &[input]), [expected]);
+    }
+
+    let compare_to_8 = "3,21,1008,21,8,20,1005,20,22,107,8,21,20,1006,20,31,\
+        1106,0,36,98,0,0,1002,21,125,20,4,20,1105,1,46,104,\
+        999,1105,1,46,1101,1000,1,20,4,20,1105,1,46,98,99";
+    assert_eq!(outputs(compare_to_8, &[7]), [999]);
+    assert_eq!(outputs(compare_to_8, &[8]), [1000]);
+    assert_eq!(outputs(compare_to_8, &[9]), [1001]);
+}
+
+#[test]
+fn quine() {
+    let quine = "109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99";
+    assert_eq!(outputs(quine, &[]), parse(quine).into_vec());
+}
+
+#[test]
+fn large_numbers() {
+    assert_eq!(
+        outputs("1102,34915192,34915192,7,4,7,99,0", &[]),
+        [1219070632396864]
+    );
+    assert_eq!(outputs("104,1125899906842624,99", &[]), [1125899906842624]);
+}
+
+#[test]
+fn relative_base() {
+    // From the description: with a relative base of 2000, `109,19` followed
+    // by `204,-34` outputs the value at 1985.
+    let mut state = ProgramState::new_empty(&parse("109,2000,109,19,204,-34,99"));
+    state.write(1985, 42);
+    state.run_to_exit().unwrap();
+    assert_eq!(state.relative_base(), &2019);
+    assert_eq!(state.drain_outputs().collect::<Vec<_>>(), [42]);
+
+    // Relative writes past the end of the program, and a negative base.
+    assert_eq!(outputs("109,10,21101,3,4,0,204,0,99", &[]), [7]);
+    assert_eq!(outputs("109,-5,21201,14,0,20,204,20,99,77", &[]), [77]);
+}
+
+#[test]
+fn input_and_halting() {
+    let mut state = ProgramState::new_empty(&parse("3,0,4,0,99"));
+    assert_eq!(state.run(), Ok(RunState::NeedsInput));
+    assert_eq!(state.ip(), 0);
+
+    state.add_input(5);
+    assert_eq!(state.poll(), Ok(RunState::Output(5)));
+    assert_eq!(state.poll(), Ok(RunState::Halted));
+    assert_eq!(state.poll(), Ok(RunState::Halted));
+    assert_eq!(state.instructions(), 2);
+}
+
+#[test]
+fn errors() {
+    let run = |program: &str| ProgramState::new_empty(&parse(program)).run();
+
+    assert_eq!(
+        run("1,0,0,0,42"),
+        Err(IntcodeError::UnknownOpcode { ip: 4, opcode: 42 })
+    );
+    assert_eq!(
+        run("1101,1,1,0,3001,0,0,0"),
+        Err(IntcodeError::InvalidParamMode { ip: 4, mode: 3 })
+    );
+    assert_eq!(
+        run("11101,1,1,0,99"),
+        Err(IntcodeError::ImmediateWrite { ip: 0 })
+    );
+    assert_eq!(
+        run("1,-1,0,0,99"),
+        Err(IntcodeError::NegativeAddress { ip: 0, address: -1 })
+    );
+    assert_eq!(
+        run("1105,1,100"),
+        Err(IntcodeError::IpOutOfRange { ip: 100 })
+    );
+
+    let mut checked = ProgramState::new_empty(&parse("1102,4611686018427387904,2,0,99"));
+    checked.set_checked(true);
+    assert_eq!(checked.run(), Err(IntcodeError::Overflow { ip: 0 }));
+}
+
+#[test]
+fn big_words() {
+    let program: Vec<BigInt> = parse("1102,34915192,34915192,11,1002,11,34915192,11,4,11,99,0")
+        .iter()
+        .map(|v| BigInt::from(*v as i128))
+        .collect();
+
+    let mut state = ProgramState::<BigInt>::with_memory(Vec::new(), program);
+    state.run_to_exit().unwrap();
+
+    let output = state.take_output().unwrap();
+    assert_eq!(output.to_string(), "42564085191697926757888");
+}
+
+#[test]
+fn assembler_round_trip() {
+    let source = "
+        ; Sum the inputs until a zero is read.
+        loop:   IN value
+                JZ value, #done
+                ADD sum, value, sum
+                JZ #0, #loop
+        done:   OUT sum
+                ARB #3
+                OUT @rb-3
+                HLT
+        value:  data 0
+        sum:    data 0
+    ";
+
+    let program = assemble(source).unwrap();
+    assert_eq!(assemble(&to_source(&program)).unwrap(), program);
+
+    let mut state = ProgramState::new_multi_input(vec![1, 2, 3, 0], program);
+    state.run_to_exit().unwrap();
+    assert_eq!(state.drain_outputs().collect::<Vec<_>>(), [6, 3]);
+
+    // Programs that don't disassemble cleanly survive the round trip too.
+    for program in [
+        "1,9,10,3,2,3,11,0,99,30,40,50",
+        "104,1125899906842624,99,-5,7",
+    ] {
+        let program = parse(program).into_vec();
+        assert_eq!(assemble(&to_source(&program)).unwrap(), program);
+    }
+}
@@ -0,0 +1,141 @@
+//! Checks that [`Interpreter::Cached`] behaves exactly like
+//! [`Interpreter::Decoding`], on the examples from the puzzle descriptions
+//! and on every Intcode puzzle input found in `inputs/`.
+
+use aoc2019::{
+    network::{Network, NetworkStop, Topology},
+    Interpreter, Program, ProgramState,
+};
+
+/// Run `program` with `inputs` on both interpreters and compare outputs,
+/// memory and the final state.
+fn compare(name: &str, program: &[isize], inputs: &[isize]) {
+    let run = |interpreter| {
+        let mut state = ProgramState::new_multi_input(inputs.to_vec(), program.to_vec());
+        state.set_interpreter(interpreter);
+        let result = state.run();
+        (result, state)
+    };
+
+    let (decoding_result, mut decoding) = run(Interpreter::Decoding);
+    let (cached_result, mut cached) = run(Interpreter::Cached);
+
+    assert_eq!(decoding_result, cached_result, "{name}: result");
+    assert_eq!(
+        decoding.instructions(),
+        cached.instructions(),
+        "{name}: instructions"
+    );
+    assert_eq!(decoding.snapshot(), cached.snapshot(), "{name}: state");
+    assert!(
+        decoding.drain_outputs().eq(cached.drain_outputs()),
+        "{name}: outputs"
+    );
+}
+
+/// Run five copies of `program` as a day 7 feedback loop with phase
+/// `settings` on both interpreters, and compare every amplifier.
+fn compare_feedback(name: &str, program: &[isize], settings: [isize; 5]) {
+    let run = |interpreter| {
+        let amplifiers = settings
+            .iter()
+            .map(|setting| {
+                let mut state = ProgramState::new(*setting, program);
+                state.set_interpreter(interpreter);
+                state
+            })
+            .collect();
+
+        let mut network = Network::new(amplifiers, Topology::Ring);
+        network.send(0, 0);
+        let result = network.run();
+        (result, network)
+    };
+
+    let (decoding_result, decoding) = run(Interpreter::Decoding);
+    let (cached_result, cached) = run(Interpreter::Cached);
+
+    assert_eq!(decoding_result, Ok(NetworkStop::Halted), "{name}: result");
+    assert_eq!(decoding_result, cached_result, "{name}: result");
+    assert_eq!(decoding.outputs(), cached.outputs(), "{name}: outputs");
+
+    for (idx, (decoding, cached)) in decoding.machines().zip(cached.machines()).enumerate() {
+        assert_eq!(
+            decoding.instructions(),
+            cached.instructions(),
+            "{name}: amplifier {idx} instructions"
+        );
+        assert_eq!(
+            decoding.snapshot(),
+            cached.snapshot(),
+            "{name}: amplifier {idx} state"
+        );
+    }
+}
+
+fn parse(program: &str) -> Program {
+    program.parse().unwrap()
+}
+
+#[test]
+fn examples() {
+    let quine = parse("109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99");
+    compare("quine", &quine, &[]);
+
+    let compare_to_8 = parse(
+        "3,21,1008,21,8,20,1005,20,22,107,8,21,20,1006,20,31,1106,0,36,98,0,0,1002,21,125,20,4,20,1105,1,46,104,999,1105,1,46,1101,1000,1,20,4,20,1105,1,46,98,99",
+    );
+    for input in 6..=10 {
+        compare("compare to 8", &compare_to_8, &[input]);
+    }
+
+    // Increments the operand of the `OUT` at 0 until it reaches 10.
+    let self_modifying = parse("104,7,1001,1,1,1,1007,1,10,20,1005,20,0,99");
+    compare("self-modifying", &self_modifying, &[]);
+
+    let missing_input = parse("3,0,3,0,99");
+    compare("missing input", &missing_input, &[1]);
+
+    // The first parameter is a negative address, which is reported before
+    // the invalid mode of the third.
+    let error_order = parse("30201,-4,0,0,99");
+    compare("error order", &error_order, &[]);
+
+    let feedback = parse(
+        "3,26,1001,26,-4,26,3,27,1002,27,2,27,1,27,26,27,4,27,1001,28,-1,28,1005,28,6,99,0,0,5",
+    );
+    compare_feedback("feedback", &feedback, [9, 8, 7, 6, 5]);
+}
+
+#[test]
+fn puzzle_inputs() {
+    // Inputs for every Intcode day, in the order they are given.
+    let days: &[(u32, &[&[isize]])] = &[
+        (2, &[&[]]),
+        (5, &[&[1], &[5]]),
+        (7, &[&[0, 0], &[4, 0], &[9, 0]]),
+        (9, &[&[1], &[2]]),
+    ];
+
+    for (day, runs) in days {
+        let path = format!("{}/inputs/day{day}.txt", env!("CARGO_MANIFEST_DIR"));
+        if !std::path::Path::new(&path).exists() {
+            eprintln!("skipping day {day}: {path} not found");
+            continue;
+        }
+
+        let mut program = Program::from_file(&path).unwrap_or_else(|e| panic!("{path}: {e}"));
+        if *day == 2 {
+            program[1] = 12;
+            program[2] = 2;
+        }
+
+        for inputs in *runs {
+            compare(&format!("day {day} {inputs:?}"), &program, inputs);
+        }
+
+        if *day == 7 {
+            compare_feedback("day 7 feedback", &program, [9, 8, 7, 6, 5]);
+        }
+    }
+}
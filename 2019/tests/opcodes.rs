@@ -0,0 +1,161 @@
+//! Machines with custom opcodes registered next to, or instead of, the
+//! built-in ones.
+
+use std::{cell::RefCell, rc::Rc};
+
+use aoc2019::{
+    opcodes::Action, IntcodeError, Interpreter, ParamMode, Program, ProgramState, RunState,
+};
+
+fn parse(program: &str) -> Program {
+    program.parse().unwrap()
+}
+
+#[test]
+fn debug_print() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+
+    // `DBG a` logs `a` without producing an output.
+    let mut state = ProgramState::new_empty(&parse("10,7,110,42,4,7,99,5"));
+    let handler_log = log.clone();
+    state.register_opcode(10, "DBG", 1, move |ctx| {
+        handler_log.borrow_mut().push((ctx.ip(), ctx.get(0)?));
+        Ok(Action::Next)
+    });
+
+    state.run_to_exit().unwrap();
+    assert_eq!(*log.borrow(), [(0, 5), (2, 42)]);
+    assert_eq!(state.drain_outputs().collect::<Vec<_>>(), [5]);
+}
+
+#[test]
+fn host_call() {
+    // `CALL n, dest` stores the `n`th Fibonacci number, computed by the host.
+    let fib = |n: isize| (0..n).fold((0, 1), |(a, b), _| (b, a + b)).0;
+    let program = parse("3,11,11,11,12,4,12,99,0,0,0,0,0");
+
+    for interpreter in [Interpreter::Decoding, Interpreter::Cached] {
+        let mut state = ProgramState::new(30, &program);
+        state.set_interpreter(interpreter);
+        state.register_opcode(11, "CALL", 2, move |ctx| {
+            let n = ctx.get(0)?;
+            ctx.set(1, fib(n))?;
+            Ok(Action::Next)
+        });
+
+        state.run_to_exit().unwrap();
+        assert_eq!(state.take_output(), Some(832040));
+    }
+}
+
+#[test]
+fn replace_builtin() {
+    // Make `ADD` subtract, and count how often the new `SUB` ran.
+    let mut state = ProgramState::new_empty(&parse("1101,10,3,5,104,0,1,5,5,5,4,5,99"));
+    state.set_profiling(true);
+    state.register_opcode(1, "SUB", 3, |ctx| {
+        let (a, b) = (ctx.get(0)?, ctx.get(1)?);
+        ctx.set(2, a - b)?;
+        Ok(Action::Next)
+    });
+
+    state.run_to_exit().unwrap();
+    assert_eq!(state.drain_outputs().collect::<Vec<_>>(), [7, 0]);
+
+    let profile = state.profile().unwrap();
+    assert_eq!(profile.opcode_counts().get("SUB"), Some(&2));
+    assert_eq!(profile.opcode_counts().get("ADD"), None);
+}
+
+#[test]
+fn custom_control_flow() {
+    // `JMPD` jumps to the address in its parameter and halts on negative
+    // ones; `WAIT` takes an input and throws it away.
+    let mut state = ProgramState::new_empty(&parse("112,4,99,0,13,112,-1"));
+    state.register_opcode(12, "JMPD", 1, |ctx| match usize::try_from(ctx.get(0)?) {
+        Ok(target) => Ok(Action::Jump(target)),
+        Err(_) => Ok(Action::Halt),
+    });
+    state.register_opcode(13, "WAIT", 0, |ctx| {
+        if ctx.input().is_some() {
+            Ok(Action::Next)
+        } else {
+            Ok(Action::NeedsInput)
+        }
+    });
+
+    assert_eq!(state.run(), Ok(RunState::NeedsInput));
+    assert_eq!(state.ip(), 4);
+
+    state.add_input(0);
+    assert_eq!(state.run(), Ok(RunState::Halted));
+    assert_eq!(state.ip(), 5);
+    assert_eq!(state.instructions(), 2);
+}
+
+#[test]
+fn removed_opcodes() {
+    let mut opcodes = ProgramState::new_empty(&[]).opcodes().clone();
+    assert!(opcodes.remove(2));
+    assert!(!opcodes.remove(2));
+    assert!(opcodes.get(2).is_none());
+    assert_eq!(opcodes.get(1).unwrap().mnemonic, "ADD");
+
+    let mut state = ProgramState::new_empty(&parse("1,0,0,0,2,0,0,0,99"));
+    state.set_opcodes(opcodes);
+    assert_eq!(
+        state.run(),
+        Err(IntcodeError::UnknownOpcode { ip: 4, opcode: 2 })
+    );
+}
+
+#[test]
+fn parameters_past_the_modes() {
+    // `PEEK` reads parameters past its arity, which are the cells after it,
+    // and outputs the raw value of parameter 4.
+    let program = parse("15,0,0,0,99,42");
+
+    for interpreter in [Interpreter::Decoding, Interpreter::Cached] {
+        let mut state = ProgramState::new_empty(&program);
+        state.set_interpreter(interpreter);
+        state.register_opcode(15, "PEEK", 3, |ctx| {
+            assert_eq!(ctx.param(3)?, (ParamMode::Position, 99));
+            assert_eq!(ctx.param(25)?, (ParamMode::Position, 0));
+            Ok(Action::Output(ctx.param(4)?.1))
+        });
+
+        state.run_to_exit().unwrap();
+        assert_eq!(state.take_output(), Some(42));
+    }
+}
+
+#[test]
+fn failed_input_keeps_the_input() {
+    for interpreter in [Interpreter::Decoding, Interpreter::Cached] {
+        let mut state = ProgramState::new(7, &parse("3,-1,4,1,99"));
+        state.set_interpreter(interpreter);
+        assert_eq!(
+            state.run(),
+            Err(IntcodeError::NegativeAddress { ip: 0, address: -1 })
+        );
+
+        // Once the address is fixed, the same input is stored there.
+        state.write(1, 1);
+        assert_eq!(state.run(), Ok(RunState::Halted));
+        assert_eq!(state.take_output(), Some(7));
+
+        let mut state = ProgramState::new(7, &parse("103,0,99"));
+        state.set_interpreter(interpreter);
+        assert_eq!(state.run(), Err(IntcodeError::ImmediateWrite { ip: 0 }));
+        state.write(0, 3);
+        assert_eq!(state.run(), Ok(RunState::Halted));
+        assert_eq!(state.read(0), 7);
+    }
+}
+
+#[test]
+#[should_panic]
+fn too_many_parameters() {
+    let mut state = ProgramState::new_empty(&[]);
+    state.register_opcode(14, "WIDE", 4, |_| Ok(Action::Next));
+}
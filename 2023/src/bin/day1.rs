@@ -1,10 +1,38 @@
-use std::io::stdin;
+use std::fmt::Display;
 
-fn main() -> std::io::Result<()> {
-    let lines = stdin().lines();
+use aoc_common::Solution;
 
-    let mut total = 0;
+fn main() {
+    aoc_common::run::<Day1>();
+}
+
+struct Day1;
+
+impl Solution for Day1 {
+    type Parsed = Vec<String>;
+
+    fn parse(input: &str) -> Self::Parsed {
+        input.lines().map(str::to_string).collect()
+    }
+
+    fn part1(lines: &Self::Parsed) -> impl Display {
+        lines
+            .iter()
+            .map(|line| calibration_value(line, false))
+            .sum::<u32>()
+    }
+
+    fn part2(lines: &Self::Parsed) -> impl Display {
+        lines
+            .iter()
+            .map(|line| calibration_value(line, true))
+            .sum::<u32>()
+    }
+}
 
+/// The number formed by the first and last digit in `line`, also counting
+/// digits that are spelled out if `spelled` is set.
+fn calibration_value(line: &str, spelled: bool) -> u32 {
     let string_reprs = [
         ("one", 1),
         ("two", 2),
@@ -17,52 +45,43 @@ fn main() -> std::io::Result<()> {
         ("nine", 9),
     ];
 
-    for line in lines {
-        let line = line?;
-
-        let mut chars = line.chars().peekable();
-        let mut start_digit = None;
-        let mut end_digit = None;
-
-        while let Some(c) = chars.peek() {
-            let mut digit_value = None;
-
-            if c.is_ascii_digit() {
-                digit_value = Some(*c as u32 - '0' as u32);
-            } else {
-                for (substr, value) in string_reprs {
-                    if chars
-                        .clone()
-                        .zip(substr.chars())
-                        .filter(|(a, b)| a == b)
-                        .count()
-                        == substr.len()
-                    {
-                        digit_value = Some(value);
-                        break;
-                    }
-                }
-            }
+    let mut chars = line.chars().peekable();
+    let mut start_digit = None;
+    let mut end_digit = None;
 
-            if let Some(digit_value) = digit_value {
-                if start_digit.is_none() {
-                    start_digit = Some(digit_value);
+    while let Some(c) = chars.peek() {
+        let mut digit_value = None;
+
+        if c.is_ascii_digit() {
+            digit_value = Some(*c as u32 - '0' as u32);
+        } else if spelled {
+            for (substr, value) in string_reprs {
+                if chars
+                    .clone()
+                    .zip(substr.chars())
+                    .filter(|(a, b)| a == b)
+                    .count()
+                    == substr.len()
+                {
+                    digit_value = Some(value);
+                    break;
                 }
+            }
+        }
 
-                end_digit = Some(digit_value);
+        if let Some(digit_value) = digit_value {
+            if start_digit.is_none() {
+                start_digit = Some(digit_value);
             }
 
-            chars.next();
+            end_digit = Some(digit_value);
         }
 
-        let first_digit = start_digit.unwrap();
-        let last_digit = end_digit.unwrap();
-
-        total += (first_digit * 10) + last_digit;
-        println!("{}: {}", line, first_digit * 10 + last_digit);
+        chars.next();
     }
 
-    println!("{}", total);
+    let first_digit = start_digit.unwrap();
+    let last_digit = end_digit.unwrap();
 
-    Ok(())
+    (first_digit * 10) + last_digit
 }
@@ -1,4 +1,6 @@
-use std::fmt::Write;
+use std::fmt::{Display, Write};
+
+use aoc_common::{Solution, Unsolved};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Direction {
@@ -216,13 +218,13 @@ impl Map {
         };
 
         let above_pipes = &[Pipe::Vertical, Pipe::SouthAndEast, Pipe::SouthAndWest];
-        let above = (r > 0).then(|| ch(r - 1, c, above_pipes)).unwrap_or(false);
+        let above = r > 0 && ch(r - 1, c, above_pipes);
 
         let below_pipes = &[Pipe::Vertical, Pipe::NorthAndEast, Pipe::NorthAndWest];
         let below = ch(r + 1, c, below_pipes);
 
         let left_pipes = &[Pipe::Horiztonal, Pipe::NorthAndWest, Pipe::SouthAndWest];
-        let left = (c > 0).then(|| ch(r, c - 1, left_pipes)).unwrap_or(false);
+        let left = c > 0 && ch(r, c - 1, left_pipes);
 
         let right_pipes = &[Pipe::Horiztonal, Pipe::NorthAndEast, Pipe::SouthAndEast];
         let right = ch(r, c + 1, right_pipes);
@@ -307,19 +309,30 @@ impl std::fmt::Display for Map {
     }
 }
 
-fn main() -> std::io::Result<()> {
-    let lines = std::io::stdin().lines().map(|v| v.unwrap());
+fn main() {
+    aoc_common::run::<Day10>();
+}
 
-    let mut tiles = Vec::new();
+struct Day10;
 
-    for line in lines {
-        tiles.push(line.chars().map(|v| Tile::try_from(v).unwrap()).collect());
-    }
+impl Solution for Day10 {
+    type Parsed = Map;
 
-    let map = Map::new(tiles);
+    fn parse(input: &str) -> Self::Parsed {
+        let mut tiles = Vec::new();
 
-    let steps = map.walk().count();
-    println!("{}", steps / 2);
+        for line in input.lines() {
+            tiles.push(line.chars().map(|v| Tile::try_from(v).unwrap()).collect());
+        }
 
-    Ok(())
+        Map::new(tiles)
+    }
+
+    fn part1(map: &Self::Parsed) -> impl Display {
+        map.walk().count() / 2
+    }
+
+    fn part2(_: &Self::Parsed) -> impl Display {
+        Unsolved
+    }
 }
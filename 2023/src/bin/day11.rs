@@ -1,4 +1,6 @@
-use std::fmt::Write;
+use std::fmt::{Display, Write};
+
+use aoc_common::Solution;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Galaxy(pub usize, pub usize);
@@ -107,26 +109,37 @@ fn sum_of_paths(universe: &Universe) -> usize {
     universe.pairs().map(|(g1, g2)| g1.shortest_path(&g2)).sum()
 }
 
-fn main() -> std::io::Result<()> {
-    let lines = std::io::stdin().lines().map(|v| v.unwrap());
+fn main() {
+    aoc_common::run::<Day11>();
+}
+
+struct Day11;
 
-    let mut galaxies = Vec::new();
-    for (row, line) in lines.enumerate() {
-        for (col, char) in line.chars().enumerate() {
-            if char == '#' {
-                galaxies.push(Galaxy(row, col));
+impl Solution for Day11 {
+    type Parsed = Universe;
+
+    fn parse(input: &str) -> Self::Parsed {
+        let mut galaxies = Vec::new();
+        for (row, line) in input.lines().enumerate() {
+            for (col, char) in line.chars().enumerate() {
+                if char == '#' {
+                    galaxies.push(Galaxy(row, col));
+                }
             }
         }
-    }
-
-    let mut universe = Universe::new(galaxies);
-    let mut older_universe = universe.clone();
 
-    universe.expand();
-    older_universe.expand_by(1_000_000);
+        Universe::new(galaxies)
+    }
 
-    println!("{}", sum_of_paths(&universe));
-    println!("{}", sum_of_paths(&older_universe));
+    fn part1(universe: &Self::Parsed) -> impl Display {
+        let mut universe = universe.clone();
+        universe.expand();
+        sum_of_paths(&universe)
+    }
 
-    Ok(())
+    fn part2(universe: &Self::Parsed) -> impl Display {
+        let mut older_universe = universe.clone();
+        older_universe.expand_by(1_000_000);
+        sum_of_paths(&older_universe)
+    }
 }
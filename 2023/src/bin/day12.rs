@@ -1,4 +1,6 @@
-use indicatif::ProgressBar;
+use std::fmt::Display;
+
+use aoc_common::Solution;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SpringRecord {
@@ -16,8 +18,7 @@ pub struct SpringRecordAndCounts {
 impl SpringRecordAndCounts {
     pub fn valid_arrangement_count(&self) -> usize {
         let mut records = self.records.clone();
-        let len = Self::valid_arrangements_impl(None, &mut records, &self.counts);
-        len
+        Self::valid_arrangements_impl(None, &mut records, &self.counts)
     }
 
     fn valid_arrangements_impl(
@@ -88,50 +89,60 @@ impl SpringRecordAndCounts {
 }
 
 fn main() {
-    let lines = std::io::stdin().lines().map(|v| v.unwrap());
-
-    let records: Vec<_> = lines
-        .map(|line| {
-            let (springs, counts) = line.split_once(' ').unwrap();
-
-            let springs: Vec<_> = springs
-                .chars()
-                .map(|v| match v {
-                    '?' => SpringRecord::Unknown,
-                    '.' => SpringRecord::Operational,
-                    '#' => SpringRecord::Damaged,
-                    _ => panic!(),
-                })
-                .collect();
-
-            let counts: Vec<_> = counts.split(',').map(|v| v.parse().unwrap()).collect();
-
-            SpringRecordAndCounts {
-                records: springs,
-                counts,
-            }
-        })
-        .collect();
+    aoc_common::run::<Day12>();
+}
+
+struct Day12;
+
+impl Solution for Day12 {
+    type Parsed = Vec<SpringRecordAndCounts>;
+
+    fn parse(input: &str) -> Self::Parsed {
+        input
+            .lines()
+            .map(|line| {
+                let (springs, counts) = line.split_once(' ').unwrap();
+
+                let springs: Vec<_> = springs
+                    .chars()
+                    .map(|v| match v {
+                        '?' => SpringRecord::Unknown,
+                        '.' => SpringRecord::Operational,
+                        '#' => SpringRecord::Damaged,
+                        _ => panic!(),
+                    })
+                    .collect();
+
+                let counts: Vec<_> = counts.split(',').map(|v| v.parse().unwrap()).collect();
 
-    part1(&records);
-    part2(&records);
+                SpringRecordAndCounts {
+                    records: springs,
+                    counts,
+                }
+            })
+            .collect()
+    }
+
+    fn part1(records: &Self::Parsed) -> impl Display {
+        part1(records)
+    }
+
+    fn part2(records: &Self::Parsed) -> impl Display {
+        part2(records)
+    }
 }
 
-fn part1(records: &[SpringRecordAndCounts]) {
-    let total: usize = records
+fn part1(records: &[SpringRecordAndCounts]) -> usize {
+    records
         .iter()
         .map(SpringRecordAndCounts::valid_arrangement_count)
-        .sum();
-
-    println!("Part 1: {total}");
+        .sum()
 }
 
-fn part2(records: &[SpringRecordAndCounts]) {
-    let total: usize = records
+fn part2(records: &[SpringRecordAndCounts]) -> usize {
+    records
         .iter()
         .map(SpringRecordAndCounts::unfold)
         .map(|s| s.valid_arrangement_count())
-        .sum();
-
-    println!("Part 2: {total}");
+        .sum()
 }
@@ -1,4 +1,6 @@
-use std::{collections::HashMap, ops::Range};
+use std::{collections::HashMap, fmt::Display, ops::Range};
+
+use aoc_common::Solution;
 
 #[derive(Debug, Clone)]
 pub struct Workflow {
@@ -101,21 +103,35 @@ impl From<[usize; 4]> for Part {
 }
 
 fn main() {
-    let lines: Vec<_> = std::io::stdin().lines().map(|v| v.unwrap()).collect();
+    aoc_common::run::<Day19>();
+}
+
+struct Day19;
+
+impl Solution for Day19 {
+    /// The workflows by name, and the parts to sort.
+    type Parsed = (HashMap<WorkflowName, Workflow>, Vec<Part>);
 
-    let (workflows, ratings) = parse(&lines);
+    fn parse(input: &str) -> Self::Parsed {
+        parse(input)
+    }
+
+    fn part1((workflows, parts): &Self::Parsed) -> impl Display {
+        part1(workflows, parts)
+    }
 
-    part1(&workflows, &ratings);
-    part2(&workflows);
+    fn part2((workflows, _): &Self::Parsed) -> impl Display {
+        part2(workflows)
+    }
 }
 
-fn part1(workflows: &HashMap<WorkflowName, Workflow>, parts: &[Part]) {
+fn part1(workflows: &HashMap<WorkflowName, Workflow>, parts: &[Part]) -> usize {
     let in_workflow = WorkflowName("in".to_string());
     let mut sum = 0;
     for part in parts {
         let mut workflow = workflows.get(&in_workflow).unwrap();
         loop {
-            let next = workflow.process(&part);
+            let next = workflow.process(part);
 
             if next.is_accepted() {
                 sum += part.x + part.m + part.a + part.s;
@@ -128,10 +144,10 @@ fn part1(workflows: &HashMap<WorkflowName, Workflow>, parts: &[Part]) {
         }
     }
 
-    println!("Part 1: {sum}");
+    sum
 }
 
-fn part2<'a>(workflows: &HashMap<WorkflowName, Workflow>) {
+fn part2(workflows: &HashMap<WorkflowName, Workflow>) -> usize {
     const TOTAL: usize = 4000 * 4000 * 4000 * 4000;
 
     fn calculate_acceptance(
@@ -143,7 +159,7 @@ fn part2<'a>(workflows: &HashMap<WorkflowName, Workflow>) {
 
         let total: usize = available_ranges.iter().map(|v| v.len()).product();
 
-        let workflow = workflows.get(&current_workflow).unwrap();
+        let workflow = workflows.get(current_workflow).unwrap();
 
         let mut rejected = 0;
         let mut accepted = 0;
@@ -252,15 +268,15 @@ fn part2<'a>(workflows: &HashMap<WorkflowName, Workflow>) {
 
     let in_workflow = WorkflowName("in".to_string());
     let mut ranges = [0..4000, 0..4000, 0..4000, 0..4000];
-    let (accepted, rejected) = calculate_acceptance(&workflows, &in_workflow, &mut ranges);
+    let (accepted, rejected) = calculate_acceptance(workflows, &in_workflow, &mut ranges);
 
     assert_eq!(accepted + rejected, TOTAL);
 
-    println!("Part 2: {accepted}");
+    accepted
 }
 
-fn parse(lines: &[String]) -> (HashMap<WorkflowName, Workflow>, Vec<Part>) {
-    let mut lines = lines.iter();
+fn parse(input: &str) -> (HashMap<WorkflowName, Workflow>, Vec<Part>) {
+    let mut lines = input.lines();
 
     let mut workflows = HashMap::new();
 
@@ -1,65 +1,75 @@
-use std::io::stdin;
+use std::fmt::Display;
 
-fn main() -> std::io::Result<()> {
-    let lines = stdin().lines();
+use aoc_common::Solution;
 
-    let mut sum = 0;
-    let mut power_sum = 0;
+fn main() {
+    aoc_common::run::<Day2>();
+}
 
-    for line in lines {
-        let line = line?;
+/// A game, with the fewest cubes of each colour it could have been played
+/// with.
+#[derive(Debug, Clone)]
+struct Game {
+    number: usize,
+    min_red: usize,
+    min_green: usize,
+    min_blue: usize,
+}
 
-        let (game, grabs) = line.split_once(':').unwrap();
-        let game_n: usize = game.split_once(' ').unwrap().1.parse().unwrap();
+struct Day2;
 
-        let grabs = grabs.split(';');
-        let mut valid = true;
+impl Solution for Day2 {
+    type Parsed = Vec<Game>;
 
-        let mut min_red = 0;
-        let mut min_green = 0;
-        let mut min_blue = 0;
+    fn parse(input: &str) -> Self::Parsed {
+        let mut games = Vec::new();
 
-        for grab in grabs {
-            let colors = grab.split(',');
-            for count_color in colors {
-                let (count, color) = count_color.trim().split_once(' ').unwrap();
+        for line in input.lines() {
+            let (game, grabs) = line.split_once(':').unwrap();
+            let number: usize = game.split_once(' ').unwrap().1.parse().unwrap();
 
-                let count: usize = count.parse().unwrap();
+            let mut min_red = 0;
+            let mut min_green = 0;
+            let mut min_blue = 0;
 
-                // Determine the max valid value for an allowed colour,
-                // and re-assing the minimum value for that color as well.
-                let max = match color {
-                    "red" => {
-                        min_red = min_red.max(count);
-                        12
-                    }
-                    "green" => {
-                        min_green = min_green.max(count);
-                        13
-                    }
-                    "blue" => {
-                        min_blue = min_blue.max(count);
-                        14
-                    }
-                    _ => panic!(),
-                };
+            for grab in grabs.split(';') {
+                for count_color in grab.split(',') {
+                    let (count, color) = count_color.trim().split_once(' ').unwrap();
 
-                if count > max {
-                    valid = false;
+                    let count: usize = count.parse().unwrap();
+
+                    match color {
+                        "red" => min_red = min_red.max(count),
+                        "green" => min_green = min_green.max(count),
+                        "blue" => min_blue = min_blue.max(count),
+                        _ => panic!(),
+                    }
                 }
             }
-        }
 
-        if valid {
-            sum += game_n;
+            games.push(Game {
+                number,
+                min_red,
+                min_green,
+                min_blue,
+            });
         }
 
-        let power = min_red * min_green * min_blue;
-        power_sum += power;
+        games
     }
 
-    println!("Sum: {sum}");
-    println!("Power sum: {power_sum}");
+    fn part1(games: &Self::Parsed) -> impl Display {
+        games
+            .iter()
+            .filter(|g| g.min_red <= 12 && g.min_green <= 13 && g.min_blue <= 14)
+            .map(|g| g.number)
+            .sum::<usize>()
+    }
 
-    Ok(())
+    fn part2(games: &Self::Parsed) -> impl Display {
+        games
+            .iter()
+            .map(|g| g.min_red * g.min_green * g.min_blue)
+            .sum::<usize>()
+    }
 }
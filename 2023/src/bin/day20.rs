@@ -1,29 +1,51 @@
-use std::collections::{HashMap, VecDeque};
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::Display,
+};
+
+use aoc_common::{Solution, Unsolved};
 
 fn main() {
-    let lines: Vec<_> = std::io::stdin().lines().map(|v| v.unwrap()).collect();
+    aoc_common::run::<Day20>();
+}
+
+struct Day20;
+
+impl Solution for Day20 {
+    /// The broadcaster, before the button is pressed.
+    type Parsed = Broadcaster;
+
+    fn parse(input: &str) -> Self::Parsed {
+        let lines: Vec<_> = input.lines().collect();
+
+        let broadcast_dests: Vec<_> = lines[0]
+            .split_once(" -> ")
+            .unwrap()
+            .1
+            .split(", ")
+            .map(|v| v.to_string())
+            .collect();
+
+        let mut modules = Vec::new();
 
-    let broadcast_dests: Vec<_> = lines[0]
-        .split_once(" -> ")
-        .unwrap()
-        .1
-        .split(", ")
-        .map(|v| v.to_string())
-        .collect();
+        for line in lines.iter().skip(1) {
+            let module = LogicalModule::parse(line).unwrap();
+            modules.push(module);
+        }
 
-    let mut modules = Vec::new();
+        Broadcaster::build(broadcast_dests, &modules)
+    }
 
-    for line in lines.iter().skip(1) {
-        let module = LogicalModule::parse(&line).unwrap();
-        modules.push(module);
+    fn part1(broadcaster: &Self::Parsed) -> impl Display {
+        part1(broadcaster.clone())
     }
 
-    part1(broadcast_dests.clone(), &modules);
+    fn part2(_: &Self::Parsed) -> impl Display {
+        Unsolved
+    }
 }
 
-fn part1(broadcast_dests: Vec<String>, modules: &[LogicalModule]) {
-    let mut broadcaster = Broadcaster::build(broadcast_dests, &modules);
-
+fn part1(mut broadcaster: Broadcaster) -> usize {
     for _ in 0..1000 {
         broadcaster.button();
     }
@@ -31,8 +53,7 @@ fn part1(broadcast_dests: Vec<String>, modules: &[LogicalModule]) {
     let low_pulses = broadcaster.counter_state.low;
     let high_pulses = broadcaster.counter_state.high;
 
-    println!("HI: {high_pulses}, LO: {low_pulses}");
-    println!("Part 1: {}", high_pulses * low_pulses);
+    high_pulses * low_pulses
 }
 
 #[derive(Debug, Clone)]
@@ -69,11 +90,10 @@ impl Broadcaster {
             for destination in module.destinations.iter() {
                 module_map
                     .entry(destination.to_string())
-                    .and_modify(|entry| match &mut entry.state {
-                        ModuleState::Conjunction(hash_map) => {
+                    .and_modify(|entry| {
+                        if let ModuleState::Conjunction(hash_map) = &mut entry.state {
                             hash_map.insert(module.name.to_string(), State::Low);
                         }
-                        _ => {}
                     })
                     .or_insert_with(|| Module {
                         state: ModuleState::End,
@@ -95,15 +115,11 @@ impl Broadcaster {
         // Low signal for button press
         self.counter_state.low += 1;
 
-        println!("button -low-> broadcaster");
-
         self.counter_state.low += self.destinations.len();
 
         let mut pulses = VecDeque::new();
 
         for input in self.destinations.iter_mut() {
-            println!("broadcaster -low-> {input}");
-
             let input_module = self.modules.get_mut(input).unwrap();
             if input_module.pulse(&"".to_string(), State::Low) {
                 pulses.push_back(input.to_string());
@@ -122,10 +138,7 @@ impl Broadcaster {
                 self.counter_state.high += destinations.len();
             }
 
-            let text = if output.is_low() { "-low" } else { "-high" };
             for destination in destinations {
-                println!("{pulsed} {text}-> {destination}");
-
                 let input_module = self.modules.get_mut(&destination).unwrap();
                 if input_module.pulse(&pulsed, output) {
                     pulses.push_back(destination);
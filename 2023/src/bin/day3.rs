@@ -1,4 +1,6 @@
-use std::{collections::HashMap, io::stdin};
+use std::{collections::HashMap, fmt::Display};
+
+use aoc_common::Solution;
 
 #[derive(Clone, Debug)]
 struct Number {
@@ -35,97 +37,86 @@ struct Symbol {
     line_idx: usize,
 }
 
-fn main() -> std::io::Result<()> {
-    let lines = stdin().lines().enumerate();
-
-    let mut numbers = Vec::new();
-    let mut symbols = Vec::new();
-
-    for (line_idx, line) in lines {
-        let line = line?;
-
-        let mut chars = line.chars().enumerate().peekable();
-        while let Some((idx, char)) = chars.next() {
-            if !char.is_ascii_digit() && char != '.' {
-                symbols.push(Symbol {
-                    idx,
-                    line_idx,
-                    is_gear: char == '*',
-                });
-            } else if char.is_ascii_digit() {
-                let mut value = char as u32 - '0' as u32;
-                let start_idx = idx;
-                let mut end_idx = idx;
-
-                while let Some(digit) = chars
-                    .peek()
-                    .map(|(_, v)| {
-                        if v.is_ascii_digit() {
-                            Some(*v as u32 - '0' as u32)
-                        } else {
-                            None
-                        }
+fn main() {
+    aoc_common::run::<Day3>();
+}
+
+struct Day3;
+
+impl Solution for Day3 {
+    type Parsed = (Vec<Number>, Vec<Symbol>);
+
+    fn parse(input: &str) -> Self::Parsed {
+        let mut numbers = Vec::new();
+        let mut symbols = Vec::new();
+
+        for (line_idx, line) in input.lines().enumerate() {
+            let mut chars = line.chars().enumerate().peekable();
+            while let Some((idx, char)) = chars.next() {
+                if !char.is_ascii_digit() && char != '.' {
+                    symbols.push(Symbol {
+                        idx,
+                        line_idx,
+                        is_gear: char == '*',
+                    });
+                } else if char.is_ascii_digit() {
+                    let mut value = char as u32 - '0' as u32;
+                    let start_idx = idx;
+                    let mut end_idx = idx;
+
+                    while let Some(digit) = chars.peek().and_then(|(_, v)| v.to_digit(10)) {
+                        value *= 10;
+                        value += digit;
+                        end_idx += 1;
+                        chars.next();
+                    }
+
+                    numbers.push(Number {
+                        value,
+                        start_idx,
+                        end_idx: end_idx + 1,
+                        line_idx,
                     })
-                    .flatten()
-                {
-                    value *= 10;
-                    value += digit;
-                    end_idx += 1;
-                    chars.next();
                 }
-
-                numbers.push(Number {
-                    value,
-                    start_idx,
-                    end_idx: end_idx + 1,
-                    line_idx,
-                })
             }
         }
+
+        (numbers, symbols)
     }
 
-    let mut sum = 0;
-    let mut adjecent_parts = 0;
+    fn part1((numbers, symbols): &Self::Parsed) -> impl Display {
+        let mut sum = 0;
 
-    let mut gears: HashMap<Symbol, Vec<&Number>> = symbols
-        .iter()
-        .filter_map(|s| {
-            if s.is_gear {
-                Some((s.clone(), Vec::new()))
-            } else {
-                None
-            }
-        })
-        .collect();
-
-    for number in &numbers {
-        for symbol in &symbols {
-            if number.is_adjecent_to(symbol) {
-                sum += number.value;
-                println!(
-                    "{} is adjecent to line {}, col {}",
-                    number.value,
-                    symbol.line_idx + 1,
-                    symbol.idx + 1
-                );
-                adjecent_parts += 1;
-
-                if let Some(adjecent_parts) = gears.get_mut(symbol) {
-                    adjecent_parts.push(number);
+        for number in numbers {
+            for symbol in symbols {
+                if number.is_adjecent_to(symbol) {
+                    sum += number.value;
                 }
             }
         }
+
+        sum
     }
 
-    let gear_sum: u32 = gears
-        .into_iter()
-        .filter(|(_, v)| v.len() == 2)
-        .map(|(_, v)| v[0].value * v[1].value)
-        .sum();
+    fn part2((numbers, symbols): &Self::Parsed) -> impl Display {
+        let mut gears: HashMap<&Symbol, Vec<&Number>> = symbols
+            .iter()
+            .filter(|s| s.is_gear)
+            .map(|s| (s, Vec::new()))
+            .collect();
 
-    println!("Total adjecent parts: {adjecent_parts}");
-    println!("Sum of all of the part numbers: {sum}");
-    println!("Gear sum: {gear_sum}");
+        for number in numbers {
+            for (symbol, adjecent_parts) in gears.iter_mut() {
+                if number.is_adjecent_to(symbol) {
+                    adjecent_parts.push(number);
+                }
+            }
+        }
 
-    Ok(())
+        gears
+            .into_values()
+            .filter(|v| v.len() == 2)
+            .map(|v| v[0].value * v[1].value)
+            .sum::<u32>()
+    }
 }
@@ -1,4 +1,6 @@
-use std::{collections::HashMap, io::stdin};
+use std::{collections::HashMap, fmt::Display};
+
+use aoc_common::{parse::parse_int_list, Solution};
 
 #[derive(Debug, Clone)]
 struct ScratchCard {
@@ -16,76 +18,68 @@ impl ScratchCard {
     }
 }
 
-fn main() -> std::io::Result<()> {
-    let lines = stdin().lines();
-
-    let mut sum = 0;
-    let mut cards = Vec::new();
-    for line in lines {
-        let line = line?;
-
-        let (card_no, numbers) = line.split_once(':').unwrap();
+fn main() {
+    aoc_common::run::<Day4>();
+}
 
-        let card_no: u32 = card_no.split(' ').last().unwrap().trim().parse().unwrap();
-        let (winning_numbers, my_numbers) = numbers.split_once('|').unwrap();
-        let winning_numbers = winning_numbers.trim();
+struct Day4;
 
-        let winning_numbers: Vec<u32> = winning_numbers
-            .split(' ')
-            .filter(|v| !v.is_empty())
-            .map(|v| v.trim().parse().unwrap())
-            .collect();
+impl Solution for Day4 {
+    type Parsed = Vec<ScratchCard>;
 
-        let my_numbers: Vec<_> = my_numbers
-            .split(' ')
-            .filter(|v| !v.is_empty())
-            .map(|v| v.trim().parse::<u32>().unwrap())
-            .collect();
+    fn parse(input: &str) -> Self::Parsed {
+        let mut cards = Vec::new();
 
-        let mut total = None;
+        for line in input.lines() {
+            let (card_no, numbers) = line.split_once(':').unwrap();
 
-        for my_number in &my_numbers {
-            if winning_numbers.contains(&my_number) {
-                if let Some(total) = total.as_mut() {
-                    *total *= 2;
-                } else {
-                    total = Some(1);
-                }
+            let card_no: u32 = card_no
+                .split(' ')
+                .next_back()
+                .unwrap()
+                .trim()
+                .parse()
+                .unwrap();
+            let (winning_numbers, my_numbers) = numbers.split_once('|').unwrap();
 
-                println!("{my_number} is winning on card {card_no}.");
-            }
+            cards.push(ScratchCard {
+                card_number: card_no,
+                winning_numbers: parse_int_list(winning_numbers),
+                my_numbers: parse_int_list(my_numbers),
+            });
         }
 
-        if let Some(total) = total {
-            sum += total;
-        }
+        cards
+    }
 
-        cards.push(ScratchCard {
-            card_number: card_no,
-            winning_numbers,
-            my_numbers,
-        });
+    fn part1(cards: &Self::Parsed) -> impl Display {
+        cards
+            .iter()
+            .map(|card| match card.wins() {
+                0 => 0,
+                wins => 1 << (wins - 1),
+            })
+            .sum::<u32>()
     }
 
-    let reversed = cards.iter().enumerate().rev();
-    let mut state = HashMap::new();
-    let mut total_cards = 0;
+    fn part2(cards: &Self::Parsed) -> impl Display {
+        let reversed = cards.iter().enumerate().rev();
+        let mut state = HashMap::new();
+        let mut total_cards = 0;
+
+        for (idx, card) in reversed {
+            let wins = card.wins();
+            let succeeding_cards = cards.iter().skip(idx + 1).take(wins);
 
-    for (idx, card) in reversed {
-        let wins = card.wins();
-        let succeeding_cards = cards.iter().skip(idx + 1).take(wins);
+            let mut this_card = 1;
+            for successive in succeeding_cards {
+                this_card += state.get(&successive.card_number).unwrap();
+            }
 
-        let mut this_card = 1;
-        for successive in succeeding_cards {
-            this_card += state.get(&successive.card_number).unwrap();
+            total_cards += this_card;
+            state.insert(card.card_number, this_card);
         }
 
-        total_cards += this_card;
-        state.insert(card.card_number, this_card);
+        total_cards
     }
-
-    println!("Sum: {sum}");
-    println!("Total cards: {total_cards}");
-
-    Ok(())
 }
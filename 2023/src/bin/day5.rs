@@ -1,18 +1,17 @@
-use std::io::stdin;
+use std::fmt::Display;
 
-fn parse_int_list(input: &str) -> Vec<u64> {
-    input
-        .split(' ')
-        .map(|v| v.trim().parse().unwrap())
-        .collect()
-}
+use aoc_common::{parse::parse_int_list, Solution};
 
+// Seed ranges are only used by the range-based approach that is still
+// commented out in `part2`, and by the tests.
+#[allow(dead_code)]
 #[derive(Debug, Clone, PartialEq)]
 struct SeedRange {
     start: u64,
     len: u64,
 }
 
+#[allow(dead_code)]
 impl SeedRange {
     pub fn end(&self) -> u64 {
         self.start + self.len - 1
@@ -27,6 +26,7 @@ struct Range {
 }
 
 impl Range {
+    #[allow(dead_code)]
     fn source_end(&self) -> u64 {
         self.source_start + self.len - 1
     }
@@ -38,6 +38,7 @@ impl Range {
     /// Returns:
     /// List of untransformed ranges
     /// Optional is the transformed range
+    #[allow(dead_code)]
     pub fn transform(&self, range: &SeedRange) -> (Vec<SeedRange>, Option<SeedRange>) {
         // Range is entirely outside
         if range.end() < self.source_start || range.start > self.source_end() {
@@ -82,6 +83,7 @@ impl Range {
     }
 }
 
+#[allow(dead_code)]
 fn merge_ranges(ranges: &[SeedRange]) -> Vec<SeedRange> {
     let mut output_ranges = Vec::<SeedRange>::new();
 
@@ -102,62 +104,66 @@ fn merge_ranges(ranges: &[SeedRange]) -> Vec<SeedRange> {
     output_ranges
 }
 
-fn main() -> std::io::Result<()> {
-    let mut lines = stdin().lines().map(|v| v.unwrap());
+fn main() {
+    aoc_common::run::<Day5>();
+}
 
-    let seeds = lines.next().unwrap();
-    lines.next();
+struct Day5;
 
-    let (_, seeds) = seeds.split_once(':').unwrap();
-    let mut seeds: Vec<u64> = parse_int_list(seeds.trim());
+impl Solution for Day5 {
+    /// The seeds, and the ranges of every map in order.
+    type Parsed = (Vec<u64>, Vec<Vec<Range>>);
 
-    let mut seed_ranges = Vec::new();
-    let mut seeds_iter = seeds.iter();
-    println!("Loading seeds...");
+    fn parse(input: &str) -> Self::Parsed {
+        let mut lines = input.lines();
 
-    while let Some(start) = seeds_iter.next() {
-        let len = seeds_iter.next().unwrap();
+        let seeds = lines.next().unwrap();
+        lines.next();
 
-        for seed in *start..*start + len - 1 {
-            seed_ranges.push(seed);
-        }
-    }
+        let (_, seeds) = seeds.split_once(':').unwrap();
+        let seeds = parse_int_list(seeds.trim());
 
-    println!("Loaded {} seeds...", seed_ranges.len());
+        let mut maps = Vec::new();
 
-    // First line is always just the map name
-    while let Some(map) = lines.next() {
-        let mut ranges = Vec::new();
+        // First line is always just the map name
+        while lines.next().is_some() {
+            let mut ranges = Vec::new();
 
-        while let Some(line) = lines.next() {
-            if line.is_empty() {
-                break;
-            }
+            for line in &mut lines {
+                if line.is_empty() {
+                    break;
+                }
 
-            let range_info = parse_int_list(&line);
+                let range_info = parse_int_list(line);
+
+                ranges.push(Range {
+                    destination_start: range_info[0],
+                    source_start: range_info[1],
+                    len: range_info[2],
+                })
+            }
 
-            ranges.push(Range {
-                destination_start: range_info[0],
-                source_start: range_info[1],
-                len: range_info[2],
-            })
+            maps.push(ranges);
         }
 
-        let transform = |v: &mut u64| {
-            for range in &ranges {
-                if range.contains(*v) {
-                    let diff = *v - range.source_start;
-                    let new = range.destination_start + diff;
-                    *v = new;
-                    break;
-                }
-            }
-        };
+        (seeds, maps)
+    }
 
-        seeds.iter_mut().for_each(transform);
-        seed_ranges.iter_mut().for_each(transform);
+    fn part1((seeds, maps): &Self::Parsed) -> impl Display {
+        lowest_location(seeds.clone(), maps)
+    }
+
+    fn part2((seeds, maps): &Self::Parsed) -> impl Display {
+        let mut seed_ranges = Vec::new();
+        let mut seeds_iter = seeds.iter();
 
-        println!("Finished {}.", map.split_once(':').unwrap().0);
+        while let Some(start) = seeds_iter.next() {
+            let len = seeds_iter.next().unwrap();
+
+            for seed in *start..*start + len - 1 {
+                seed_ranges.push(seed);
+            }
+        }
 
         /*
         let mut unmapped_ranges = seed_ranges;
@@ -200,15 +206,30 @@ fn main() -> std::io::Result<()> {
             seed_ranges.iter().min_by(|a, b| a.start.cmp(&b.start))
         );
         */
+
+        lowest_location(seed_ranges, maps)
     }
+}
+
+/// The lowest location any of `seeds` ends up at after going through
+/// every map.
+fn lowest_location(mut seeds: Vec<u64>, maps: &[Vec<Range>]) -> u64 {
+    for ranges in maps {
+        let transform = |v: &mut u64| {
+            for range in ranges {
+                if range.contains(*v) {
+                    let diff = *v - range.source_start;
+                    let new = range.destination_start + diff;
+                    *v = new;
+                    break;
+                }
+            }
+        };
 
-    println!("Lowest location: {}", seeds.iter().min().unwrap());
-    println!(
-        "Lowest location (ranges): {:?}",
-        seed_ranges.iter().min().unwrap()
-    );
+        seeds.iter_mut().for_each(transform);
+    }
 
-    Ok(())
+    seeds.into_iter().min().unwrap()
 }
 
 macro_rules! test {
@@ -1,13 +1,6 @@
-use std::io::stdin;
-
-fn parse_int_list(input: &str) -> Vec<u64> {
-    input
-        .split(' ')
-        .map(|v| v.trim())
-        .filter(|v| !v.is_empty())
-        .map(|v| v.parse().unwrap())
-        .collect()
-}
+use std::fmt::Display;
+
+use aoc_common::{parse::parse_int_list, Solution};
 
 fn calculate_possible_wins(time: u64, record_distance: u64) -> u64 {
     // total time = C
@@ -25,7 +18,7 @@ fn calculate_possible_wins(time: u64, record_distance: u64) -> u64 {
     let best_distance_hold = time / 2;
     let mut winning_times = (best_distance_hold - record_held) * 2;
 
-    if time % 2 == 0 {
+    if time.is_multiple_of(2) {
         // Subtract one to account for double-counting the best distance hold.
         winning_times -= 1;
     }
@@ -33,47 +26,56 @@ fn calculate_possible_wins(time: u64, record_distance: u64) -> u64 {
     winning_times
 }
 
-fn main() -> std::io::Result<()> {
-    let mut lines = stdin().lines().map(|v| v.unwrap());
-
-    let times = lines.next().unwrap();
-    let (_, times_str) = times.split_once(':').unwrap();
-    let times = parse_int_list(times_str.trim());
-
-    let distances = lines.next().unwrap();
-    let (_, distances_str) = distances.split_once(':').unwrap();
-    let distances = parse_int_list(distances_str.trim());
-
-    let times_distances = times.into_iter().zip(distances.into_iter());
-
-    let mut total = None;
-    for (time, distance) in times_distances {
-        let wins = calculate_possible_wins(time, distance);
+fn main() {
+    aoc_common::run::<Day6>();
+}
 
-        if let Some(total) = total.as_mut() {
-            *total *= wins;
-        } else {
-            total = Some(wins);
-        }
+struct Day6;
+
+impl Solution for Day6 {
+    /// The time and record distance of every race, and of the single long
+    /// race you get by ignoring the spaces.
+    type Parsed = (Vec<(u64, u64)>, (u64, u64));
+
+    fn parse(input: &str) -> Self::Parsed {
+        let mut lines = input.lines();
+
+        let times = lines.next().unwrap();
+        let (_, times_str) = times.split_once(':').unwrap();
+        let times: Vec<u64> = parse_int_list(times_str.trim());
+
+        let distances = lines.next().unwrap();
+        let (_, distances_str) = distances.split_once(':').unwrap();
+        let distances: Vec<u64> = parse_int_list(distances_str.trim());
+
+        let long_time: u64 = times_str
+            .chars()
+            .filter(char::is_ascii_digit)
+            .collect::<String>()
+            .parse()
+            .unwrap();
+
+        let long_distance: u64 = distances_str
+            .chars()
+            .filter(char::is_ascii_digit)
+            .collect::<String>()
+            .parse()
+            .unwrap();
+
+        (
+            times.into_iter().zip(distances).collect(),
+            (long_time, long_distance),
+        )
     }
 
-    let long_time: u64 = times_str
-        .chars()
-        .filter(char::is_ascii_digit)
-        .collect::<String>()
-        .parse()
-        .unwrap();
-
-    let long_distance: u64 = distances_str
-        .chars()
-        .filter(char::is_ascii_digit)
-        .collect::<String>()
-        .parse()
-        .unwrap();
-
-    let long_wins = calculate_possible_wins(long_time, long_distance);
-
-    println!("{}, {}", total.unwrap(), long_wins);
+    fn part1((races, _): &Self::Parsed) -> impl Display {
+        races
+            .iter()
+            .map(|(time, distance)| calculate_possible_wins(*time, *distance))
+            .product::<u64>()
+    }
 
-    Ok(())
+    fn part2((_, (long_time, long_distance)): &Self::Parsed) -> impl Display {
+        calculate_possible_wins(*long_time, *long_distance)
+    }
 }
@@ -1,4 +1,6 @@
-use std::io::stdin;
+use std::fmt::Display;
+
+use aoc_common::Solution;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 #[repr(u32)]
@@ -187,9 +189,7 @@ impl Hand {
         let card_type_count = self.counts.iter().filter(|(_, c)| *c != 0).count();
 
         three_of_kind && two_of_kind
-            || (joker_as_any
-                && ((self.jokers == 1 && card_type_count == 3)
-                    || (self.jokers == 2 && card_type_count == 3)))
+            || (joker_as_any && (self.jokers == 1 || self.jokers == 2) && card_type_count == 3)
     }
 
     pub fn three_of_kind(&self, joker_as_any: bool) -> bool {
@@ -229,7 +229,7 @@ impl Hand {
     }
 
     pub fn high_card(&self) -> Card {
-        self.cards.iter().max().unwrap().clone()
+        *self.cards.iter().max().unwrap()
     }
 
     define_hand_types!(
@@ -250,46 +250,54 @@ impl PartialOrd for Hand {
 
 impl Ord for Hand {
     fn cmp(&self, other_hand: &Self) -> std::cmp::Ordering {
-        Self::cmp(&self, other_hand, false)
+        Self::cmp(self, other_hand, false)
     }
 }
 
-fn main() -> std::io::Result<()> {
-    let lines = stdin().lines().map(|v| v.unwrap());
-
-    let mut hands: Vec<_> = lines
-        .map(|v| {
-            let (hand, bid) = v.split_once(' ').unwrap();
-
-            let mut cards = [Card::A; 5];
-            cards
-                .iter_mut()
-                .zip(hand.trim().chars())
-                .for_each(|(card, char)| *card = Card::try_from(char).unwrap());
-            let bid: usize = bid.trim().parse().unwrap();
-
-            (Hand::new(cards), bid)
-        })
-        .collect();
+fn main() {
+    aoc_common::run::<Day7>();
+}
 
-    let mut joker_hands = hands.clone();
+struct Day7;
 
-    hands.sort_by(|a, b| a.0.cmp(&b.0, false));
-    joker_hands.sort_by(|a, b| a.0.cmp(&b.0, true));
+impl Solution for Day7 {
+    /// Every hand with its bid.
+    type Parsed = Vec<(Hand, usize)>;
 
-    let calc_sum = |input: &[(Hand, usize)]| {
+    fn parse(input: &str) -> Self::Parsed {
         input
-            .iter()
-            .enumerate()
-            .map(|(r, (_, b))| b * (r + 1))
-            .sum()
-    };
+            .lines()
+            .map(|v| {
+                let (hand, bid) = v.split_once(' ').unwrap();
+
+                let mut cards = [Card::A; 5];
+                cards
+                    .iter_mut()
+                    .zip(hand.trim().chars())
+                    .for_each(|(card, char)| *card = Card::try_from(char).unwrap());
+                let bid: usize = bid.trim().parse().unwrap();
+
+                (Hand::new(cards), bid)
+            })
+            .collect()
+    }
 
-    let sum: usize = calc_sum(&hands);
-    let joker_sum: usize = calc_sum(&joker_hands);
+    fn part1(hands: &Self::Parsed) -> impl Display {
+        total_winnings(hands, false)
+    }
+
+    fn part2(hands: &Self::Parsed) -> impl Display {
+        total_winnings(hands, true)
+    }
+}
 
-    println!("Sum: {sum}");
-    println!("Joker sum: {joker_sum}");
+fn total_winnings(hands: &[(Hand, usize)], joker_as_any: bool) -> usize {
+    let mut hands = hands.to_vec();
+    hands.sort_by(|a, b| a.0.cmp(&b.0, joker_as_any));
 
-    Ok(())
+    hands
+        .iter()
+        .enumerate()
+        .map(|(r, (_, b))| b * (r + 1))
+        .sum()
 }
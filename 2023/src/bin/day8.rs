@@ -1,4 +1,6 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt::Display};
+
+use aoc_common::{math::lcm_all, Solution};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Step {
@@ -24,7 +26,9 @@ pub struct Decision {
     right: String,
 }
 
-fn part1<'a>(
+/// The number of steps it takes to get from `start_node` to `ZZZ`, or to
+/// any node ending in `Z` if `any_z` is set.
+fn count_steps<'a>(
     start_node: &str,
     steps: impl Iterator<Item = &'a Step> + Clone,
     nodes: &HashMap<String, Decision>,
@@ -56,7 +60,7 @@ fn part1<'a>(
 fn part2<'a>(
     steps: impl Iterator<Item = &'a Step> + Clone,
     decisions: &HashMap<String, Decision>,
-) -> usize {
+) -> u64 {
     let nodes: Vec<_> = decisions.keys().collect();
 
     let nodes_with = |char| {
@@ -70,75 +74,61 @@ fn part2<'a>(
 
     let nodes_and_steps = starting_nodes.iter().map(|node| {
         let name = nodes[*node];
-        part1(name, steps.clone(), decisions, true) as u64
+        count_steps(name, steps.clone(), decisions, true) as u64
     });
 
-    println!("{}", lcm(nodes_and_steps).unwrap());
-
-    0
-}
-
-fn lcm(values: impl Iterator<Item = u64> + Clone) -> Option<u64> {
-    values.reduce(|a, b| {
-        if a == 0 || b == 0 {
-            0
-        } else {
-            a / (gcd(a, b)) * b
-        }
-    })
+    lcm_all(nodes_and_steps).unwrap()
 }
 
-fn gcd(mut a: u64, mut b: u64) -> u64 {
-    while b != 0 {
-        let store_b = b;
-        b = a % b;
-        a = store_b;
-    }
-
-    a
+fn main() {
+    aoc_common::run::<Day8>();
 }
 
-fn main() -> std::io::Result<()> {
-    let mut lines = std::io::stdin().lines().map(|v| v.unwrap());
-
-    let steps: Vec<_> = lines
-        .next()
-        .unwrap()
-        .chars()
-        .map(|v| Step::try_from(v).unwrap())
-        .collect();
-
-    let mut decisions = HashMap::new();
-    let mut first_node = None;
-
-    for line in lines {
-        if line.is_empty() {
-            continue;
+struct Day8;
+
+impl Solution for Day8 {
+    /// The steps to repeat, and the decision to make at every node.
+    type Parsed = (Vec<Step>, HashMap<String, Decision>);
+
+    fn parse(input: &str) -> Self::Parsed {
+        let mut lines = input.lines();
+
+        let steps: Vec<_> = lines
+            .next()
+            .unwrap()
+            .chars()
+            .map(|v| Step::try_from(v).unwrap())
+            .collect();
+
+        let mut decisions = HashMap::new();
+
+        for line in lines {
+            if line.is_empty() {
+                continue;
+            }
+
+            let (name, r_l) = line.split_once(" = ").unwrap();
+            let (l, r) = r_l.split_once(", ").unwrap();
+            let left = &l[1..];
+            let right = &r[..r.len() - 1];
+
+            decisions.insert(
+                name.to_string(),
+                Decision {
+                    left: left.to_string(),
+                    right: right.to_string(),
+                },
+            );
         }
 
-        let (name, r_l) = line.split_once(" = ").unwrap();
-        let (l, r) = r_l.split_once(", ").unwrap();
-        let left = &l[1..];
-        let right = &r[..r.len() - 1];
-
-        decisions.insert(
-            name.to_string(),
-            Decision {
-                left: left.to_string(),
-                right: right.to_string(),
-            },
-        );
-
-        if first_node.is_none() {
-            first_node = Some(name.to_string());
-        }
+        (steps, decisions)
     }
 
-    let part1 = part1("AAA", steps.iter(), &decisions, false);
-    let part2 = part2(steps.iter(), &decisions);
-
-    println!("Part 1 took {part1} steps.");
-    println!("Part 2 took {part2} steps");
+    fn part1((steps, decisions): &Self::Parsed) -> impl Display {
+        count_steps("AAA", steps.iter(), decisions, false)
+    }
 
-    Ok(())
+    fn part2((steps, decisions): &Self::Parsed) -> impl Display {
+        part2(steps.iter(), decisions)
+    }
 }
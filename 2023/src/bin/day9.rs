@@ -1,16 +1,20 @@
-fn part1(sequences: &Vec<Vec<i64>>) -> i64 {
+use std::fmt::Display;
+
+use aoc_common::Solution;
+
+fn next_value(sequences: &[Vec<i64>]) -> i64 {
     assert!(sequences[sequences.len() - 1].iter().all(|v| *v == 0));
 
     let mut diff = 0;
     for sequence in sequences.iter().rev().skip(1) {
         let last_val = sequence[sequence.len() - 1];
-        diff = last_val + diff;
+        diff += last_val;
     }
 
     diff
 }
 
-fn part2(sequences: &Vec<Vec<i64>>) -> i64 {
+fn previous_value(sequences: &[Vec<i64>]) -> i64 {
     assert!(sequences[sequences.len() - 1].iter().all(|v| *v == 0));
 
     let mut diff = 0;
@@ -22,42 +26,54 @@ fn part2(sequences: &Vec<Vec<i64>>) -> i64 {
     diff
 }
 
-fn main() -> std::io::Result<()> {
-    let lines = std::io::stdin().lines().map(|v| v.unwrap());
+fn main() {
+    aoc_common::run::<Day9>();
+}
+
+struct Day9;
+
+impl Solution for Day9 {
+    /// For every line, the sequence followed by its differences, down to
+    /// the sequence of zeroes.
+    type Parsed = Vec<Vec<Vec<i64>>>;
 
-    let sequences: Vec<_> = lines
-        .map(|v| {
+    fn parse(input: &str) -> Self::Parsed {
+        let sequences = input.lines().map(|v| {
             v.split(' ')
                 .filter(|v| !v.is_empty())
                 .map(|v| v.parse::<i64>().unwrap())
                 .collect::<Vec<_>>()
-        })
-        .collect();
-
-    let mut part1_sum = 0;
-    let mut part2_sum = 0;
-    for mut sequence in sequences {
-        let mut this_line_sequences = vec![sequence.clone()];
-        while !sequence.iter().all(|v| v == &0) {
-            let mut iter = sequence.iter();
-
-            let mut diffs = Vec::with_capacity(sequence.len() - 1);
-            if let Some(mut previous_value) = iter.next().cloned() {
-                while let Some(next_value) = iter.next() {
-                    diffs.push(next_value - previous_value);
-                    previous_value = *next_value;
+        });
+
+        let mut all_sequences = Vec::new();
+        for mut sequence in sequences {
+            let mut this_line_sequences = vec![sequence.clone()];
+            while !sequence.iter().all(|v| v == &0) {
+                let mut iter = sequence.iter();
+
+                let mut diffs = Vec::with_capacity(sequence.len() - 1);
+                if let Some(mut previous_value) = iter.next().cloned() {
+                    for next_value in iter {
+                        diffs.push(next_value - previous_value);
+                        previous_value = *next_value;
+                    }
                 }
+
+                this_line_sequences.push(diffs.clone());
+                sequence = diffs;
             }
 
-            this_line_sequences.push(diffs.clone());
-            sequence = diffs;
+            all_sequences.push(this_line_sequences);
         }
 
-        part1_sum += part1(&this_line_sequences);
-        part2_sum += part2(&this_line_sequences);
+        all_sequences
     }
 
-    println!("Part 1: {part1_sum}");
-    println!("Part 2: {part2_sum}");
-    Ok(())
+    fn part1(sequences: &Self::Parsed) -> impl Display {
+        sequences.iter().map(|s| next_value(s)).sum::<i64>()
+    }
+
+    fn part2(sequences: &Self::Parsed) -> impl Display {
+        sequences.iter().map(|s| previous_value(s)).sum::<i64>()
+    }
 }
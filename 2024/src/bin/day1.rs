@@ -1,13 +1,30 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt::Display};
+
+use aoc_common::Solution;
 
 fn main() {
-    let lines: Vec<_> = std::io::stdin().lines().map(|v| v.unwrap()).collect();
+    aoc_common::run::<Day1>();
+}
+
+struct Day1;
 
-    part1(&lines);
-    part2(&lines);
+impl Solution for Day1 {
+    type Parsed = Vec<String>;
+
+    fn parse(input: &str) -> Self::Parsed {
+        input.lines().map(str::to_string).collect()
+    }
+
+    fn part1(lines: &Self::Parsed) -> impl Display {
+        part1(lines)
+    }
+
+    fn part2(lines: &Self::Parsed) -> impl Display {
+        part2(lines)
+    }
 }
 
-fn part1(lines: &[String]) {
+fn part1(lines: &[String]) -> usize {
     let mut lhs_list = Vec::with_capacity(lines.len());
     let mut rhs_list = Vec::with_capacity(lines.len());
 
@@ -21,14 +38,14 @@ fn part1(lines: &[String]) {
     }
 
     let mut sum = 0;
-    for (lhs, rhs) in lhs_list.into_iter().zip(rhs_list.into_iter()) {
+    for (lhs, rhs) in lhs_list.into_iter().zip(rhs_list) {
         let min = usize::min(lhs, rhs);
         let max = usize::max(lhs, rhs);
         let distance = max - min;
         sum += distance;
     }
 
-    println!("Part 1: {sum}");
+    sum
 }
 
 fn push_sorted(list: &mut Vec<usize>, value: usize) {
@@ -40,7 +57,7 @@ fn push_sorted(list: &mut Vec<usize>, value: usize) {
     list.insert(idx, value);
 }
 
-fn part2(lines: &[String]) {
+fn part2(lines: &[String]) -> usize {
     let mut lhs_list = Vec::with_capacity(lines.len());
     let mut rhs_counts = HashMap::with_capacity(lines.len());
 
@@ -61,5 +78,5 @@ fn part2(lines: &[String]) {
         score += lhs * *count;
     }
 
-    println!("Part 2: {score}");
+    score
 }
@@ -1,64 +1,44 @@
-use std::{collections::HashSet, usize};
-
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum Direction {
-    North,
-    East,
-    South,
-    West,
-}
-
-impl Direction {
-    pub fn do_move(
-        &self,
-        (x, y): (usize, usize),
-        (x_len, y_len): (usize, usize),
-    ) -> Option<(usize, usize)> {
-        let (new_x, new_y) = match self {
-            Direction::North => (x, y.checked_sub(1)?),
-            Direction::East => (x + 1, y),
-            Direction::South => (x, y + 1),
-            Direction::West => (x.checked_sub(1)?, y),
-        };
-
-        if new_x < x_len && new_y < y_len {
-            Some((new_x, new_y))
-        } else {
-            None
-        }
-    }
+use std::{collections::HashSet, fmt::Display};
 
-    fn moves(pos: (usize, usize), dims: (usize, usize)) -> impl Iterator<Item = (usize, usize)> {
-        Self::all().flat_map(move |d| d.do_move(pos, dims))
-    }
+use aoc_common::{grid::neighbours, Solution};
 
-    pub fn all() -> impl Iterator<Item = Direction> {
-        [Self::North, Self::East, Self::South, Self::West].into_iter()
-    }
+fn main() {
+    aoc_common::run::<Day10>();
 }
 
-fn main() {
-    let lines: Vec<_> = std::io::stdin().lines().map(|v| v.unwrap()).collect();
+struct Day10;
 
-    let mut height_map = Vec::new();
+impl Solution for Day10 {
+    type Parsed = Vec<Vec<usize>>;
 
-    for line in lines {
-        let mut this_line = Vec::new();
-        for char in line.chars() {
-            if char == '.' {
-                this_line.push(usize::MAX);
-            } else {
-                this_line.push(char as usize - '0' as usize);
+    fn parse(input: &str) -> Self::Parsed {
+        let mut height_map = Vec::new();
+
+        for line in input.lines() {
+            let mut this_line = Vec::new();
+            for char in line.chars() {
+                if char == '.' {
+                    this_line.push(usize::MAX);
+                } else {
+                    this_line.push(char as usize - '0' as usize);
+                }
             }
+            height_map.push(this_line);
         }
-        height_map.push(this_line);
+
+        height_map
+    }
+
+    fn part1(height_map: &Self::Parsed) -> impl Display {
+        part1(height_map)
     }
 
-    part1(&height_map);
-    part2(&height_map);
+    fn part2(height_map: &Self::Parsed) -> impl Display {
+        part2(height_map)
+    }
 }
 
-fn walk<F, T>((x, y): (usize, usize), map: &Vec<Vec<usize>>, f: &mut F) -> T
+fn walk<F, T>((x, y): (usize, usize), map: &[Vec<usize>], f: &mut F) -> T
 where
     F: FnMut((usize, usize)) -> T,
     T: std::ops::Add<Output = T> + Default,
@@ -67,11 +47,11 @@ where
     let dims = (map[0].len(), map.len());
 
     if height == 0 {
-        return f((x, y));
+        f((x, y))
     } else {
         let target_height = height - 1;
 
-        let moves = Direction::moves((x, y), dims).filter(|(x, y)| map[*y][*x] == target_height);
+        let moves = neighbours((x, y), dims).filter(|(x, y)| map[*y][*x] == target_height);
 
         let mut sum = T::default();
         for (x, y) in moves {
@@ -81,33 +61,29 @@ where
     }
 }
 
-fn part1(height_map: &Vec<Vec<usize>>) {
+fn part1(height_map: &[Vec<usize>]) -> usize {
     let starts = (0..height_map.len())
         .flat_map(|y| (0..height_map[0].len()).map(move |x| (x, y)))
         .filter(|(x, y)| height_map[*y][*x] == 9);
 
-    let score: usize = starts
+    starts
         .map(|start| {
             let mut trail_heads = HashSet::new();
             let mut found_trail_head = |pos| trail_heads.insert(pos) as usize;
             walk(start, height_map, &mut found_trail_head)
         })
-        .sum();
-
-    println!("Part 1: {score}");
+        .sum()
 }
 
-fn part2(height_map: &Vec<Vec<usize>>) {
+fn part2(height_map: &[Vec<usize>]) -> usize {
     let starts = (0..height_map.len())
         .flat_map(|y| (0..height_map[0].len()).map(move |x| (x, y)))
         .filter(|(x, y)| height_map[*y][*x] == 9);
 
-    let unique_paths: usize = starts
+    starts
         .map(|start| {
             let mut found_path = |_| 1;
             walk(start, height_map, &mut found_path)
         })
-        .sum();
-
-    println!("Part 2: {unique_paths}");
+        .sum()
 }
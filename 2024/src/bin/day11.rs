@@ -1,20 +1,38 @@
-use std::{collections::HashMap, time::Instant};
+use std::{collections::HashMap, fmt::Display};
+
+use aoc_common::Solution;
 
 fn main() {
-    let input: String = std::io::stdin().lines().map(|v| v.unwrap()).next().unwrap();
-    let mut stones = Vec::new();
+    aoc_common::run::<Day11>();
+}
+
+struct Day11;
 
-    for stone in input.split(' ') {
-        let stone: usize = stone.parse().unwrap();
-        stones.push(stone);
+impl Solution for Day11 {
+    type Parsed = Vec<usize>;
+
+    fn parse(input: &str) -> Self::Parsed {
+        let input = input.lines().next().unwrap();
+        let mut stones = Vec::new();
+
+        for stone in input.split(' ') {
+            let stone: usize = stone.parse().unwrap();
+            stones.push(stone);
+        }
+
+        stones
+    }
+
+    fn part1(stones: &Self::Parsed) -> impl Display {
+        part1(stones)
     }
 
-    part1(&stones);
-    part2(&stones);
+    fn part2(stones: &Self::Parsed) -> impl Display {
+        part2(stones)
+    }
 }
 
-fn part1(stones: &[usize]) {
-    let total_start = Instant::now();
+fn part1(stones: &[usize]) -> usize {
     let mut old_stones = stones.to_vec();
     let mut new_stones = Vec::with_capacity(2 * old_stones.len());
 
@@ -27,7 +45,7 @@ fn part1(stones: &[usize]) {
             } else {
                 let digits = stone.ilog10() + 1;
 
-                if digits % 2 == 0 {
+                if digits.is_multiple_of(2) {
                     let mask = 10usize.pow(digits / 2);
                     new_stones.push(stone / mask);
                     new_stones.push(stone % mask);
@@ -40,22 +58,15 @@ fn part1(stones: &[usize]) {
         std::mem::swap(&mut old_stones, &mut new_stones);
     }
 
-    println!(
-        "Part 1: {}. {} ms",
-        old_stones.len(),
-        total_start.elapsed().as_millis()
-    );
+    old_stones.len()
 }
 
-fn part2(stones: &[usize]) {
-    let start = Instant::now();
+fn part2(stones: &[usize]) -> usize {
     let mut known = HashMap::new();
-    let sum: usize = stones
+    stones
         .iter()
         .map(|v| expanded_count(&mut known, 75, *v))
-        .sum();
-
-    println!("Part 2: {sum}. {} ms", start.elapsed().as_millis());
+        .sum()
 }
 
 fn expanded_count(
@@ -76,7 +87,7 @@ fn expanded_count(
     } else {
         let digits = stone.ilog10() + 1;
 
-        if digits % 2 == 0 {
+        if digits.is_multiple_of(2) {
             let mask = 10usize.pow(digits / 2);
             expanded_count(known, next_iterations, stone / mask)
                 + expanded_count(known, next_iterations, stone % mask)
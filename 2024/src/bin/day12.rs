@@ -1,23 +1,39 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, fmt::Display};
+
+use aoc_common::Solution;
 
 fn main() {
-    let input: Vec<String> = std::io::stdin().lines().map(|v| v.unwrap()).collect();
-    let regions = parse(&input);
+    aoc_common::run::<Day12>();
+}
+
+struct Day12;
 
-    part1(&regions);
-    part2(&regions);
+impl Solution for Day12 {
+    type Parsed = Vec<Region>;
+
+    fn parse(input: &str) -> Self::Parsed {
+        parse(input)
+    }
+
+    fn part1(regions: &Self::Parsed) -> impl Display {
+        part1(regions)
+    }
+
+    fn part2(regions: &Self::Parsed) -> impl Display {
+        part2(regions)
+    }
 }
 
-fn part1(regions: &[Region]) {
+fn part1(regions: &[Region]) -> usize {
     let mut sum = 0;
     for region in regions {
         sum += region.area() * region.fence();
     }
 
-    println!("Part 1: {sum}");
+    sum
 }
 
-fn part2(regions: &[Region]) {
+fn part2(regions: &[Region]) -> usize {
     let mut sum = 0;
 
     for region in regions {
@@ -27,12 +43,12 @@ fn part2(regions: &[Region]) {
         sum += area * sides;
     }
 
-    println!("Part 2: {sum}");
+    sum
 }
 
-fn parse(input: &[String]) -> Vec<Region> {
+fn parse(input: &str) -> Vec<Region> {
     let mut regions = Vec::new();
-    let input: Vec<Vec<char>> = input.into_iter().map(|v| v.chars().collect()).collect();
+    let input: Vec<Vec<char>> = input.lines().map(|v| v.chars().collect()).collect();
 
     let x_len = input[0].len();
     let y_len = input.len();
@@ -64,7 +80,7 @@ struct Region {
 }
 
 impl Region {
-    pub fn new(input: &Vec<Vec<char>>, start: (usize, usize)) -> Self {
+    pub fn new(input: &[Vec<char>], start: (usize, usize)) -> Self {
         let width = input[0].len();
         let height = input.len();
 
@@ -79,7 +95,7 @@ impl Region {
         me
     }
 
-    fn build_rec(&mut self, pos: (usize, usize), input: &Vec<Vec<char>>) {
+    fn build_rec(&mut self, pos: (usize, usize), input: &[Vec<char>]) {
         if input[pos.1][pos.0] == self.plant && !self.get(pos) {
             self.set(pos, true);
 
@@ -214,7 +230,7 @@ impl Region {
         ];
 
         xs.into_iter()
-            .zip(ys.into_iter())
+            .zip(ys)
             .map(|(x, y)| x.and_then(|x| y.map(|y| (x, y))))
     }
 
@@ -1,3 +1,7 @@
+use std::fmt::Display;
+
+use aoc_common::{math::gcd, Solution};
+
 #[derive(Debug, Copy, Clone)]
 struct Point {
     x: usize,
@@ -18,7 +22,7 @@ impl Machine {
     pub fn solve(&self) -> Option<usize> {
         let Self { a, b, prize } = self;
 
-        let prize_gcd = Self::gcd(prize.x, prize.y);
+        let prize_gcd = gcd(prize.x, prize.y);
 
         let (a_x, b_x) = ((a.x * prize.y) / prize_gcd, (b.x * prize.y) / prize_gcd);
         let (a_y, b_y) = ((a.y * prize.x) / prize_gcd, (b.y * prize.x) / prize_gcd);
@@ -26,7 +30,7 @@ impl Machine {
         let total_a = a_x.max(a_y) - a_x.min(a_y);
         let total_b = b_x.max(b_y) - b_x.min(b_y);
 
-        let gcd = Self::gcd(total_a, total_b);
+        let gcd = gcd(total_a, total_b);
 
         let a_mul = (total_a / gcd).max(1);
         let b_mul = (total_b / gcd).max(1);
@@ -55,48 +59,53 @@ impl Machine {
 
         result.map(|iter| (iter * b_mul * A_COST) + (iter * a_mul * B_COST))
     }
-
-    fn gcd(a: usize, b: usize) -> usize {
-        if b == 0 {
-            a
-        } else if a < b {
-            Self::gcd(b, a)
-        } else {
-            Self::gcd(b, a % b)
-        }
-    }
 }
 
 fn main() {
-    let lines: Vec<_> = std::io::stdin().lines().map(|v| v.unwrap()).collect();
+    aoc_common::run::<Day13>();
+}
+
+struct Day13;
 
-    let mut machines = Vec::new();
-    for machine in lines.chunks(4) {
-        let a = parse_line(&machine[0]);
-        let b = parse_line(&machine[1]);
-        let prize = parse_line(&machine[2]);
+impl Solution for Day13 {
+    type Parsed = Vec<Machine>;
 
-        machines.push(Machine { a, b, prize });
+    fn parse(input: &str) -> Self::Parsed {
+        let lines: Vec<_> = input.lines().collect();
+
+        let mut machines = Vec::new();
+        for machine in lines.chunks(4) {
+            let a = parse_line(machine[0]);
+            let b = parse_line(machine[1]);
+            let prize = parse_line(machine[2]);
+
+            machines.push(Machine { a, b, prize });
+        }
+
+        machines
     }
 
-    part1(&machines);
-    part2(&machines);
+    fn part1(machines: &Self::Parsed) -> impl Display {
+        part1(machines)
+    }
+
+    fn part2(machines: &Self::Parsed) -> impl Display {
+        part2(machines)
+    }
 }
 
-fn part1(machines: &[Machine]) {
-    let result: usize = machines.iter().flat_map(Machine::solve).sum();
-    println!("Part 1: {result}");
+fn part1(machines: &[Machine]) -> usize {
+    machines.iter().flat_map(Machine::solve).sum()
 }
 
-fn part2(machines: &[Machine]) {
-    let mut machines: Vec<_> = machines.iter().cloned().collect();
+fn part2(machines: &[Machine]) -> usize {
+    let mut machines = machines.to_vec();
     machines.iter_mut().for_each(|m| {
         m.prize.x += 10000000000000;
         m.prize.y += 10000000000000
     });
 
-    let result: usize = machines.iter().flat_map(Machine::solve).sum();
-    println!("Part 2: {result}");
+    machines.iter().flat_map(Machine::solve).sum()
 }
 
 fn parse_line(input: &str) -> Point {
@@ -1,3 +1,7 @@
+use std::fmt::Display;
+
+use aoc_common::Solution;
+
 #[derive(Debug, Clone, PartialEq)]
 struct Robot {
     position: (isize, isize),
@@ -12,11 +16,11 @@ impl Robot {
         let (mut x_vel, mut y_vel) = self.velocity;
 
         if x_vel < 0 {
-            x_vel = self.room_dims.0 + x_vel;
+            x_vel += self.room_dims.0;
         }
 
         if y_vel < 0 {
-            y_vel = self.room_dims.1 + y_vel;
+            y_vel += self.room_dims.1;
         }
 
         let new_x = x.wrapping_add(x_vel * count) % x_len;
@@ -27,43 +31,60 @@ impl Robot {
 }
 
 fn main() {
-    let lines: Vec<_> = std::io::stdin().lines().map(|v| v.unwrap()).collect();
+    aoc_common::run::<Day14>();
+}
+
+struct Day14;
+
+impl Solution for Day14 {
+    type Parsed = Vec<Robot>;
+
+    fn parse(input: &str) -> Self::Parsed {
+        let x_len = 101;
+        let y_len = 103;
+
+        let mut robots = Vec::new();
+        for line in input.lines() {
+            let (pos, vel) = line.split_once(' ').unwrap();
 
-    let x_len = 101;
-    let y_len = 103;
+            let (x_pos, y_pos) = pos[2..].split_once(',').unwrap();
+            let (x_vel, y_vel) = vel[2..].split_once(',').unwrap();
 
-    let mut robots = Vec::new();
-    for line in lines {
-        let (pos, vel) = line.split_once(' ').unwrap();
+            let (x_pos, y_pos): (isize, isize) = (x_pos.parse().unwrap(), y_pos.parse().unwrap());
+            let (x_vel, y_vel): (isize, isize) = (x_vel.parse().unwrap(), y_vel.parse().unwrap());
+            robots.push(Robot {
+                position: (x_pos, y_pos),
+                velocity: (x_vel, y_vel),
+                room_dims: (x_len, y_len),
+            });
+        }
 
-        let (x_pos, y_pos) = pos[2..].split_once(',').unwrap();
-        let (x_vel, y_vel) = vel[2..].split_once(',').unwrap();
+        robots
+    }
 
-        let (x_pos, y_pos): (isize, isize) = (x_pos.parse().unwrap(), y_pos.parse().unwrap());
-        let (x_vel, y_vel): (isize, isize) = (x_vel.parse().unwrap(), y_vel.parse().unwrap());
-        robots.push(Robot {
-            position: (x_pos, y_pos),
-            velocity: (x_vel, y_vel),
-            room_dims: (x_len, y_len),
-        });
+    fn part1(robots: &Self::Parsed) -> impl Display {
+        part1(robots)
     }
 
-    part1(&robots);
-    part2(&robots);
+    fn part2(robots: &Self::Parsed) -> impl Display {
+        part2(robots)
+    }
 }
 
-fn part2(robots: &[Robot]) {
+/// The number of seconds until the robots first draw a picture, followed by
+/// the picture itself.
+fn part2(robots: &[Robot]) -> String {
     let mut robots = robots.to_vec();
 
     for iter in 1.. {
         robots.iter_mut().for_each(|r| r.make_move(1));
 
         if has_line(&robots) {
-            println!("Part 2: {iter}");
-            print_robots(&robots);
-            break;
+            return format!("{iter}\n{}", draw_robots(&robots));
         }
     }
+
+    unreachable!()
 }
 
 fn has_line(robots: &[Robot]) -> bool {
@@ -93,7 +114,7 @@ fn has_line(robots: &[Robot]) -> bool {
     false
 }
 
-fn part1(robots: &[Robot]) {
+fn part1(robots: &[Robot]) -> usize {
     let (x_len, y_len) = robots[0].room_dims;
     let mut robots = robots.to_vec();
 
@@ -131,12 +152,10 @@ fn part1(robots: &[Robot]) {
         }
     }
 
-    let prod: usize = quadrant_sums.into_iter().product();
-
-    println!("Part 1: {prod}");
+    quadrant_sums.into_iter().product()
 }
 
-fn print_robots(robots: &[Robot]) {
+fn draw_robots(robots: &[Robot]) -> String {
     let x_len = robots[0].room_dims.0;
     let y_len = robots[0].room_dims.1;
 
@@ -154,16 +173,17 @@ fn print_robots(robots: &[Robot]) {
         }
     }
 
-    for y in 0..y_len {
-        for x in 0..x_len {
-            let val = rows[y as usize][x as usize];
-
+    let mut picture = String::new();
+    for row in rows {
+        for val in row {
             if val == 0 {
-                print!(".");
+                picture.push('.');
             } else {
-                print!("{val}");
+                picture.push_str(&val.to_string());
             }
         }
-        println!();
+        picture.push('\n');
     }
+
+    picture
 }
@@ -1,4 +1,6 @@
-use std::{collections::VecDeque, task::Poll};
+use std::{collections::VecDeque, fmt::Display, task::Poll};
+
+use aoc_common::Solution;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Move {
@@ -25,7 +27,7 @@ pub struct Map {
 }
 
 impl Map {
-    pub fn parse(mut lines: impl Iterator<Item = String>) -> Self {
+    pub fn parse<'a>(mut lines: impl Iterator<Item = &'a str>) -> Self {
         let mut map = Vec::new();
         let mut bot = (0, 0);
 
@@ -71,7 +73,7 @@ impl Map {
         assert!(!self
             .map
             .iter()
-            .flat_map(|r| r)
+            .flatten()
             .any(|c| c == &Space::BoxLeft || c == &Space::BoxRight));
 
         let mut new_map = Vec::new();
@@ -91,7 +93,7 @@ impl Map {
         }
 
         self.map = new_map;
-        self.bot.0 = self.bot.0 * 2;
+        self.bot.0 *= 2;
     }
 
     fn can_move(
@@ -206,27 +208,37 @@ impl Map {
 }
 
 fn main() {
-    let lines = std::io::stdin().lines().map(|v| v.unwrap());
-    let map = Map::parse(lines);
+    aoc_common::run::<Day15>();
+}
+
+struct Day15;
+
+impl Solution for Day15 {
+    type Parsed = Map;
+
+    fn parse(input: &str) -> Self::Parsed {
+        Map::parse(input.lines())
+    }
 
-    part1(map.clone());
-    part2(map.clone());
+    fn part1(map: &Self::Parsed) -> impl Display {
+        part1(map.clone())
+    }
+
+    fn part2(map: &Self::Parsed) -> impl Display {
+        part2(map.clone())
+    }
 }
 
-fn part1(mut map: Map) {
+fn part1(mut map: Map) -> usize {
     while map.poll_move().is_pending() {}
 
-    let box_sum: usize = map.box_gps_distances().sum();
-
-    println!("Part 1: {box_sum}");
+    map.box_gps_distances().sum()
 }
 
-fn part2(mut map: Map) {
+fn part2(mut map: Map) -> usize {
     map.expand();
 
     while map.poll_move().is_pending() {}
 
-    let box_sum: usize = map.box_gps_distances().sum();
-
-    println!("Part 2: {box_sum}");
+    map.box_gps_distances().sum()
 }
@@ -1,4 +1,6 @@
-use std::{collections::HashMap, usize};
+use std::{collections::HashMap, fmt::Display};
+
+use aoc_common::{Solution, Unsolved};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Space {
@@ -64,7 +66,7 @@ impl Direction {
 }
 
 impl Map {
-    pub fn parse(mut lines: impl Iterator<Item = String>) -> Self {
+    pub fn parse<'a>(mut lines: impl Iterator<Item = &'a str>) -> Self {
         let mut map = Vec::new();
         let mut end = (0, 0);
         let mut reindeer = (0, 0);
@@ -172,8 +174,23 @@ impl Map {
 }
 
 fn main() {
-    let lines = std::io::stdin().lines().map(|v| v.unwrap());
-    let map = Map::parse(lines);
+    aoc_common::run::<Day16>();
+}
+
+struct Day16;
 
-    println!("Part 1: {}", map.shortest_route());
+impl Solution for Day16 {
+    type Parsed = Map;
+
+    fn parse(input: &str) -> Self::Parsed {
+        Map::parse(input.lines())
+    }
+
+    fn part1(map: &Self::Parsed) -> impl Display {
+        map.shortest_route()
+    }
+
+    fn part2(_: &Self::Parsed) -> impl Display {
+        Unsolved
+    }
 }
@@ -1,3 +1,7 @@
+use std::fmt::Display;
+
+use aoc_common::{Solution, Unsolved};
+
 #[derive(Debug, Clone, Copy)]
 pub struct Regs {
     a: isize,
@@ -18,26 +22,43 @@ impl Regs {
 }
 
 fn main() {
-    let lines: Vec<_> = std::io::stdin().lines().map(|v| v.unwrap()).collect();
+    aoc_common::run::<Day17>();
+}
 
-    let a: isize = lines[0].split_once(": ").unwrap().1.parse().unwrap();
-    let b: isize = lines[1].split_once(": ").unwrap().1.parse().unwrap();
-    let c: isize = lines[2].split_once(": ").unwrap().1.parse().unwrap();
+struct Day17;
 
-    let program: Vec<isize> = lines[4]
-        .split_once(' ')
-        .unwrap()
-        .1
-        .split(',')
-        .map(|v| v.parse().unwrap())
-        .collect();
+impl Solution for Day17 {
+    /// The initial registers, and the program.
+    type Parsed = (Regs, Vec<isize>);
 
-    let regs = Regs { a, b, c };
+    fn parse(input: &str) -> Self::Parsed {
+        let lines: Vec<_> = input.lines().collect();
 
-    part1(regs, &program);
+        let a: isize = lines[0].split_once(": ").unwrap().1.parse().unwrap();
+        let b: isize = lines[1].split_once(": ").unwrap().1.parse().unwrap();
+        let c: isize = lines[2].split_once(": ").unwrap().1.parse().unwrap();
+
+        let program: Vec<isize> = lines[4]
+            .split_once(' ')
+            .unwrap()
+            .1
+            .split(',')
+            .map(|v| v.parse().unwrap())
+            .collect();
+
+        (Regs { a, b, c }, program)
+    }
+
+    fn part1((regs, program): &Self::Parsed) -> impl Display {
+        part1(*regs, program)
+    }
+
+    fn part2(_: &Self::Parsed) -> impl Display {
+        Unsolved
+    }
 }
 
-fn part1(mut regs: Regs, program: &[isize]) {
+fn part1(mut regs: Regs, program: &[isize]) -> String {
     let mut output: Vec<isize> = Vec::new();
 
     let mut ip = 0;
@@ -103,7 +124,5 @@ fn part1(mut regs: Regs, program: &[isize]) {
     }
 
     let output: Vec<_> = output.iter().map(|v| v.to_string()).collect();
-    let output = output.join(",");
-
-    println!("Part 1: {output}");
+    output.join(",")
 }
@@ -1,30 +1,46 @@
 use std::{
     collections::{BinaryHeap, HashMap, VecDeque},
-    time::Instant,
+    fmt::Display,
 };
 
+use aoc_common::Solution;
+
 fn main() {
-    let lines = std::io::stdin().lines().map(|v| v.unwrap());
+    aoc_common::run::<Day18>();
+}
 
-    let input: Vec<_> = lines
-        .map(|l| {
-            let (lhs, rhs) = l.split_once(',').unwrap();
+struct Day18;
 
-            let (lhs, rhs): (usize, usize) = (lhs.parse().unwrap(), rhs.parse().unwrap());
+impl Solution for Day18 {
+    type Parsed = Vec<(usize, usize)>;
 
-            (lhs, rhs)
-        })
-        .collect();
+    fn parse(input: &str) -> Self::Parsed {
+        input
+            .lines()
+            .map(|l| {
+                let (lhs, rhs) = l.split_once(',').unwrap();
+
+                let (lhs, rhs): (usize, usize) = (lhs.parse().unwrap(), rhs.parse().unwrap());
 
-    part1(&input);
-    part2(&input);
+                (lhs, rhs)
+            })
+            .collect()
+    }
+
+    fn part1(falls: &Self::Parsed) -> impl Display {
+        part1(falls)
+    }
+
+    fn part2(falls: &Self::Parsed) -> impl Display {
+        part2(falls)
+    }
 }
 
 const WIDTH: usize = 71;
 const HEIGHT: usize = 71;
 const FALLEN_BYTES: usize = 1024;
 
-fn part1(falls: &[(usize, usize)]) {
+fn part1(falls: &[(usize, usize)]) -> usize {
     let mut map: Vec<Vec<_>> = (0..HEIGHT)
         .map(|_| (0..WIDTH).map(|_| false).collect())
         .collect();
@@ -33,25 +49,11 @@ fn part1(falls: &[(usize, usize)]) {
         map[y][x] = true;
     }
 
-    let mut path = Vec::new();
-    let mut memoized = HashMap::new();
-    let result = min_path_len(
-        (0, 0),
-        (WIDTH - 1, HEIGHT - 1),
-        &mut path,
-        &map,
-        &mut memoized,
-    )
-    .unwrap();
-
-    println!("Part 1: {result}");
-
-    let a_star = min_path_len_astar((0, 0), (WIDTH - 1, HEIGHT - 1), &map).unwrap() - 1;
-
-    println!("Part 2: {a_star}");
+    // The path includes the start, which is not a step.
+    min_path_len_astar((0, 0), (WIDTH - 1, HEIGHT - 1), &map).expect("the exit is blocked") - 1
 }
 
-fn part2(falls: &[(usize, usize)]) {
+fn part2(falls: &[(usize, usize)]) -> String {
     let mut map: Vec<Vec<_>> = (0..HEIGHT)
         .map(|_| (0..WIDTH).map(|_| false).collect())
         .collect();
@@ -62,46 +64,17 @@ fn part2(falls: &[(usize, usize)]) {
         map[y][x] = true;
     }
 
-    let mut map_clone = map.clone();
-    let bytes_clone = fallen_bytes.clone();
-
-    let start = Instant::now();
-    let mut first_result = None;
-    for (x, y) in fallen_bytes {
-        let mut path = Vec::new();
-        let mut memoized = HashMap::new();
-        map[y][x] = true;
-        let result = min_path_len(
-            (0, 0),
-            (WIDTH - 1, HEIGHT - 1),
-            &mut path,
-            &map,
-            &mut memoized,
-        );
-
-        if result.is_none() {
-            println!("Part 2: ({x}, {y}) in {} ms", start.elapsed().as_millis());
-            first_result = Some((x, y));
-            break;
-        }
-    }
+    let (x, y) = fallen_bytes
+        .find(|&(x, y)| {
+            map[y][x] = true;
+            min_path_len_astar((0, 0), (WIDTH - 1, HEIGHT - 1), &map).is_none()
+        })
+        .expect("no byte blocks the exit");
 
-    let start = Instant::now();
-    for (x, y) in bytes_clone {
-        map_clone[y][x] = true;
-        let result = min_path_len_astar((0, 0), (WIDTH - 1, HEIGHT - 1), &map_clone);
-
-        if result.is_none() {
-            println!(
-                "Part 2 A*: ({x}, {y}) in {} ms",
-                start.elapsed().as_millis()
-            );
-            assert_eq!(Some((x, y)), first_result);
-            break;
-        }
-    }
+    format!("{x},{y}")
 }
 
+/// The neighbours of `(x, y)`: east, south, west, north.
 fn neighbours(
     (x, y): (usize, usize),
     (x_len, y_len): (usize, usize),
@@ -138,7 +111,7 @@ fn reconstruct_path(
 fn min_path_len_astar(
     start: (usize, usize),
     goal: (usize, usize),
-    map: &Vec<Vec<bool>>,
+    map: &[Vec<bool>],
 ) -> Option<usize> {
     struct HeapEntry {
         pos: (usize, usize),
@@ -155,7 +128,7 @@ fn min_path_len_astar(
 
     impl PartialOrd for HeapEntry {
         fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-            self.score.partial_cmp(&other.score)
+            Some(self.cmp(other))
         }
     }
 
@@ -209,49 +182,3 @@ fn min_path_len_astar(
 
     None
 }
-
-fn min_path_len(
-    current: (usize, usize),
-    destination: (usize, usize),
-    path: &mut Vec<(usize, usize)>,
-    map: &Vec<Vec<bool>>,
-    memoized: &mut HashMap<(usize, usize), Option<usize>>,
-) -> Option<usize> {
-    let x_len = map[0].len();
-    let y_len = map.len();
-
-    if current == destination {
-        return Some(0);
-    }
-
-    if path.contains(&current) {
-        return None;
-    }
-
-    if let Some(memoized) = memoized.get(&current) {
-        return memoized.clone();
-    }
-
-    path.push(current);
-
-    let mut min = None;
-    for (nb_x, nb_y) in neighbours(current, (x_len, y_len)) {
-        if map[nb_y][nb_x] {
-            continue;
-        }
-
-        let min_from_nb = min_path_len((nb_x, nb_y), destination, path, map, memoized);
-
-        if let Some(min_from_nb) = min_from_nb {
-            let len = min_from_nb + 1;
-            let min = min.get_or_insert(len);
-            *min = (*min).min(len);
-        }
-    }
-
-    path.pop();
-
-    memoized.insert(current, min);
-
-    min
-}
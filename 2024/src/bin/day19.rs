@@ -1,22 +1,45 @@
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+};
+
+use aoc_common::Solution;
 
 fn main() {
-    let mut lines = std::io::stdin().lines().map(|v| v.unwrap());
+    aoc_common::run::<Day19>();
+}
+
+struct Day19;
+
+impl Solution for Day19 {
+    /// The available towels, and the patterns to make with them.
+    type Parsed = (Vec<String>, Vec<String>);
 
-    let available_towels: Vec<_> = lines
-        .next()
-        .unwrap()
-        .split(", ")
-        .map(str::to_string)
-        .collect();
+    fn parse(input: &str) -> Self::Parsed {
+        let mut lines = input.lines();
 
-    let desired_patterns: Vec<_> = lines.skip(1).collect();
+        let available_towels: Vec<_> = lines
+            .next()
+            .unwrap()
+            .split(", ")
+            .map(str::to_string)
+            .collect();
+
+        let desired_patterns: Vec<_> = lines.skip(1).map(str::to_string).collect();
+
+        (available_towels, desired_patterns)
+    }
 
-    part1(&available_towels, &desired_patterns);
-    part2(&available_towels, &desired_patterns);
+    fn part1((towels, desired_patterns): &Self::Parsed) -> impl Display {
+        part1(towels, desired_patterns)
+    }
+
+    fn part2((towels, desired_patterns): &Self::Parsed) -> impl Display {
+        part2(towels, desired_patterns)
+    }
 }
 
-fn part1(towels: &[String], desired_patterns: &[String]) {
+fn part1(towels: &[String], desired_patterns: &[String]) -> usize {
     let mut sum = 0;
     let mut rec_sum = 0;
 
@@ -32,7 +55,7 @@ fn part1(towels: &[String], desired_patterns: &[String]) {
 
     assert_eq!(sum, rec_sum);
 
-    println!("Part 1: {sum}");
+    sum
 }
 
 fn is_possible(towels: &[String], pattern: &str) -> bool {
@@ -53,7 +76,7 @@ fn is_possible(towels: &[String], pattern: &str) -> bool {
         }
     }
 
-    return false;
+    false
 }
 
 fn is_possible_rec(towels: &[String], pattern: &str) -> bool {
@@ -70,7 +93,7 @@ fn is_possible_rec(towels: &[String], pattern: &str) -> bool {
     false
 }
 
-fn part2(towels: &[String], desired_patterns: &[String]) {
+fn part2(towels: &[String], desired_patterns: &[String]) -> usize {
     let mut sum = 0;
     let mut rec_sum = 0;
 
@@ -83,7 +106,7 @@ fn part2(towels: &[String], desired_patterns: &[String]) {
 
     assert_eq!(sum, rec_sum);
 
-    println!("Part 2: {sum}");
+    sum
 }
 
 fn possible_patterns(
@@ -1,16 +1,33 @@
+use std::fmt::Display;
+
+use aoc_common::Solution;
+
 fn main() {
-    let lines: Vec<_> = std::io::stdin().lines().map(|v| v.unwrap()).collect();
+    aoc_common::run::<Day2>();
+}
 
-    let reports: Vec<Vec<usize>> = lines
-        .iter()
-        .map(|l| l.split(' ').map(|v| v.parse::<usize>().unwrap()).collect())
-        .collect();
+struct Day2;
 
-    part1(reports.clone());
-    part2(reports.clone());
+impl Solution for Day2 {
+    type Parsed = Vec<Vec<usize>>;
+
+    fn parse(input: &str) -> Self::Parsed {
+        input
+            .lines()
+            .map(|l| l.split(' ').map(|v| v.parse::<usize>().unwrap()).collect())
+            .collect()
+    }
+
+    fn part1(reports: &Self::Parsed) -> impl Display {
+        part1(reports.clone())
+    }
+
+    fn part2(reports: &Self::Parsed) -> impl Display {
+        part2(reports.clone())
+    }
 }
 
-fn part1(reports: Vec<Vec<usize>>) {
+fn part1(reports: Vec<Vec<usize>>) -> usize {
     let mut safe_reports = 0;
 
     for report in reports {
@@ -19,10 +36,10 @@ fn part1(reports: Vec<Vec<usize>>) {
         }
     }
 
-    println!("Part 1: {safe_reports}");
+    safe_reports
 }
 
-fn part2(reports: Vec<Vec<usize>>) {
+fn part2(reports: Vec<Vec<usize>>) -> usize {
     let mut safe_reports = 0;
 
     for report in reports {
@@ -31,11 +48,11 @@ fn part2(reports: Vec<Vec<usize>>) {
         }
     }
 
-    println!("Part 2: {safe_reports}");
+    safe_reports
 }
 
 fn part2_rec(mut report: Vec<usize>) -> bool {
-    if report.len() == 0 || report.len() == 1 {
+    if report.len() <= 1 {
         return true;
     }
 
@@ -65,7 +82,7 @@ fn report_is_safe<'a>(mut report: impl Iterator<Item = &'a usize>) -> bool {
         let max = prev.max(level);
         let diff = max - min;
 
-        all_have_right_diff &= diff >= 1 && diff <= 3;
+        all_have_right_diff &= (1..=3).contains(&diff);
         all_decreasing &= level < prev;
         all_increasing &= level > prev;
 
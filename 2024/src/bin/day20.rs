@@ -1,3 +1,7 @@
+use std::fmt::Display;
+
+use aoc_common::{Solution, Unsolved};
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Space {
     Wall,
@@ -10,6 +14,8 @@ impl Space {
     }
 }
 
+// Nothing reads the map yet: neither part has been solved.
+#[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub struct Map {
     start: (isize, isize),
@@ -18,7 +24,7 @@ pub struct Map {
 }
 
 impl Map {
-    pub fn parse(mut lines: impl Iterator<Item = String>) -> Self {
+    pub fn parse<'a>(mut lines: impl Iterator<Item = &'a str>) -> Self {
         let mut map = Vec::new();
         let mut end = (0, 0);
         let mut reindeer = (0, 0);
@@ -53,7 +59,25 @@ impl Map {
         }
     }
 }
+
 fn main() {
-    let lines = std::io::stdin().lines().map(|v| v.unwrap());
-    let map = Map::parse(lines);
+    aoc_common::run::<Day20>();
+}
+
+struct Day20;
+
+impl Solution for Day20 {
+    type Parsed = Map;
+
+    fn parse(input: &str) -> Self::Parsed {
+        Map::parse(input.lines())
+    }
+
+    fn part1(_: &Self::Parsed) -> impl Display {
+        Unsolved
+    }
+
+    fn part2(_: &Self::Parsed) -> impl Display {
+        Unsolved
+    }
 }
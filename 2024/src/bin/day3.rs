@@ -1,46 +1,66 @@
+use std::fmt::Display;
+
+use aoc_common::Solution;
+
 fn main() {
-    let input: String = std::io::stdin().lines().map(|v| v.unwrap()).collect();
+    aoc_common::run::<Day3>();
+}
 
-    part1(&input);
-    part2(&input);
+struct Day3;
+
+impl Solution for Day3 {
+    /// The program, with its lines joined together.
+    type Parsed = String;
+
+    fn parse(input: &str) -> Self::Parsed {
+        input.lines().collect()
+    }
+
+    fn part1(input: &Self::Parsed) -> impl Display {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Parsed) -> impl Display {
+        part2(input)
+    }
 }
 
-fn part1(mut input: &str) {
+fn part1(mut input: &str) -> usize {
     let mut sum = 0;
     for _ in 0..input.len() {
-        if let Some((len, val)) = find_mul(&input) {
+        if let Some((len, val)) = find_mul(input) {
             input = &input[len..];
             sum += val;
-        } else if input.len() > 0 {
+        } else if !input.is_empty() {
             input = &input[1..];
         } else {
             break;
         }
     }
 
-    println!("Part 1: {sum}");
+    sum
 }
 
-fn part2(mut input: &str) {
+fn part2(mut input: &str) -> usize {
     let mut sum = 0;
     let mut enabled = true;
     for _ in 0..input.len() {
-        if let Some((len, val)) = find_mul(&input) {
+        if let Some((len, val)) = find_mul(input) {
             input = &input[len..];
             if enabled {
                 sum += val;
             }
-        } else if let Some((len, val)) = do_or_dont(&input) {
+        } else if let Some((len, val)) = do_or_dont(input) {
             input = &input[len..];
             enabled = val;
-        } else if input.len() > 0 {
+        } else if !input.is_empty() {
             input = &input[1..];
         } else {
             break;
         }
     }
 
-    println!("Part 2: {sum}");
+    sum
 }
 
 fn do_or_dont(input: &str) -> Option<(usize, bool)> {
@@ -54,11 +74,7 @@ fn do_or_dont(input: &str) -> Option<(usize, bool)> {
 }
 
 fn find_mul(input: &str) -> Option<(usize, usize)> {
-    let nums_endparen = if input.starts_with("mul(") {
-        &input[4..]
-    } else {
-        return None;
-    };
+    let nums_endparen = input.strip_prefix("mul(")?;
 
     let mut num1 = 0;
     let mut num2 = 0;
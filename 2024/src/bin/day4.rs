@@ -1,6 +1,10 @@
+use std::fmt::Display;
+
+use aoc_common::Solution;
+
 type Modifier = fn((usize, usize)) -> Option<(usize, usize)>;
 
-const TESTS: [fn((usize, usize)) -> Option<(usize, usize)>; 8] = [
+const TESTS: [Modifier; 8] = [
     |(x, y): (usize, usize)| Some((x + 1, y)),
     |(x, y): (usize, usize)| Some((x, y + 1)),
     |(x, y): (usize, usize)| Some((x + 1, y + 1)),
@@ -15,16 +19,28 @@ const TESTS: [fn((usize, usize)) -> Option<(usize, usize)>; 8] = [
 ];
 
 fn main() {
-    let lines: Vec<Vec<char>> = std::io::stdin()
-        .lines()
-        .map(|v| v.unwrap().chars().collect())
-        .collect();
+    aoc_common::run::<Day4>();
+}
+
+struct Day4;
+
+impl Solution for Day4 {
+    type Parsed = Vec<Vec<char>>;
 
-    part1(&lines);
-    part2(&lines);
+    fn parse(input: &str) -> Self::Parsed {
+        input.lines().map(|v| v.chars().collect()).collect()
+    }
+
+    fn part1(lines: &Self::Parsed) -> impl Display {
+        part1(lines)
+    }
+
+    fn part2(lines: &Self::Parsed) -> impl Display {
+        part2(lines)
+    }
 }
 
-fn part1(lines: &Vec<Vec<char>>) {
+fn part1(lines: &[Vec<char>]) -> usize {
     let mut starts = 0;
     for y in 0..lines.len() {
         for x in 0..lines[y].len() {
@@ -36,13 +52,13 @@ fn part1(lines: &Vec<Vec<char>>) {
         }
     }
 
-    println!("Part 1: {starts}");
+    starts
 }
 
 fn is_start<const N: usize>(
     str: [char; N],
     mut location: (usize, usize),
-    lines: &Vec<Vec<char>>,
+    lines: &[Vec<char>],
     offset_op: &Modifier,
 ) -> bool {
     let eq_and_inbounds = |(x, y): (usize, usize), val: char| {
@@ -59,8 +75,7 @@ fn is_start<const N: usize>(
         }
     };
 
-    for idx in 0..str.len() {
-        let next = str[idx];
+    for next in str {
         if !eq_and_inbounds(location, next) {
             return false;
         }
@@ -74,10 +89,10 @@ fn is_start<const N: usize>(
         }
     }
 
-    return true;
+    true
 }
 
-fn part2(lines: &Vec<Vec<char>>) {
+fn part2(lines: &[Vec<char>]) -> usize {
     let mut count = 0;
     for y in 0..lines.len() {
         for x in 0..lines[y].len() {
@@ -87,10 +102,10 @@ fn part2(lines: &Vec<Vec<char>>) {
         }
     }
 
-    println!("Part 2: {count}");
+    count
 }
 
-fn is_x_mas_start(location: (usize, usize), lines: &Vec<Vec<char>>) -> bool {
+fn is_x_mas_start(location: (usize, usize), lines: &[Vec<char>]) -> bool {
     let (x, y) = location;
     let corner_ops = [
         ((x, y), TESTS[2]),
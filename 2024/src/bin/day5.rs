@@ -1,33 +1,51 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt::Display};
+
+use aoc_common::Solution;
 
 fn main() {
-    let input: Vec<String> = std::io::stdin().lines().map(|v| v.unwrap()).collect();
+    aoc_common::run::<Day5>();
+}
+
+struct Day5;
 
-    let mut prerequisite_rules = HashMap::new();
+impl Solution for Day5 {
+    /// The pages that must come before each page, and the updates.
+    type Parsed = (HashMap<usize, Vec<usize>>, Vec<Vec<usize>>);
+
+    fn parse(input: &str) -> Self::Parsed {
+        let mut prerequisite_rules = HashMap::new();
+
+        let mut input_iter = input.lines();
+        for line in &mut input_iter {
+            if line.is_empty() {
+                break;
+            }
 
-    let mut input_iter = input.iter();
-    for line in &mut input_iter {
-        if line.is_empty() {
-            break;
+            let (prerequisite, page) = line.split_once('|').unwrap();
+            let (prerequisite, page): (usize, usize) =
+                (prerequisite.parse().unwrap(), page.parse().unwrap());
+
+            let prerequisites = prerequisite_rules.entry(page).or_insert_with(Vec::new);
+            prerequisites.push(prerequisite);
         }
 
-        let (prerequisite, page) = line.split_once('|').unwrap();
-        let (prerequisite, page): (usize, usize) =
-            (prerequisite.parse().unwrap(), page.parse().unwrap());
+        let mut updates: Vec<Vec<usize>> = Vec::new();
 
-        let prerequisites = prerequisite_rules.entry(page).or_insert(Vec::new());
-        prerequisites.push(prerequisite);
-    }
+        for full_update in input_iter {
+            let update: Vec<usize> = full_update.split(',').map(|v| v.parse().unwrap()).collect();
+            updates.push(update);
+        }
 
-    let mut updates: Vec<Vec<usize>> = Vec::new();
+        (prerequisite_rules, updates)
+    }
 
-    for full_update in input_iter {
-        let update: Vec<usize> = full_update.split(',').map(|v| v.parse().unwrap()).collect();
-        updates.push(update);
+    fn part1((prerequisites, updates): &Self::Parsed) -> impl Display {
+        part1(prerequisites, updates)
     }
 
-    part1(&prerequisite_rules, &updates);
-    part2(&prerequisite_rules, &updates);
+    fn part2((prerequisites, updates): &Self::Parsed) -> impl Display {
+        part2(prerequisites, updates)
+    }
 }
 
 fn is_valid(prerequisites: &HashMap<usize, Vec<usize>>, update: &[usize]) -> bool {
@@ -52,11 +70,11 @@ fn missing_prerequisites(
         .filter(|prerequisite| {
             !previous_pages.contains(prerequisite) && update.contains(prerequisite)
         })
-        .map(|v| *v)
+        .copied()
         .collect()
 }
 
-fn part1(prerequisites: &HashMap<usize, Vec<usize>>, updates: &Vec<Vec<usize>>) {
+fn part1(prerequisites: &HashMap<usize, Vec<usize>>, updates: &[Vec<usize>]) -> usize {
     let mut sum = 0;
 
     for update in updates {
@@ -67,13 +85,13 @@ fn part1(prerequisites: &HashMap<usize, Vec<usize>>, updates: &Vec<Vec<usize>>)
         }
     }
 
-    println!("Part 1: {sum}");
+    sum
 }
 
-fn part2(prerequisites: &HashMap<usize, Vec<usize>>, updates: &Vec<Vec<usize>>) {
+fn part2(prerequisites: &HashMap<usize, Vec<usize>>, updates: &[Vec<usize>]) -> usize {
     let mut sum = 0;
 
-    for mut update in updates.iter().map(|v| v.clone()) {
+    for mut update in updates.iter().cloned() {
         if is_valid(prerequisites, &update) {
             continue;
         }
@@ -84,11 +102,9 @@ fn part2(prerequisites: &HashMap<usize, Vec<usize>>, updates: &Vec<Vec<usize>>)
 
                 let mut insert_idx = None;
                 for missing in missing_prerequisites {
-                    for idx in page_idx..update.len() {
-                        if update[idx] == missing {
-                            insert_idx = Some(usize::max(*insert_idx.get_or_insert(idx), idx));
-                            break;
-                        }
+                    if let Some(offset) = update[page_idx..].iter().position(|p| *p == missing) {
+                        let idx = page_idx + offset;
+                        insert_idx = Some(usize::max(*insert_idx.get_or_insert(idx), idx));
                     }
                 }
 
@@ -104,5 +120,5 @@ fn part2(prerequisites: &HashMap<usize, Vec<usize>>, updates: &Vec<Vec<usize>>)
         sum += update[update.len() / 2];
     }
 
-    println!("Part 2: {sum}");
+    sum
 }
@@ -1,47 +1,6 @@
-use std::time::Instant;
+use std::fmt::Display;
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-#[repr(usize)]
-pub enum Direction {
-    North,
-    East,
-    South,
-    West,
-}
-
-impl Direction {
-    pub fn rotate_right(&mut self) {
-        *self = match self {
-            Direction::North => Direction::East,
-            Direction::East => Direction::South,
-            Direction::South => Direction::West,
-            Direction::West => Direction::North,
-        };
-    }
-
-    pub fn make_move(
-        &self,
-        (x, y): (usize, usize),
-        (x_len, y_len): (usize, usize),
-    ) -> Option<(usize, usize)> {
-        let (x, y) = match self {
-            Direction::North => (x, y.checked_sub(1)?),
-            Direction::East => (x + 1, y),
-            Direction::South => (x, y + 1),
-            Direction::West => (x.checked_sub(1)?, y),
-        };
-
-        if x >= x_len {
-            return None;
-        }
-
-        if y >= y_len {
-            return None;
-        }
-
-        return Some((x, y));
-    }
-}
+use aoc_common::{grid::Direction, Solution};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum Location {
@@ -57,36 +16,48 @@ impl Location {
 }
 
 fn main() {
-    let input: Vec<String> = std::io::stdin().lines().map(|v| v.unwrap()).collect();
-
-    let mut map = Vec::new();
-    let mut guard_position = (0, 0);
-
-    let mut y = 0;
-    for line in input {
-        let mut row = Vec::new();
-        let mut x = 0;
-        for char in line.chars() {
-            match char {
-                '.' => row.push(Location::NotVisited),
-                '^' => {
-                    row.push(Location::Visited);
-                    guard_position = (x, y);
+    aoc_common::run::<Day6>();
+}
+
+struct Day6;
+
+impl Solution for Day6 {
+    /// The map, and where the guard starts.
+    type Parsed = (Vec<Vec<Location>>, (usize, usize));
+
+    fn parse(input: &str) -> Self::Parsed {
+        let mut map = Vec::new();
+        let mut guard_position = (0, 0);
+
+        for (y, line) in input.lines().enumerate() {
+            let mut row = Vec::new();
+            for (x, char) in line.chars().enumerate() {
+                match char {
+                    '.' => row.push(Location::NotVisited),
+                    '^' => {
+                        row.push(Location::Visited);
+                        guard_position = (x, y);
+                    }
+                    _ => row.push(Location::Obstructed),
                 }
-                _ => row.push(Location::Obstructed),
             }
-            x += 1;
+            map.push(row);
         }
-        map.push(row);
-        y += 1;
+
+        (map, guard_position)
+    }
+
+    fn part1((map, guard_position): &Self::Parsed) -> impl Display {
+        part1(map.clone(), *guard_position)
     }
 
-    part1(map.clone(), guard_position);
-    part2(map.clone(), guard_position);
+    fn part2((map, guard_position): &Self::Parsed) -> impl Display {
+        part2(map.clone(), *guard_position)
+    }
 }
 
 fn walk(
-    map: &mut Vec<Vec<Location>>,
+    map: &mut [Vec<Location>],
     mut guard_position: (usize, usize),
 ) -> (impl Iterator<Item = (usize, usize)> + '_, bool) {
     let mut direction = Direction::North;
@@ -103,12 +74,12 @@ fn walk(
         let mut next_position = None;
 
         for _ in 0..3 {
-            if let Some((new_x, new_y)) = direction.make_move(guard_position, (x_len, y_len)) {
+            if let Some((new_x, new_y)) = direction.step(guard_position, (x_len, y_len)) {
                 if !map[new_y][new_x].is_obstructed() {
                     next_position = Some((new_x, new_y));
                     break;
                 } else {
-                    direction.rotate_right();
+                    direction = direction.turn_right();
                 }
             }
         }
@@ -137,18 +108,16 @@ fn walk(
     (visited_positions, is_loop)
 }
 
-fn part1(mut map: Vec<Vec<Location>>, guard_position: (usize, usize)) {
+fn part1(mut map: Vec<Vec<Location>>, guard_position: (usize, usize)) -> usize {
     let (visited_positions, _) = walk(&mut map, guard_position);
-    let count = visited_positions.count();
-    println!("Part 1: {count}");
+    visited_positions.count()
 }
 
-fn part2(map: Vec<Vec<Location>>, guard_position: (usize, usize)) {
+fn part2(map: Vec<Vec<Location>>, guard_position: (usize, usize)) -> usize {
     let mut map_clone = map.clone();
     let (places_to_block, _) = walk(&mut map_clone, guard_position);
 
     let mut loops = 0;
-    let start = Instant::now();
     for (x, y) in places_to_block {
         if (x, y) == guard_position {
             continue;
@@ -162,5 +131,5 @@ fn part2(map: Vec<Vec<Location>>, guard_position: (usize, usize)) {
         }
     }
 
-    println!("Part 2: {loops}. {} ms", start.elapsed().as_millis());
+    loops
 }
@@ -1,3 +1,7 @@
+use std::fmt::Display;
+
+use aoc_common::Solution;
+
 #[derive(Clone)]
 struct Equation {
     answer: usize,
@@ -17,30 +21,45 @@ impl std::fmt::Display for Equation {
     }
 }
 
-const PLUS: &'static str = "+";
-const STAR: &'static str = "*";
-const CONCAT: &'static str = "||";
+const PLUS: &str = "+";
+const STAR: &str = "*";
+const CONCAT: &str = "||";
 
 fn main() {
-    let lines: Vec<_> = std::io::stdin().lines().map(|v| v.unwrap()).collect();
+    aoc_common::run::<Day7>();
+}
+
+struct Day7;
+
+impl Solution for Day7 {
+    type Parsed = Vec<Equation>;
 
-    let mut equations = Vec::new();
+    fn parse(input: &str) -> Self::Parsed {
+        let mut equations = Vec::new();
 
-    for line in lines {
-        let (answer, parts) = line.split_once(": ").unwrap();
-        let answer: usize = answer.parse().unwrap();
+        for line in input.lines() {
+            let (answer, parts) = line.split_once(": ").unwrap();
+            let answer: usize = answer.parse().unwrap();
 
-        let parts = parts.split(' ').map(|p| p.parse().unwrap()).collect();
+            let parts = parts.split(' ').map(|p| p.parse().unwrap()).collect();
 
-        equations.push(Equation {
-            answer,
-            parts,
-            operators: Vec::new(),
-        })
+            equations.push(Equation {
+                answer,
+                parts,
+                operators: Vec::new(),
+            })
+        }
+
+        equations
     }
 
-    part1(equations.clone());
-    part2(equations.clone());
+    fn part1(equations: &Self::Parsed) -> impl Display {
+        part1(equations.clone())
+    }
+
+    fn part2(equations: &Self::Parsed) -> impl Display {
+        part2(equations.clone())
+    }
 }
 
 type MapFn = fn(usize, usize) -> usize;
@@ -61,7 +80,7 @@ fn concat(a: usize, b: usize) -> usize {
     current_shifted + b
 }
 
-fn part1(mut equations: Vec<Equation>) {
+fn part1(mut equations: Vec<Equation>) -> usize {
     let mut sum = 0;
 
     let operators = [(PLUS, add as MapFn), (STAR, multiply as MapFn)];
@@ -69,14 +88,13 @@ fn part1(mut equations: Vec<Equation>) {
     for equation in equations.iter_mut() {
         if is_possible(equation.answer, equation.parts[0], 1, &operators, equation) {
             sum += equation.answer;
-            println!("{equation}");
         }
     }
 
-    println!("Part 1: {sum}");
+    sum
 }
 
-fn part2(mut equations: Vec<Equation>) {
+fn part2(mut equations: Vec<Equation>) -> usize {
     let mut sum = 0;
 
     let operators = [
@@ -88,18 +106,17 @@ fn part2(mut equations: Vec<Equation>) {
     for equation in equations.iter_mut() {
         if is_possible(equation.answer, equation.parts[0], 1, &operators, equation) {
             sum += equation.answer;
-            println!("{equation}");
         }
     }
 
-    println!("Part 2: {sum}");
+    sum
 }
 
 fn is_possible(
     goal: usize,
     current: usize,
     idx: usize,
-    operators: &[(&'static str, fn(usize, usize) -> usize)],
+    operators: &[(&'static str, MapFn)],
     equation: &mut Equation,
 ) -> bool {
     let current_part = if let Some(current_part) = equation.parts.get(idx) {
@@ -1,20 +1,38 @@
+use std::fmt::Display;
+
+use aoc_common::Solution;
+
 type AntennaMap = Vec<((usize, usize), char)>;
 
 fn main() {
-    let lines: Vec<_> = std::io::stdin().lines().map(|v| v.unwrap()).collect();
+    aoc_common::run::<Day8>();
+}
 
-    let (dim, antenna_locations) = parse(&lines);
+struct Day8;
+
+impl Solution for Day8 {
+    /// The size of the map, and the antennas on it.
+    type Parsed = ((usize, usize), AntennaMap);
+
+    fn parse(input: &str) -> Self::Parsed {
+        parse(input)
+    }
 
-    part1(dim, &antenna_locations);
-    part2(dim, &antenna_locations);
+    fn part1((dim, antenna_locations): &Self::Parsed) -> impl Display {
+        part1(*dim, antenna_locations)
+    }
+
+    fn part2((dim, antenna_locations): &Self::Parsed) -> impl Display {
+        part2(*dim, antenna_locations)
+    }
 }
 
-fn parse(input: &[String]) -> ((usize, usize), AntennaMap) {
+fn parse(input: &str) -> ((usize, usize), AntennaMap) {
     let mut antenna_locations = Vec::new();
     let mut x_len = 0;
     let mut y_len = 0;
 
-    for (y, line) in input.into_iter().enumerate() {
+    for (y, line) in input.lines().enumerate() {
         y_len += 1;
         let mut x_len_inner = 0;
         for (x, char) in line.chars().enumerate() {
@@ -36,7 +54,7 @@ fn count(map: Vec<Vec<bool>>) -> usize {
         .count()
 }
 
-fn part1((x_len, y_len): (usize, usize), antenna_locations: &AntennaMap) {
+fn part1((x_len, y_len): (usize, usize), antenna_locations: &AntennaMap) -> usize {
     let mut map: Vec<_> = (0..y_len).map(|_| vec![false; x_len]).collect();
 
     for ((x1, y1), freq1) in antenna_locations.iter().cloned() {
@@ -53,9 +71,7 @@ fn part1((x_len, y_len): (usize, usize), antenna_locations: &AntennaMap) {
         }
     }
 
-    let antinodes = count(map);
-
-    println!("Part 1: {antinodes}");
+    count(map)
 }
 
 fn calculate_antinode_positions_p1(
@@ -90,7 +106,7 @@ fn calculate_antinode_positions_p1(
     positions
 }
 
-fn part2((x_len, y_len): (usize, usize), antenna_locations: &AntennaMap) {
+fn part2((x_len, y_len): (usize, usize), antenna_locations: &AntennaMap) -> usize {
     let mut map: Vec<_> = (0..y_len).map(|_| vec![false; x_len]).collect();
 
     for (p1, freq1) in antenna_locations.iter().cloned() {
@@ -103,9 +119,7 @@ fn part2((x_len, y_len): (usize, usize), antenna_locations: &AntennaMap) {
         }
     }
 
-    let antinodes = count(map);
-
-    println!("Part 2: {antinodes}");
+    count(map)
 }
 
 fn calculate_antinode_positions_p2(
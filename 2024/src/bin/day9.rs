@@ -1,17 +1,35 @@
-use std::usize;
+use std::fmt::Display;
+
+use aoc_common::Solution;
 
 fn main() {
-    let first_line = std::io::stdin().lines().map(|v| v.unwrap()).next().unwrap();
-    let block_counts: Vec<usize> = first_line
-        .chars()
-        .map(|v| std::iter::once(v).collect::<String>().parse().unwrap())
-        .collect();
+    aoc_common::run::<Day9>();
+}
+
+struct Day9;
+
+impl Solution for Day9 {
+    /// The length of every file and free space, in order.
+    type Parsed = Vec<usize>;
 
-    part1(&block_counts);
-    part2(&block_counts);
+    fn parse(input: &str) -> Self::Parsed {
+        let first_line = input.lines().next().unwrap();
+        first_line
+            .chars()
+            .map(|v| std::iter::once(v).collect::<String>().parse().unwrap())
+            .collect()
+    }
+
+    fn part1(block_counts: &Self::Parsed) -> impl Display {
+        part1(block_counts)
+    }
+
+    fn part2(block_counts: &Self::Parsed) -> impl Display {
+        part2(block_counts)
+    }
 }
 
-fn part1(block_counts: &[usize]) {
+fn part1(block_counts: &[usize]) -> usize {
     let mut block_id_map = parse(block_counts);
 
     let mut free_idx = block_id_map
@@ -37,11 +55,10 @@ fn part1(block_counts: &[usize]) {
         }
     }
 
-    let checksum = checksum(&block_id_map);
-    println!("Part 1: {checksum}");
+    checksum(&block_id_map)
 }
 
-fn part2(block_counts: &[usize]) {
+fn part2(block_counts: &[usize]) -> usize {
     let mut blocks = Vec::new();
     let mut free_spaces = Vec::new();
 
@@ -96,20 +113,19 @@ fn part2(block_counts: &[usize]) {
         )
         .collect();
 
-    fs_compressed.sort_by(|(_, o1, _), (_, o2, _)| o1.cmp(o2));
+    fs_compressed.sort_by_key(|(_, offset, _)| *offset);
 
     let fs: Vec<_> = fs_compressed
         .into_iter()
         .flat_map(|(id, _, len)| (0..len).map(move |_| id))
         .collect();
 
-    let checksum = checksum(&fs);
-    println!("Part 2: {:?}", checksum);
+    checksum(&fs)
 }
 
 fn checksum(fs: &[Option<usize>]) -> usize {
     let mut sum = 0;
-    for (idx, id) in fs.into_iter().enumerate() {
+    for (idx, id) in fs.iter().enumerate() {
         if let Some(id) = id {
             sum += idx * id;
         }
@@ -1,20 +1,38 @@
+use std::fmt::Display;
+
+use aoc_common::Solution;
+
 fn main() {
-    let lines = std::io::stdin().lines().map(|v| v.unwrap());
+    aoc_common::run::<Day1>();
+}
 
-    let rotations: Vec<_> = lines
-        .map(|v| {
-            let count: usize = v[1..].parse().unwrap();
+struct Day1;
 
-            if v.chars().next() == Some('L') {
-                Rotation::Left(count)
-            } else {
-                Rotation::Right(count)
-            }
-        })
-        .collect();
+impl Solution for Day1 {
+    type Parsed = Vec<Rotation>;
+
+    fn parse(input: &str) -> Self::Parsed {
+        input
+            .lines()
+            .map(|v| {
+                let count: usize = v[1..].parse().unwrap();
+
+                if v.starts_with('L') {
+                    Rotation::Left(count)
+                } else {
+                    Rotation::Right(count)
+                }
+            })
+            .collect()
+    }
 
-    part1(&rotations);
-    part2(&rotations);
+    fn part1(rotations: &Self::Parsed) -> impl Display {
+        part1(rotations)
+    }
+
+    fn part2(rotations: &Self::Parsed) -> impl Display {
+        part2(rotations)
+    }
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -23,7 +41,7 @@ enum Rotation {
     Right(usize),
 }
 
-fn part1(rotation: &[Rotation]) {
+fn part1(rotation: &[Rotation]) -> usize {
     let mut zeros = 0;
     let mut current_position = 50;
 
@@ -49,10 +67,10 @@ fn part1(rotation: &[Rotation]) {
         }
     }
 
-    println!("Part 1: {zeros}");
+    zeros
 }
 
-fn part2(rotation: &[Rotation]) {
+fn part2(rotation: &[Rotation]) -> usize {
     let mut zeros = 0;
     let mut current_position = 50;
 
@@ -84,5 +102,5 @@ fn part2(rotation: &[Rotation]) {
         }
     }
 
-    println!("Part 2: {zeros}");
+    zeros
 }
@@ -1,22 +1,41 @@
-use std::ops::RangeInclusive;
+use std::{fmt::Display, ops::RangeInclusive};
+
+use aoc_common::Solution;
 
 fn main() {
-    let range_strs = std::io::stdin().lines().map(|v| v.unwrap()).next().unwrap();
+    aoc_common::run::<Day2>();
+}
+
+struct Day2;
+
+impl Solution for Day2 {
+    type Parsed = Vec<RangeInclusive<usize>>;
+
+    fn parse(input: &str) -> Self::Parsed {
+        let range_strs = input.lines().next().unwrap();
 
-    let mut ranges = Vec::new();
-    for range in range_strs.split(',') {
-        let (from, to) = range.split_once('-').unwrap();
+        let mut ranges = Vec::new();
+        for range in range_strs.split(',') {
+            let (from, to) = range.split_once('-').unwrap();
 
-        let (from, to): (usize, usize) = (from.parse().unwrap(), to.parse().unwrap());
-        ranges.push(from..=to);
+            let (from, to): (usize, usize) = (from.parse().unwrap(), to.parse().unwrap());
+            ranges.push(from..=to);
+        }
+
+        ranges
     }
 
-    part1(&ranges);
-    part2(&ranges);
-    assert!(!invalid_pt2(60606));
+    fn part1(ranges: &Self::Parsed) -> impl Display {
+        part1(ranges)
+    }
+
+    fn part2(ranges: &Self::Parsed) -> impl Display {
+        assert!(!invalid_pt2(60606));
+        part2(ranges)
+    }
 }
 
-fn part1(ranges: &[RangeInclusive<usize>]) {
+fn part1(ranges: &[RangeInclusive<usize>]) -> usize {
     let mut sum = 0;
     for range in ranges.iter().cloned() {
         for value in range {
@@ -25,21 +44,21 @@ fn part1(ranges: &[RangeInclusive<usize>]) {
             }
         }
     }
-    println!("Part 1: {sum}");
+    sum
 }
 
 fn invalid(num: usize) -> bool {
     let n_digits = num.ilog10() + 1;
     let pow = 10u32.pow(n_digits / 2) as usize;
 
-    if n_digits % 2 == 0 {
+    if n_digits.is_multiple_of(2) {
         num / pow == num % pow
     } else {
         false
     }
 }
 
-fn part2(ranges: &[RangeInclusive<usize>]) {
+fn part2(ranges: &[RangeInclusive<usize>]) -> usize {
     let mut sum = 0;
     for range in ranges.iter().cloned() {
         for value in range {
@@ -48,12 +67,12 @@ fn part2(ranges: &[RangeInclusive<usize>]) {
             }
         }
     }
-    println!("Part 2: {sum}");
+    sum
 }
 
 fn invalid_pt2(num: usize) -> bool {
     let log = num.ilog10();
-    let add = if log % 2 == 0 { 0 } else { 1 };
+    let add = if log.is_multiple_of(2) { 0 } else { 1 };
 
     for n_digits in 1..=(log / 2 + add) {
         let pow = 10usize.pow(n_digits);
@@ -75,5 +94,5 @@ fn invalid_pt2(num: usize) -> bool {
         }
     }
 
-    return false;
+    false
 }
@@ -1,28 +1,47 @@
+use std::fmt::Display;
+
+use aoc_common::Solution;
+
 fn main() {
-    let lines: Vec<_> = std::io::stdin().lines().map(|v| v.unwrap()).collect();
+    aoc_common::run::<Day3>();
+}
+
+struct Day3;
+
+impl Solution for Day3 {
+    type Parsed = Vec<String>;
+
+    fn parse(input: &str) -> Self::Parsed {
+        input.lines().map(str::to_string).collect()
+    }
+
+    fn part1(lines: &Self::Parsed) -> impl Display {
+        part1(lines)
+    }
 
-    part1(&lines);
-    part2(&lines);
+    fn part2(lines: &Self::Parsed) -> impl Display {
+        part2(lines)
+    }
 }
 
-fn part1(lines: &[String]) {
+fn part1(lines: &[String]) -> u64 {
     let mut sum = 0;
 
     for line in lines {
         sum += biggest(line, 2);
     }
 
-    println!("Part 1: {sum}");
+    sum
 }
 
-fn part2(lines: &[String]) {
+fn part2(lines: &[String]) -> u64 {
     let mut sum = 0;
 
     for line in lines {
         sum += biggest(line, 12);
     }
 
-    println!("Part 2: {sum}");
+    sum
 }
 
 fn biggest(mut input: &str, k: usize) -> u64 {
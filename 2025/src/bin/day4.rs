@@ -1,27 +1,46 @@
+use std::fmt::Display;
+
+use aoc_common::Solution;
+
 fn main() {
-    let lines = std::io::stdin().lines().map(|v| v.unwrap());
+    aoc_common::run::<Day4>();
+}
+
+struct Day4;
+
+impl Solution for Day4 {
+    type Parsed = Vec<Vec<bool>>;
 
-    let mut grid = Vec::new();
+    fn parse(input: &str) -> Self::Parsed {
+        let mut grid = Vec::new();
 
-    for line in lines {
-        let mut row = Vec::with_capacity(line.len());
+        for line in input.lines() {
+            let mut row = Vec::with_capacity(line.len());
 
-        for c in line.chars() {
-            if c == '@' {
-                row.push(true);
-            } else {
-                row.push(false)
+            for c in line.chars() {
+                if c == '@' {
+                    row.push(true);
+                } else {
+                    row.push(false)
+                }
             }
+
+            grid.push(row)
         }
 
-        grid.push(row)
+        grid
     }
 
-    part1(&grid);
-    part2(grid);
+    fn part1(grid: &Self::Parsed) -> impl Display {
+        part1(grid)
+    }
+
+    fn part2(grid: &Self::Parsed) -> impl Display {
+        part2(grid.clone())
+    }
 }
 
-fn part1(grid: &[Vec<bool>]) {
+fn part1(grid: &[Vec<bool>]) -> usize {
     let width = grid[0].len();
     let mut total = 0;
 
@@ -33,10 +52,10 @@ fn part1(grid: &[Vec<bool>]) {
         }
     }
 
-    println!("Part 1: {total}");
+    total
 }
 
-fn part2(mut grid: Vec<Vec<bool>>) {
+fn part2(mut grid: Vec<Vec<bool>>) -> usize {
     let width = grid[0].len();
     let mut total = 0;
 
@@ -58,7 +77,7 @@ fn part2(mut grid: Vec<Vec<bool>>) {
         }
     }
 
-    println!("Part 2: {total}");
+    total
 }
 
 fn is_accessible_roll(grid: &[Vec<bool>], x: usize, y: usize) -> bool {
@@ -77,12 +96,7 @@ fn is_accessible_roll(grid: &[Vec<bool>], x: usize, y: usize) -> bool {
             let x = (x as isize + x_off) as usize;
             let y = (y as isize + y_off) as usize;
 
-            let occupied = grid
-                .get(y)
-                .map(|v| v.get(x))
-                .flatten()
-                .copied()
-                .unwrap_or(false);
+            let occupied = grid.get(y).and_then(|v| v.get(x)).copied().unwrap_or(false);
 
             if occupied {
                 total_occupied += 1;
@@ -1,44 +1,64 @@
-use std::ops::RangeInclusive;
+use std::{fmt::Display, ops::RangeInclusive};
+
+use aoc_common::Solution;
 
 fn main() {
-    let mut lines = std::io::stdin().lines().map(|v| v.unwrap());
+    aoc_common::run::<Day5>();
+}
 
-    let mut fresh_ranges = Vec::new();
+struct Day5;
 
-    for line in &mut lines {
-        if line.is_empty() {
-            break;
-        }
+impl Solution for Day5 {
+    /// The ranges of fresh ingredient IDs, and the available ingredients.
+    type Parsed = (Vec<RangeInclusive<usize>>, Vec<usize>);
+
+    fn parse(input: &str) -> Self::Parsed {
+        let mut lines = input.lines();
+
+        let mut fresh_ranges = Vec::new();
+
+        for line in &mut lines {
+            if line.is_empty() {
+                break;
+            }
+
+            let (start, end) = line.split_once('-').unwrap();
 
-        let (start, end) = line.split_once('-').unwrap();
+            let (start, end): (usize, usize) = (start.parse().unwrap(), end.parse().unwrap());
+
+            fresh_ranges.push(start..=end);
+        }
 
-        let (start, end): (usize, usize) = (start.parse().unwrap(), end.parse().unwrap());
+        let ingredients: Vec<usize> = lines.map(|v| v.parse().unwrap()).collect();
 
-        fresh_ranges.push(start..=end);
+        (fresh_ranges, ingredients)
     }
 
-    let ingredients: Vec<usize> = lines.map(|v| v.parse().unwrap()).collect();
+    fn part1((fresh_ranges, ingredients): &Self::Parsed) -> impl Display {
+        part1(fresh_ranges, ingredients)
+    }
 
-    part1(&fresh_ranges, &ingredients);
-    part2(&fresh_ranges);
+    fn part2((fresh_ranges, _): &Self::Parsed) -> impl Display {
+        part2(fresh_ranges)
+    }
 }
 
-fn part1(fresh_ranges: &[RangeInclusive<usize>], ingredients: &[usize]) {
+fn part1(fresh_ranges: &[RangeInclusive<usize>], ingredients: &[usize]) -> usize {
     let mut fresh = 0;
-    for ingredient in ingredients.iter().copied() {
+    for ingredient in ingredients {
         for range in fresh_ranges {
-            if range.contains(&ingredient) {
+            if range.contains(ingredient) {
                 fresh += 1;
                 break;
             }
         }
     }
 
-    println!("Part 1: {fresh}");
+    fresh
 }
 
-fn part2(fresh_ranges: &[RangeInclusive<usize>]) {
-    let mut ranges: Vec<_> = fresh_ranges.iter().cloned().collect();
+fn part2(fresh_ranges: &[RangeInclusive<usize>]) -> usize {
+    let mut ranges = fresh_ranges.to_vec();
     ranges.sort_unstable_by_key(|v| *v.start());
 
     let mut new_ranges = vec![ranges[0].clone(); 1];
@@ -59,7 +79,5 @@ fn part2(fresh_ranges: &[RangeInclusive<usize>]) {
         new_ranges.push(range);
     }
 
-    let sum: usize = new_ranges.into_iter().map(|v| v.count()).sum();
-
-    println!("Part 2: {}", sum);
+    new_ranges.into_iter().map(|v| v.count()).sum()
 }
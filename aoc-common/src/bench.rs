@@ -0,0 +1,157 @@
+//! Timing the steps of a [`Solution`] separately.
+//!
+//! A day started with `--bench` times its steps instead of printing its
+//! answers, and prints the duration of every sample in nanoseconds. With
+//! `--part <1|2>`, only parsing and that part are timed:
+//!
+//! ```text
+//! Bench parse: 10512 9870 9911
+//! Bench part1: 1201 1187 1190
+//! Bench part2: 520113 519876 523001
+//! ```
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use crate::Solution;
+
+/// The steps that are timed, in the order they are printed.
+pub const STEPS: [&str; 3] = ["parse", "part1", "part2"];
+
+/// How often each step is run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BenchConfig {
+    /// Runs before sampling starts, which are not timed.
+    pub warmup: usize,
+    pub samples: usize,
+    /// Only time parsing and this part, instead of every step.
+    pub part: Option<usize>,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        Self {
+            warmup: 1,
+            samples: 10,
+            part: None,
+        }
+    }
+}
+
+impl BenchConfig {
+    /// Read `--warmup <n>`, `--samples <n>` and `--part <1|2>` from `args`.
+    pub fn from_args(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut config = Self::default();
+
+        while let Some(arg) = args.next() {
+            let value = args
+                .next()
+                .ok_or_else(|| format!("missing value for {arg}"))?;
+            let value = value
+                .parse()
+                .map_err(|_| format!("invalid value for {arg}: {value}"))?;
+
+            match arg.as_str() {
+                "--warmup" => config.warmup = value,
+                "--samples" if value > 0 => config.samples = value,
+                "--samples" => return Err("--samples must be at least 1".into()),
+                "--part" if value == 1 || value == 2 => config.part = Some(value),
+                "--part" => return Err(format!("--part must be 1 or 2, not {value}")),
+                _ => return Err(format!("unknown argument {arg}")),
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// The steps that are timed, in [`STEPS`] order.
+    pub fn steps(&self) -> Vec<&'static str> {
+        STEPS
+            .into_iter()
+            .filter(|step| match self.part {
+                Some(part) => *step == "parse" || *step == format!("part{part}"),
+                None => true,
+            })
+            .collect()
+    }
+}
+
+/// Time the steps of `S` selected by `config` on `input`, and print the
+/// samples.
+pub fn bench<S: Solution>(input: &str, config: BenchConfig) {
+    let parsed = S::parse(input);
+
+    for step in config.steps() {
+        let samples = match step {
+            "parse" => sample(config, || S::parse(input)),
+            "part1" => sample(config, || S::part1(&parsed)),
+            _ => sample(config, || S::part2(&parsed)),
+        };
+
+        let samples: Vec<_> = samples.iter().map(|s| s.as_nanos().to_string()).collect();
+        println!("Bench {step}: {}", samples.join(" "));
+    }
+}
+
+fn sample<T>(config: BenchConfig, mut f: impl FnMut() -> T) -> Vec<Duration> {
+    for _ in 0..config.warmup {
+        black_box(f());
+    }
+
+    (0..config.samples)
+        .map(|_| {
+            let start = Instant::now();
+            let result = black_box(f());
+            let elapsed = start.elapsed();
+            // Dropping the result is not part of the step.
+            drop(result);
+            elapsed
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(args: &[&str]) -> Result<BenchConfig, String> {
+        BenchConfig::from_args(args.iter().map(|a| a.to_string()))
+    }
+
+    #[test]
+    fn from_args() {
+        assert_eq!(config(&[]), Ok(BenchConfig::default()));
+        assert_eq!(
+            config(&["--samples", "3", "--part", "2", "--warmup", "0"]),
+            Ok(BenchConfig {
+                warmup: 0,
+                samples: 3,
+                part: Some(2),
+            })
+        );
+
+        for args in [
+            &["--samples", "0"][..],
+            &["--part", "3"],
+            &["--warmup"],
+            &["--warmup", "x"],
+            &["--speed", "1"],
+        ] {
+            assert!(config(args).is_err(), "{args:?}");
+        }
+    }
+
+    #[test]
+    fn steps() {
+        let part = |part| BenchConfig {
+            part,
+            ..BenchConfig::default()
+        };
+
+        assert_eq!(part(None).steps(), STEPS);
+        assert_eq!(part(Some(1)).steps(), ["parse", "part1"]);
+        assert_eq!(part(Some(2)).steps(), ["parse", "part2"]);
+    }
+}
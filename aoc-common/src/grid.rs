@@ -0,0 +1,50 @@
+//! Positions on a rectangular grid, as `(x, y)` with `y` growing downwards.
+
+/// A position, or the size of a grid.
+pub type Pos = (usize, usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [Self::North, Self::East, Self::South, Self::West];
+
+    pub fn turn_right(self) -> Self {
+        match self {
+            Self::North => Self::East,
+            Self::East => Self::South,
+            Self::South => Self::West,
+            Self::West => Self::North,
+        }
+    }
+
+    pub fn turn_left(self) -> Self {
+        self.turn_right().turn_right().turn_right()
+    }
+
+    /// The position one step away from `pos` in this direction, if it is
+    /// inside a grid of size `dims`.
+    pub fn step(self, (x, y): Pos, (x_len, y_len): Pos) -> Option<Pos> {
+        let (x, y) = match self {
+            Self::North => (x, y.checked_sub(1)?),
+            Self::East => (x + 1, y),
+            Self::South => (x, y + 1),
+            Self::West => (x.checked_sub(1)?, y),
+        };
+
+        (x < x_len && y < y_len).then_some((x, y))
+    }
+}
+
+/// The positions directly above, below and beside `pos` that are inside a
+/// grid of size `dims`.
+pub fn neighbours(pos: Pos, dims: Pos) -> impl Iterator<Item = Pos> {
+    Direction::ALL
+        .into_iter()
+        .filter_map(move |d| d.step(pos, dims))
+}
@@ -0,0 +1,9 @@
+//! Helpers shared by the solutions of every year.
+
+pub mod bench;
+pub mod grid;
+pub mod math;
+pub mod parse;
+pub mod solution;
+
+pub use solution::{format_answer, parse_answers, run, Solution, Unsolved};
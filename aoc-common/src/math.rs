@@ -0,0 +1,35 @@
+//! Number theory for unsigned integers.
+
+use std::ops::{Div, Mul, Rem};
+
+/// The greatest common divisor of `a` and `b`.
+pub fn gcd<T>(mut a: T, mut b: T) -> T
+where
+    T: Copy + Default + PartialEq + Rem<Output = T>,
+{
+    while b != T::default() {
+        (a, b) = (b, a % b);
+    }
+
+    a
+}
+
+/// The least common multiple of `a` and `b`, or 0 if either is 0.
+pub fn lcm<T>(a: T, b: T) -> T
+where
+    T: Copy + Default + PartialEq + Rem<Output = T> + Div<Output = T> + Mul<Output = T>,
+{
+    if a == T::default() || b == T::default() {
+        T::default()
+    } else {
+        a / gcd(a, b) * b
+    }
+}
+
+/// The least common multiple of all `values`, or `None` if there are none.
+pub fn lcm_all<T>(values: impl IntoIterator<Item = T>) -> Option<T>
+where
+    T: Copy + Default + PartialEq + Rem<Output = T> + Div<Output = T> + Mul<Output = T>,
+{
+    values.into_iter().reduce(lcm)
+}
@@ -0,0 +1,17 @@
+//! Parsing puzzle input.
+
+use std::{fmt::Debug, str::FromStr};
+
+/// Parse a list of numbers separated by whitespace.
+///
+/// Panics if an item is not a valid `T`.
+pub fn parse_int_list<T>(input: &str) -> Vec<T>
+where
+    T: FromStr,
+    T::Err: Debug,
+{
+    input
+        .split_whitespace()
+        .map(|v| v.parse().unwrap())
+        .collect()
+}
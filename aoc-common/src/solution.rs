@@ -0,0 +1,198 @@
+//! The interface every day implements.
+
+use std::{fmt::Display, io::Read};
+
+use crate::bench::{self, BenchConfig};
+
+/// The solution to one day's puzzle.
+///
+/// Parsing is kept apart from the parts so each of them can be run, and
+/// checked, on its own.
+pub trait Solution {
+    /// The puzzle input, as both parts use it.
+    type Parsed;
+
+    fn parse(input: &str) -> Self::Parsed;
+
+    fn part1(parsed: &Self::Parsed) -> impl Display;
+
+    fn part2(parsed: &Self::Parsed) -> impl Display;
+}
+
+/// The answer to a part that has not been solved yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Unsolved;
+
+impl Display for Unsolved {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("unsolved")
+    }
+}
+
+/// Solve `S` for the input on stdin, and print both answers as described by
+/// [`format_answer`]. Started with `--part <1|2>`, only that part is solved.
+///
+/// Started with `--bench`, the steps of `S` are timed instead; see
+/// [`bench`](crate::bench).
+pub fn run<S: Solution>() {
+    let mut input = String::new();
+    std::io::stdin().read_to_string(&mut input).unwrap();
+
+    let mut args = std::env::args().skip(1).peekable();
+    if args.next_if(|arg| arg == "--bench").is_some() {
+        match BenchConfig::from_args(args) {
+            Ok(config) => bench::bench::<S>(&input, config),
+            Err(e) => {
+                eprintln!("{e}");
+                std::process::exit(2);
+            }
+        }
+        return;
+    }
+
+    let part = match parse_part(args) {
+        Ok(part) => part,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(2);
+        }
+    };
+
+    let parsed = S::parse(&input);
+
+    if part != Some(2) {
+        print!("{}", format_answer(1, &S::part1(&parsed).to_string()));
+    }
+    if part != Some(1) {
+        print!("{}", format_answer(2, &S::part2(&parsed).to_string()));
+    }
+}
+
+/// Read an optional `--part <1|2>` from `args`.
+fn parse_part(mut args: impl Iterator<Item = String>) -> Result<Option<usize>, String> {
+    let part = match args.next() {
+        None => None,
+        Some(arg) if arg == "--part" => match args.next().as_deref() {
+            Some("1") => Some(1),
+            Some("2") => Some(2),
+            Some(value) => return Err(format!("--part must be 1 or 2, not {value}")),
+            None => return Err("missing value for --part".into()),
+        },
+        Some(arg) => return Err(format!("unknown argument {arg}")),
+    };
+
+    match args.next() {
+        Some(arg) => Err(format!("unknown argument {arg}")),
+        None => Ok(part),
+    }
+}
+
+/// The answer to `part`, framed so [`parse_answers`] can find it again.
+///
+/// An answer on a single line follows its `Part N: ` header. An answer that
+/// spans several lines, like a picture, starts on the line after a bare
+/// `Part N:` header and runs up to the next header, or the end of the
+/// output.
+pub fn format_answer(part: usize, answer: &str) -> String {
+    let answer = answer.trim_end_matches('\n');
+
+    if answer.contains('\n') {
+        format!("Part {part}:\n{answer}\n")
+    } else {
+        format!("Part {part}: {answer}\n")
+    }
+}
+
+/// The answers to both parts in `output`, as written by [`format_answer`].
+pub fn parse_answers(output: &str) -> [Option<String>; 2] {
+    let mut answers = [None, None];
+    // The part whose multi-line answer is being read, and its lines so far.
+    let mut block: Option<(usize, Vec<&str>)> = None;
+
+    for line in output.lines() {
+        let Some((part, answer)) = parse_header(line) else {
+            if let Some((_, lines)) = &mut block {
+                lines.push(line);
+            }
+            continue;
+        };
+
+        if let Some((part, lines)) = block.take() {
+            answers[part - 1] = Some(lines.join("\n"));
+        }
+
+        match answer.strip_prefix(' ') {
+            Some(answer) => answers[part - 1] = Some(answer.trim().to_string()),
+            None => block = Some((part, Vec::new())),
+        }
+    }
+
+    if let Some((part, lines)) = block {
+        answers[part - 1] = Some(lines.join("\n"));
+    }
+
+    answers
+}
+
+/// The part and the rest of the line, if `line` is a `Part N:` header.
+fn parse_header(line: &str) -> Option<(usize, &str)> {
+    let (part, rest) = line.strip_prefix("Part ")?.split_once(':')?;
+    let part = match part {
+        "1" => 1,
+        "2" => 2,
+        _ => return None,
+    };
+
+    (rest.is_empty() || rest.starts_with(' ')).then_some((part, rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn answers_round_trip() {
+        let picture = "#..#\n####\n#..#";
+
+        for (part1, part2) in [
+            ("12", "34"),
+            (picture, "34"),
+            ("12", picture),
+            (picture, picture),
+        ] {
+            let output = format_answer(1, part1) + &format_answer(2, part2);
+            assert_eq!(
+                parse_answers(&output),
+                [Some(part1.to_string()), Some(part2.to_string())],
+                "{output}"
+            );
+        }
+    }
+
+    #[test]
+    fn missing_answers() {
+        assert_eq!(parse_answers("nothing to see\n"), [None, None]);
+        assert_eq!(
+            parse_answers(&format_answer(2, "x")),
+            [None, Some("x".to_string())]
+        );
+    }
+
+    #[test]
+    fn part_argument() {
+        let part = |args: &[&str]| parse_part(args.iter().map(|a| a.to_string()));
+
+        assert_eq!(part(&[]), Ok(None));
+        assert_eq!(part(&["--part", "1"]), Ok(Some(1)));
+        assert_eq!(part(&["--part", "2"]), Ok(Some(2)));
+
+        for args in [
+            &["--part"][..],
+            &["--part", "3"],
+            &["--part", "1", "--part", "2"],
+            &["--samples", "3"],
+        ] {
+            assert!(part(args).is_err(), "{args:?}");
+        }
+    }
+}
@@ -0,0 +1,274 @@
+//! The known-correct answers of each year, kept in `<year>/answers.txt`.
+//!
+//! Every line holds the answer to one part of one day. An answer that spans
+//! several lines, like a picture, is written as a `|` after the part,
+//! followed by its lines, each starting with `| `:
+//!
+//! ```text
+//! # Answers for the inputs in inputs/. Update with `aoc record`.
+//! day1 part1 3318604
+//! day1 part2 4975039
+//! day8 part2 |
+//! | #..#.
+//! | ####.
+//! day18 part2 22,50
+//! ```
+//!
+//! Blank lines and lines starting with `#` are ignored.
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+const HEADER: &str = "# Answers for the inputs in inputs/. Update with `aoc record`.";
+
+/// The registered answers of one year, by day and part.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Answers {
+    answers: BTreeMap<(u32, usize), String>,
+}
+
+/// How an answer compares to the registered one.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Check {
+    Pass,
+    Fail {
+        expected: String,
+        /// The answer that was given, if there was one.
+        got: Option<String>,
+    },
+    /// No answer is registered for this part.
+    Missing,
+}
+
+/// What [`Answers::record`] did.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Recorded {
+    New,
+    /// The answer replaced the given, different one.
+    Replaced(String),
+    Unchanged,
+    /// The part is not solved, so there is nothing to record.
+    Unsolved,
+}
+
+impl Answers {
+    /// The answers file of `year`.
+    pub fn path(root: &Path, year: u32) -> PathBuf {
+        root.join(year.to_string()).join("answers.txt")
+    }
+
+    /// Load the answers of `year`. A year without an answers file has no
+    /// answers.
+    pub fn load(root: &Path, year: u32) -> Result<Self, String> {
+        let path = Self::path(root, year);
+
+        match std::fs::read_to_string(&path) {
+            Ok(text) => text.parse().map_err(|e| format!("{}: {e}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("could not read {}: {e}", path.display())),
+        }
+    }
+
+    pub fn save(&self, root: &Path, year: u32) -> Result<(), String> {
+        let path = Self::path(root, year);
+        std::fs::write(&path, self.to_string())
+            .map_err(|e| format!("could not write {}: {e}", path.display()))
+    }
+
+    pub fn get(&self, day: u32, part: usize) -> Option<&str> {
+        self.answers.get(&(day, part)).map(String::as_str)
+    }
+
+    /// Register `answer`, returning the answer it replaces.
+    pub fn set(&mut self, day: u32, part: usize, answer: String) -> Option<String> {
+        self.answers.insert((day, part), answer)
+    }
+
+    /// Register `answer` as the correct one, unless it is a placeholder for
+    /// a part that has not been solved.
+    pub fn record(&mut self, day: u32, part: usize, answer: &str) -> Recorded {
+        if answer == aoc_common::Unsolved.to_string() {
+            return Recorded::Unsolved;
+        }
+
+        match self.set(day, part, answer.to_string()) {
+            Some(old) if old == answer => Recorded::Unchanged,
+            Some(old) => Recorded::Replaced(old),
+            None => Recorded::New,
+        }
+    }
+
+    pub fn check(&self, day: u32, part: usize, got: Option<&str>) -> Check {
+        match self.get(day, part) {
+            None => Check::Missing,
+            Some(expected) if got == Some(expected) => Check::Pass,
+            Some(expected) => Check::Fail {
+                expected: expected.to_string(),
+                got: got.map(str::to_string),
+            },
+        }
+    }
+}
+
+impl std::str::FromStr for Answers {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut answers = Self::default();
+        let mut lines = s.lines().enumerate().peekable();
+
+        while let Some((idx, line)) = lines.next() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let error = |message: &str| format!("line {}: {message}", idx + 1);
+
+            let mut fields = line.splitn(3, ' ');
+            let (Some(day), Some(part), Some(answer)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                return Err(error("expected `day<N> part<N> <answer>`"));
+            };
+
+            let day = day
+                .strip_prefix("day")
+                .and_then(|d| d.parse().ok())
+                .ok_or_else(|| error(&format!("invalid day {day}")))?;
+            let part = match part.strip_prefix("part") {
+                Some("1") => 1,
+                Some("2") => 2,
+                _ => return Err(error(&format!("invalid part {part}"))),
+            };
+
+            let answer = if answer.trim() == "|" {
+                let mut block = Vec::new();
+                while let Some(line) = lines.next_if(|(_, l)| l.starts_with('|')) {
+                    let line = &line.1[1..];
+                    block.push(line.strip_prefix(' ').unwrap_or(line));
+                }
+
+                if block.is_empty() {
+                    return Err(error("expected the lines of the answer after `|`"));
+                }
+                block.join("\n")
+            } else {
+                answer.trim().to_string()
+            };
+
+            if answers.set(day, part, answer).is_some() {
+                return Err(error(&format!("day {day} part {part} is listed twice")));
+            }
+        }
+
+        Ok(answers)
+    }
+}
+
+impl std::fmt::Display for Answers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{HEADER}")?;
+        for ((day, part), answer) in &self.answers {
+            if answer.contains('\n') {
+                writeln!(f, "day{day} part{part} |")?;
+                for line in answer.lines() {
+                    writeln!(f, "| {line}")?;
+                }
+            } else {
+                writeln!(f, "day{day} part{part} {answer}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PICTURE: &str = "#..#.\n####.\n #  #";
+
+    fn root() -> PathBuf {
+        let root = std::env::temp_dir().join(format!("aoc-answers-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("2019")).unwrap();
+        root
+    }
+
+    #[test]
+    fn parse_and_serialize() {
+        let text = "\
+# A comment
+
+day1 part1 3318604
+day8 part2 |
+| #..#.
+| ####.
+|  #  #
+day18 part2 22,50
+";
+        let answers: Answers = text.parse().unwrap();
+
+        assert_eq!(answers.get(1, 1), Some("3318604"));
+        assert_eq!(answers.get(8, 2), Some(PICTURE));
+        assert_eq!(answers.get(18, 2), Some("22,50"));
+        assert_eq!(answers.get(1, 2), None);
+
+        assert_eq!(answers.to_string().parse::<Answers>(), Ok(answers));
+    }
+
+    #[test]
+    fn malformed() {
+        for text in [
+            "day1 part1",
+            "dayone part1 12",
+            "day1 part3 12",
+            "day1 part1 12\nday1 part1 13",
+            "day8 part2 |\nday9 part1 1",
+        ] {
+            assert!(text.parse::<Answers>().is_err(), "{text}");
+        }
+    }
+
+    #[test]
+    fn record_and_verify() {
+        let root = root();
+        let mut answers = Answers::default();
+
+        assert_eq!(answers.record(8, 1, "1224"), Recorded::New);
+        assert_eq!(answers.record(8, 2, PICTURE), Recorded::New);
+        assert_eq!(answers.record(8, 2, PICTURE), Recorded::Unchanged);
+        assert_eq!(answers.record(9, 2, "unsolved"), Recorded::Unsolved);
+        assert_eq!(
+            answers.record(8, 1, "1225"),
+            Recorded::Replaced("1224".into())
+        );
+        answers.save(&root, 2019).unwrap();
+
+        let loaded = Answers::load(&root, 2019).unwrap();
+        std::fs::remove_dir_all(&root).unwrap();
+        assert_eq!(loaded, answers);
+
+        assert_eq!(loaded.check(8, 1, Some("1225")), Check::Pass);
+        assert_eq!(loaded.check(8, 2, Some(PICTURE)), Check::Pass);
+        assert_eq!(loaded.check(9, 2, Some("unsolved")), Check::Missing);
+
+        let wrong = PICTURE.replace('#', ".");
+        assert_eq!(
+            loaded.check(8, 2, Some(&wrong)),
+            Check::Fail {
+                expected: PICTURE.into(),
+                got: Some(wrong.clone())
+            }
+        );
+        assert_eq!(
+            loaded.check(8, 1, None),
+            Check::Fail {
+                expected: "1225".into(),
+                got: None
+            }
+        );
+    }
+}
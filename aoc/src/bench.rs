@@ -0,0 +1,294 @@
+//! Statistics over benchmark samples, and the history of earlier benchmarks
+//! kept in `bench_history.json`.
+//!
+//! The history holds one entry per `aoc bench` run, oldest first:
+//!
+//! ```text
+//! {
+//!   "runs": [
+//!     {
+//!       "results": [
+//!         {
+//!           "day": 11,
+//!           "median_ns": 571002,
+//!           "samples": 10,
+//!           "spread_ns": 2011,
+//!           "step": "part1",
+//!           "year": 2024
+//!         }
+//!       ],
+//!       "timestamp": 1734307200
+//!     }
+//!   ]
+//! }
+//! ```
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use crate::{json::Value, run::Puzzle};
+
+/// How much slower than its previous median a step has to be to count as a
+/// regression, as a fraction.
+const REGRESSION_THRESHOLD: f64 = 0.10;
+
+/// The median of a set of samples, and how far they spread around it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub median: Duration,
+    /// The median absolute deviation from the median.
+    pub spread: Duration,
+    pub samples: usize,
+}
+
+impl Stats {
+    pub fn new(samples: &[Duration]) -> Self {
+        let median = |values: &mut Vec<Duration>| {
+            values.sort();
+            let mid = values.len() / 2;
+            if values.len().is_multiple_of(2) {
+                (values[mid - 1] + values[mid]) / 2
+            } else {
+                values[mid]
+            }
+        };
+
+        let median_value = median(&mut samples.to_vec());
+        let mut deviations: Vec<_> = samples.iter().map(|s| s.abs_diff(median_value)).collect();
+
+        Self {
+            median: median_value,
+            spread: median(&mut deviations),
+            samples: samples.len(),
+        }
+    }
+
+    /// Whether `self` is slower than `previous` by more than the threshold,
+    /// and by more than the noise of both.
+    pub fn regressed_from(&self, previous: &Stats) -> bool {
+        let limit = previous.median.as_secs_f64() * (1.0 + REGRESSION_THRESHOLD);
+        let noise = self.spread + previous.spread;
+
+        self.median.as_secs_f64() > limit && self.median > previous.median + noise
+    }
+
+    /// The change from `previous`, as a percentage of it.
+    pub fn change_from(&self, previous: &Stats) -> f64 {
+        let previous = previous.median.as_secs_f64();
+        (self.median.as_secs_f64() - previous) / previous * 100.0
+    }
+}
+
+/// The result of one step of one puzzle.
+#[derive(Debug, Clone)]
+pub struct Measurement {
+    pub puzzle: Puzzle,
+    pub step: String,
+    pub stats: Stats,
+}
+
+impl Measurement {
+    fn to_json(&self) -> Value {
+        let number = |n: usize| Value::Number(n as f64);
+
+        Value::Object(BTreeMap::from([
+            ("year".into(), number(self.puzzle.year as usize)),
+            ("day".into(), number(self.puzzle.day as usize)),
+            ("step".into(), Value::String(self.step.clone())),
+            (
+                "median_ns".into(),
+                number(self.stats.median.as_nanos() as usize),
+            ),
+            (
+                "spread_ns".into(),
+                number(self.stats.spread.as_nanos() as usize),
+            ),
+            ("samples".into(), number(self.stats.samples)),
+        ]))
+    }
+
+    fn from_json(value: &Value) -> Option<Self> {
+        let number = |key| value.get(key)?.as_f64();
+        let nanos = |key| Some(Duration::from_nanos(number(key)? as u64));
+
+        Some(Self {
+            puzzle: Puzzle {
+                year: number("year")? as u32,
+                day: number("day")? as u32,
+            },
+            step: value.get("step")?.as_str()?.to_string(),
+            stats: Stats {
+                median: nanos("median_ns")?,
+                spread: nanos("spread_ns")?,
+                samples: number("samples")? as usize,
+            },
+        })
+    }
+}
+
+/// Every earlier `aoc bench` run.
+#[derive(Debug, Default)]
+pub struct History {
+    runs: Vec<Value>,
+}
+
+impl History {
+    pub fn path(root: &Path) -> PathBuf {
+        root.join("bench_history.json")
+    }
+
+    /// Load the history in `root`. Without a history file, there are no
+    /// earlier runs.
+    pub fn load(root: &Path) -> Result<Self, String> {
+        let path = Self::path(root);
+
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(format!("could not read {}: {e}", path.display())),
+        };
+
+        let runs = Value::parse(&text)
+            .ok()
+            .and_then(|history| Some(history.get("runs")?.as_array()?.to_vec()))
+            .ok_or_else(|| format!("{} is not a benchmark history", path.display()))?;
+
+        Ok(Self { runs })
+    }
+
+    pub fn save(&self, root: &Path) -> Result<(), String> {
+        let path = Self::path(root);
+        let history = Value::Object(BTreeMap::from([(
+            "runs".to_string(),
+            Value::Array(self.runs.clone()),
+        )]));
+
+        std::fs::write(&path, history.pretty())
+            .map_err(|e| format!("could not write {}: {e}", path.display()))
+    }
+
+    /// The most recent result for `step` of `puzzle`.
+    pub fn previous(&self, puzzle: Puzzle, step: &str) -> Option<Stats> {
+        self.runs
+            .iter()
+            .rev()
+            .flat_map(|run| run.get("results").and_then(Value::as_array).unwrap_or(&[]))
+            .filter_map(Measurement::from_json)
+            .find(|m| m.puzzle == puzzle && m.step == step)
+            .map(|m| m.stats)
+    }
+
+    /// Add a run with `measurements`, made now.
+    pub fn push(&mut self, measurements: &[Measurement]) {
+        let timestamp = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        self.runs.push(Value::Object(BTreeMap::from([
+            ("timestamp".into(), Value::Number(timestamp as f64)),
+            (
+                "results".into(),
+                Value::Array(measurements.iter().map(Measurement::to_json).collect()),
+            ),
+        ])));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    fn stats(median: u64, spread: u64) -> Stats {
+        Stats {
+            median: ms(median),
+            spread: ms(spread),
+            samples: 10,
+        }
+    }
+
+    #[test]
+    fn median_and_spread() {
+        let odd = Stats::new(&[ms(5), ms(1), ms(100), ms(3), ms(4)]);
+        assert_eq!(odd.median, ms(4));
+        // The deviations are 1, 3, 96, 1 and 0.
+        assert_eq!(odd.spread, ms(1));
+        assert_eq!(odd.samples, 5);
+
+        let even = Stats::new(&[ms(4), ms(1), ms(2), ms(10)]);
+        assert_eq!(even.median, Duration::from_micros(3000));
+        // The deviations are 1, 2, 1 and 7.
+        assert_eq!(even.spread, Duration::from_micros(1500));
+
+        let single = Stats::new(&[ms(7)]);
+        assert_eq!((single.median, single.spread), (ms(7), ms(0)));
+    }
+
+    #[test]
+    fn regressions() {
+        let previous = stats(100, 0);
+
+        // Up to 10% slower is not a regression.
+        assert!(!stats(110, 0).regressed_from(&previous));
+        assert!(stats(111, 0).regressed_from(&previous));
+        assert!(!stats(90, 0).regressed_from(&previous));
+
+        // Neither is a change within the noise of both measurements.
+        assert!(stats(120, 5).regressed_from(&stats(100, 10)));
+        assert!(!stats(120, 10).regressed_from(&stats(100, 10)));
+
+        assert!((stats(120, 0).change_from(&previous) - 20.0).abs() < 1e-9);
+        assert!((stats(75, 0).change_from(&previous) + 25.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn history() {
+        let root = std::env::temp_dir().join(format!("aoc-bench-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+
+        let puzzle = Puzzle {
+            year: 2024,
+            day: 11,
+        };
+        let measurement = |step: &str, stats| Measurement {
+            puzzle,
+            step: step.into(),
+            stats,
+        };
+
+        let mut history = History::load(&root).unwrap();
+        assert_eq!(history.previous(puzzle, "part1"), None);
+
+        history.push(&[measurement("part1", stats(100, 2))]);
+        history.push(&[
+            measurement("part1", stats(90, 1)),
+            measurement("part2", stats(5, 0)),
+        ]);
+        history.save(&root).unwrap();
+
+        let loaded = History::load(&root);
+        std::fs::remove_dir_all(&root).unwrap();
+        let loaded = loaded.unwrap();
+
+        assert_eq!(loaded.previous(puzzle, "part1"), Some(stats(90, 1)));
+        assert_eq!(loaded.previous(puzzle, "part2"), Some(stats(5, 0)));
+        assert_eq!(loaded.previous(puzzle, "parse"), None);
+        assert_eq!(
+            loaded.previous(
+                Puzzle {
+                    year: 2024,
+                    day: 12
+                },
+                "part1"
+            ),
+            None
+        );
+    }
+}
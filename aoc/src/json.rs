@@ -0,0 +1,373 @@
+//! Just enough JSON to read and write the benchmark history, and to read
+//! the messages of `cargo build --message-format=json`.
+
+use std::{collections::BTreeMap, fmt::Write};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(BTreeMap<String, Value>),
+}
+
+impl Value {
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Self::Object(fields) => fields.get(key),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Self::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut parser = Parser { text, pos: 0 };
+        let value = parser.value()?;
+
+        parser.skip_whitespace();
+        if parser.pos != text.len() {
+            return Err(parser.error("trailing characters"));
+        }
+
+        Ok(value)
+    }
+
+    /// Write `self` with one array element or object field per line.
+    pub fn pretty(&self) -> String {
+        let mut out = String::new();
+        self.write_pretty(&mut out, 0);
+        out.push('\n');
+        out
+    }
+
+    fn write_pretty(&self, out: &mut String, indent: usize) {
+        let pad = |out: &mut String, indent: usize| out.extend((0..indent).map(|_| "  "));
+
+        match self {
+            Self::Array(values) if !values.is_empty() => {
+                out.push_str("[\n");
+                for (idx, value) in values.iter().enumerate() {
+                    pad(out, indent + 1);
+                    value.write_pretty(out, indent + 1);
+                    out.push_str(if idx + 1 < values.len() { ",\n" } else { "\n" });
+                }
+                pad(out, indent);
+                out.push(']');
+            }
+            Self::Object(fields) if !fields.is_empty() => {
+                out.push_str("{\n");
+                for (idx, (key, value)) in fields.iter().enumerate() {
+                    pad(out, indent + 1);
+                    write_string(out, key);
+                    out.push_str(": ");
+                    value.write_pretty(out, indent + 1);
+                    out.push_str(if idx + 1 < fields.len() { ",\n" } else { "\n" });
+                }
+                pad(out, indent);
+                out.push('}');
+            }
+            _ => out.push_str(&self.to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Null => f.write_str("null"),
+            Self::Bool(b) => write!(f, "{b}"),
+            Self::Number(n) => write!(f, "{n}"),
+            Self::String(s) => {
+                let mut out = String::new();
+                write_string(&mut out, s);
+                f.write_str(&out)
+            }
+            Self::Array(values) => {
+                let values: Vec<_> = values.iter().map(|v| v.to_string()).collect();
+                write!(f, "[{}]", values.join(","))
+            }
+            Self::Object(fields) => {
+                let fields: Vec<_> = fields
+                    .iter()
+                    .map(|(k, v)| format!("{}:{v}", Value::String(k.clone())))
+                    .collect();
+                write!(f, "{{{}}}", fields.join(","))
+            }
+        }
+    }
+}
+
+fn write_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> String {
+        format!("{message} at byte {}", self.pos)
+    }
+
+    fn rest(&self) -> &str {
+        &self.text[self.pos..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        let found = self.rest().starts_with(token);
+        if found {
+            self.pos += token.len();
+        }
+        found
+    }
+
+    fn value(&mut self) -> Result<Value, String> {
+        self.skip_whitespace();
+
+        if self.eat("null") {
+            Ok(Value::Null)
+        } else if self.eat("true") {
+            Ok(Value::Bool(true))
+        } else if self.eat("false") {
+            Ok(Value::Bool(false))
+        } else if self.eat("[") {
+            let mut values = Vec::new();
+            if !self.eat("]") {
+                loop {
+                    values.push(self.value()?);
+                    if self.eat("]") {
+                        break;
+                    } else if !self.eat(",") {
+                        return Err(self.error("expected , or ]"));
+                    }
+                }
+            }
+            Ok(Value::Array(values))
+        } else if self.eat("{") {
+            let mut fields = BTreeMap::new();
+            if !self.eat("}") {
+                loop {
+                    self.skip_whitespace();
+                    let key = self.string()?;
+                    if !self.eat(":") {
+                        return Err(self.error("expected :"));
+                    }
+                    fields.insert(key, self.value()?);
+                    if self.eat("}") {
+                        break;
+                    } else if !self.eat(",") {
+                        return Err(self.error("expected , or }"));
+                    }
+                }
+            }
+            Ok(Value::Object(fields))
+        } else if self.rest().starts_with('"') {
+            self.string().map(Value::String)
+        } else {
+            self.number()
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        if !self.eat("\"") {
+            return Err(self.error("expected a string"));
+        }
+
+        let mut out = String::new();
+        let mut chars = self.rest().char_indices();
+        while let Some((idx, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += idx + 1;
+                    return Ok(out);
+                }
+                '\\' => {
+                    let escaped = match chars.next().map(|(_, c)| c) {
+                        Some('n') => '\n',
+                        Some('t') => '\t',
+                        Some('r') => '\r',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('u') => {
+                            let hex: String = (&mut chars).take(4).map(|(_, c)| c).collect();
+                            u32::from_str_radix(&hex, 16)
+                                .ok()
+                                .and_then(char::from_u32)
+                                .ok_or_else(|| self.error("invalid \\u escape"))?
+                        }
+                        Some(c @ ('"' | '\\' | '/')) => c,
+                        _ => return Err(self.error("invalid escape")),
+                    };
+                    out.push(escaped);
+                }
+                c => out.push(c),
+            }
+        }
+
+        Err(self.error("unterminated string"))
+    }
+
+    fn number(&mut self) -> Result<Value, String> {
+        let len = self
+            .rest()
+            .find(|c: char| !(c.is_ascii_digit() || "+-.eE".contains(c)))
+            .unwrap_or(self.rest().len());
+
+        let number = self.rest()[..len]
+            .parse()
+            .map_err(|_| self.error("expected a value"))?;
+        self.pos += len;
+
+        Ok(Value::Number(number))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn object<const N: usize>(fields: [(&str, Value); N]) -> Value {
+        Value::Object(fields.map(|(k, v)| (k.to_string(), v)).into())
+    }
+
+    #[test]
+    fn pretty_round_trip() {
+        let value = object([
+            (
+                "runs",
+                Value::Array(vec![object([
+                    ("step", Value::String("part1".into())),
+                    ("median_ns", Value::Number(571002.0)),
+                    ("tags", Value::Array(Vec::new())),
+                    ("extra", object([])),
+                    ("ok", Value::Bool(true)),
+                    ("note", Value::Null),
+                ])]),
+            ),
+            ("version", Value::Number(1.0)),
+        ]);
+
+        let expected = r#"{
+  "runs": [
+    {
+      "extra": {},
+      "median_ns": 571002,
+      "note": null,
+      "ok": true,
+      "step": "part1",
+      "tags": []
+    }
+  ],
+  "version": 1
+}
+"#;
+
+        assert_eq!(value.pretty(), expected);
+        assert_eq!(Value::parse(expected), Ok(value.clone()));
+        assert_eq!(Value::parse(&value.to_string()), Ok(value));
+    }
+
+    #[test]
+    fn strings() {
+        let text = "quote \" backslash \\ slash / newline \n tab \t bell \u{7} é ✓";
+        let value = Value::String(text.into());
+
+        assert_eq!(
+            value.to_string(),
+            r#""quote \" backslash \\ slash / newline \n tab \t bell \u0007 é ✓""#
+        );
+        assert_eq!(Value::parse(&value.to_string()), Ok(value));
+
+        assert_eq!(
+            Value::parse(r#""é✓\/\r\b\f""#),
+            Ok(Value::String("é✓/\r\u{8}\u{c}".into()))
+        );
+
+        for text in [r#""\x""#, r#""\u12""#, r#""\ud800""#, r#""open"#, r#""\"#] {
+            assert!(Value::parse(text).is_err(), "{text}");
+        }
+    }
+
+    #[test]
+    fn numbers() {
+        for (text, number) in [
+            ("0", 0.0),
+            ("-7", -7.0),
+            ("12.5", 12.5),
+            ("-1.5e3", -1500.0),
+            ("2E-2", 0.02),
+            ("1734307200", 1734307200.0),
+        ] {
+            assert_eq!(Value::parse(text), Ok(Value::Number(number)), "{text}");
+        }
+
+        assert_eq!(Value::Number(1734307200.0).to_string(), "1734307200");
+        assert_eq!(Value::Number(-0.25).to_string(), "-0.25");
+
+        for text in ["-", "1.2.3", "e5", "1x"] {
+            assert!(Value::parse(text).is_err(), "{text}");
+        }
+    }
+
+    #[test]
+    fn malformed() {
+        for text in [
+            "",
+            "[1,]",
+            "[1 2]",
+            "{\"a\" 1}",
+            "{1: 2}",
+            "{\"a\": 1,}",
+            "nul",
+            "[] []",
+        ] {
+            assert!(Value::parse(text).is_err(), "{text}");
+        }
+
+        assert_eq!(
+            Value::parse("[1] x"),
+            Err("trailing characters at byte 4".into())
+        );
+    }
+}
@@ -0,0 +1,576 @@
+//! Run Advent of Code solutions from every year in this repository.
+
+use std::{
+    collections::{btree_map::Entry, BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+};
+
+use aoc_common::bench::BenchConfig;
+
+use answers::{Answers, Check, Recorded};
+use bench::{History, Measurement, Stats};
+use run::{Outcome, Puzzle};
+
+mod answers;
+mod bench;
+mod json;
+mod run;
+
+const USAGE: &str = "\
+Usage: aoc [verify | record] [--year <year>] [--day <day>] [--part <1|2>] [--input <path>]
+       aoc bench [--year <year>] [--day <day>] [--part <1|2>] [--warmup <n>] [--samples <n>]
+
+Runs a single day, every day of a year, or every day of every year. The input
+of a day defaults to <year>/inputs/day<N>.txt, and --part solves only that
+part. Running more than one day prints a summary table.
+
+verify compares the answers to the ones in <year>/answers.txt, and record
+writes them there once they are known to be correct. Both always use the
+default inputs.
+
+bench times parsing and each part separately, or only parsing and the part
+given with --part, and reports the median and spread of the samples. Results
+are added to bench_history.json, and steps that got slower since they were
+last benchmarked are flagged.";
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+enum Mode {
+    #[default]
+    Run,
+    Verify,
+    Record,
+    Bench,
+}
+
+#[derive(Debug, Default)]
+struct Args {
+    mode: Mode,
+    year: Option<u32>,
+    day: Option<u32>,
+    part: Option<usize>,
+    input: Option<PathBuf>,
+    bench: BenchConfig,
+}
+
+fn main() {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{e}\n\n{USAGE}");
+            std::process::exit(2);
+        }
+    };
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+
+    let puzzles = match select(root, &args) {
+        Ok(puzzles) => puzzles,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(2);
+        }
+    };
+
+    if args.mode == Mode::Bench {
+        if !bench(root, &puzzles, &args) {
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    // Read the answers before running anything, so a broken answers file does
+    // not waste a run.
+    let registered = match args.mode {
+        Mode::Run | Mode::Bench => BTreeMap::new(),
+        Mode::Verify | Mode::Record => match load_answers(root, &puzzles) {
+            Ok(registered) => registered,
+            Err(e) => {
+                eprintln!("{e}");
+                std::process::exit(2);
+            }
+        },
+    };
+
+    let mut results = Vec::new();
+    for puzzle in puzzles {
+        let input = args
+            .input
+            .clone()
+            .unwrap_or_else(|| puzzle.default_input(root));
+
+        let outcome = if input.exists() {
+            eprintln!("Running {puzzle}");
+            match run::build(root, puzzle) {
+                Ok(executable) => run::run(&executable, &input, args.part),
+                Err(e) => Outcome::BuildFailed(e),
+            }
+        } else {
+            Outcome::MissingInput(input)
+        };
+
+        results.push((puzzle, outcome));
+    }
+
+    let mut failed = results
+        .iter()
+        .any(|(_, o)| matches!(o, Outcome::BuildFailed(_) | Outcome::Failed(_)));
+
+    match args.mode {
+        Mode::Run => {
+            if let [(puzzle, outcome)] = &results[..] {
+                print_single(*puzzle, outcome, args.part);
+            } else {
+                print_table(&results, args.part);
+            }
+        }
+        Mode::Verify => failed |= !verify(&results, &registered, args.part),
+        Mode::Record => {
+            if let Err(e) = record(root, &results, registered, args.part) {
+                eprintln!("{e}");
+                std::process::exit(2);
+            }
+        }
+        Mode::Bench => unreachable!(),
+    }
+
+    if failed {
+        std::process::exit(1);
+    }
+}
+
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut parsed = Args::default();
+    let mut args = args.peekable();
+
+    parsed.mode = match args.peek().map(String::as_str) {
+        Some("verify") => Mode::Verify,
+        Some("record") => Mode::Record,
+        Some("bench") => Mode::Bench,
+        _ => Mode::Run,
+    };
+    if parsed.mode != Mode::Run {
+        args.next();
+    }
+
+    let mut bench_args = Vec::new();
+
+    while let Some(arg) = args.next() {
+        if arg == "--help" || arg == "-h" {
+            println!("{USAGE}");
+            std::process::exit(0);
+        }
+
+        let value = args
+            .next()
+            .ok_or_else(|| format!("missing value for {arg}"))?;
+        let number = || {
+            value
+                .parse::<u32>()
+                .map_err(|_| format!("invalid value for {arg}: {value}"))
+        };
+
+        match arg.as_str() {
+            "--year" => parsed.year = Some(number()?),
+            "--day" => parsed.day = Some(number()?),
+            "--part" => match number()? {
+                part @ (1 | 2) => parsed.part = Some(part as usize),
+                _ => return Err(format!("--part must be 1 or 2, not {value}")),
+            },
+            "--input" => parsed.input = Some(PathBuf::from(&value)),
+            "--warmup" | "--samples" if parsed.mode == Mode::Bench => {
+                bench_args.extend([arg, value]);
+            }
+            "--warmup" | "--samples" => return Err(format!("{arg} only applies to bench")),
+            _ => return Err(format!("unknown argument {arg}")),
+        }
+    }
+
+    if parsed.day.is_some() && parsed.year.is_none() {
+        return Err("--day needs a --year".into());
+    }
+
+    if parsed.input.is_some() && parsed.day.is_none() {
+        return Err("--input needs a --year and --day".into());
+    }
+
+    if parsed.input.is_some() && parsed.mode != Mode::Run {
+        return Err("--input cannot be used with verify, record or bench".into());
+    }
+
+    parsed.bench = BenchConfig::from_args(bench_args.into_iter())?;
+    parsed.bench.part = parsed.part;
+
+    Ok(parsed)
+}
+
+/// The puzzles selected by `args`.
+fn select(root: &Path, args: &Args) -> Result<Vec<Puzzle>, String> {
+    let years = match args.year {
+        Some(year) if !run::years(root).contains(&year) => {
+            return Err(format!("there is no crate for {year}"))
+        }
+        Some(year) => vec![year],
+        None => run::years(root),
+    };
+
+    let mut puzzles = Vec::new();
+    for year in years {
+        let days = run::days(root, year);
+
+        match args.day {
+            Some(day) if !days.contains(&day) => {
+                return Err(format!("{year} has no solution for day {day}"))
+            }
+            Some(day) => puzzles.push(Puzzle { year, day }),
+            None => puzzles.extend(days.into_iter().map(|day| Puzzle { year, day })),
+        }
+    }
+
+    Ok(puzzles)
+}
+
+/// The parts to show: both, or only the one given with `--part`.
+fn parts(part: Option<usize>) -> Vec<usize> {
+    match part {
+        Some(part) => vec![part],
+        None => vec![1, 2],
+    }
+}
+
+fn print_single(puzzle: Puzzle, outcome: &Outcome, part: Option<usize>) {
+    match outcome {
+        Outcome::Solved {
+            answers,
+            output,
+            elapsed,
+        } => {
+            if answers.iter().all(Option::is_none) {
+                // Nothing to pick answers from, so show everything.
+                print!("{output}");
+            } else {
+                for part in parts(part) {
+                    let answer = answers[part - 1].as_deref().unwrap_or("-");
+                    print!("{}", aoc_common::format_answer(part, answer));
+                }
+            }
+            println!("{puzzle} took {:.2?}", elapsed);
+        }
+        Outcome::MissingInput(path) => println!("{puzzle}: no input at {}", path.display()),
+        Outcome::BuildFailed(e) => println!("{puzzle}: {e}"),
+        Outcome::Failed(e) => println!("{puzzle} failed: {e}"),
+    }
+}
+
+fn print_table(results: &[(Puzzle, Outcome)], part: Option<usize>) {
+    let parts = parts(part);
+
+    let mut rows = vec![{
+        let mut header = vec!["year".to_string(), "day".to_string()];
+        header.extend(parts.iter().map(|p| format!("part {p}")));
+        header.push("time".to_string());
+        header
+    }];
+
+    for (puzzle, outcome) in results {
+        let mut row = vec![puzzle.year.to_string(), puzzle.day.to_string()];
+
+        match outcome {
+            Outcome::Solved {
+                answers, elapsed, ..
+            } => {
+                row.extend(parts.iter().map(|p| match &answers[p - 1] {
+                    // Pictures do not fit in a table.
+                    Some(answer) if answer.contains('\n') => {
+                        format!("({} lines)", answer.lines().count())
+                    }
+                    Some(answer) => answer.clone(),
+                    None => "?".into(),
+                }));
+                row.push(format!("{:.2?}", elapsed));
+            }
+            Outcome::MissingInput(_) => row.push("no input".into()),
+            Outcome::BuildFailed(_) => row.push("build failed".into()),
+            Outcome::Failed(_) => row.push("failed".into()),
+        }
+
+        rows.push(row);
+    }
+
+    print_rows(&rows);
+
+    let solved = results
+        .iter()
+        .filter(|(_, o)| matches!(o, Outcome::Solved { .. }))
+        .count();
+    println!("\n{solved} of {} days solved", results.len());
+
+    for (puzzle, outcome) in results {
+        if let Outcome::BuildFailed(e) | Outcome::Failed(e) = outcome {
+            println!("{puzzle}: {e}");
+        }
+    }
+}
+
+/// Print `rows` as a table, with the first row as its header.
+fn print_rows(rows: &[Vec<String>]) {
+    let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let widths: Vec<usize> = (0..columns)
+        .map(|c| {
+            rows.iter()
+                .filter_map(|r| r.get(c))
+                .map(|v| v.len())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    println!();
+    for row in rows {
+        let line: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(value, width)| format!("{value:<width$}"))
+            .collect();
+        println!("{}", line.join("  ").trim_end());
+    }
+}
+
+/// The registered answers of every year in `puzzles`.
+fn load_answers(root: &Path, puzzles: &[Puzzle]) -> Result<BTreeMap<u32, Answers>, String> {
+    let mut answers = BTreeMap::new();
+    for puzzle in puzzles {
+        if let Entry::Vacant(entry) = answers.entry(puzzle.year) {
+            entry.insert(Answers::load(root, puzzle.year)?);
+        }
+    }
+    Ok(answers)
+}
+
+/// Compare the answers in `results` to the registered ones, and print a table
+/// of the outcome. Returns whether nothing failed.
+fn verify(
+    results: &[(Puzzle, Outcome)],
+    registered: &BTreeMap<u32, Answers>,
+    part: Option<usize>,
+) -> bool {
+    let parts = parts(part);
+
+    let mut header = vec!["year".to_string(), "day".to_string()];
+    header.extend(parts.iter().map(|p| format!("part {p}")));
+    header.push("time".to_string());
+    let mut rows = vec![header];
+
+    let mut failures = Vec::new();
+    let (mut passed, mut missing) = (0, 0);
+
+    for (puzzle, outcome) in results {
+        let mut row = vec![puzzle.year.to_string(), puzzle.day.to_string()];
+
+        let (given, elapsed) = match outcome {
+            Outcome::Solved {
+                answers, elapsed, ..
+            } => (answers.clone(), format!("{elapsed:.2?}")),
+            Outcome::MissingInput(_) => ([None, None], "no input".into()),
+            Outcome::BuildFailed(_) => ([None, None], "build failed".into()),
+            Outcome::Failed(_) => ([None, None], "failed".into()),
+        };
+
+        for part in &parts {
+            let check = if matches!(outcome, Outcome::MissingInput(_)) {
+                // Without an input there is nothing to compare.
+                Check::Missing
+            } else {
+                registered[&puzzle.year].check(puzzle.day, *part, given[part - 1].as_deref())
+            };
+
+            let status = match check {
+                Check::Pass => {
+                    passed += 1;
+                    "ok"
+                }
+                Check::Missing => {
+                    missing += 1;
+                    "missing"
+                }
+                Check::Fail { expected, got } => {
+                    let got = got.as_deref().map_or(" nothing".into(), show);
+                    failures.push(format!(
+                        "{puzzle} part {part}\n  expected:{}\n  got:{got}",
+                        show(&expected)
+                    ));
+                    "FAIL"
+                }
+            };
+            row.push(status.to_string());
+        }
+
+        row.push(elapsed);
+        rows.push(row);
+    }
+
+    print_rows(&rows);
+    println!(
+        "\n{passed} passed, {} failed, {missing} missing",
+        failures.len()
+    );
+
+    for failure in &failures {
+        println!("{failure}");
+    }
+
+    failures.is_empty()
+}
+
+/// Register the answers in `results` as the correct ones, and save the
+/// answers files that changed.
+fn record(
+    root: &Path,
+    results: &[(Puzzle, Outcome)],
+    mut registered: BTreeMap<u32, Answers>,
+    part: Option<usize>,
+) -> Result<(), String> {
+    let mut changed = BTreeSet::new();
+
+    for (puzzle, outcome) in results {
+        let Outcome::Solved { answers, .. } = outcome else {
+            println!("{puzzle}: nothing to record");
+            continue;
+        };
+
+        let registered = registered.get_mut(&puzzle.year).unwrap();
+
+        for part in parts(part) {
+            let Some(answer) = &answers[part - 1] else {
+                continue;
+            };
+
+            match registered.record(puzzle.day, part, answer) {
+                Recorded::Unchanged | Recorded::Unsolved => continue,
+                Recorded::Replaced(old) => println!(
+                    "{puzzle} part {part}:{}\n  was:{}",
+                    show(answer),
+                    show(&old)
+                ),
+                Recorded::New => println!("{puzzle} part {part}:{}", show(answer)),
+            }
+
+            changed.insert(puzzle.year);
+        }
+    }
+
+    for year in changed {
+        registered[&year].save(root, year)?;
+    }
+
+    Ok(())
+}
+
+/// Benchmark every step of `puzzles`, print how they compare to their
+/// previous results, and add them to the history. Returns whether every
+/// puzzle could be benchmarked.
+fn bench(root: &Path, puzzles: &[Puzzle], args: &Args) -> bool {
+    let mut history = match History::load(root) {
+        Ok(history) => history,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(2);
+        }
+    };
+
+    let mut rows = vec![[
+        "year", "day", "step", "median", "spread", "previous", "change",
+    ]
+    .map(String::from)
+    .to_vec()];
+    let mut measurements = Vec::new();
+    let mut problems = Vec::new();
+    let mut missing_inputs = 0;
+    let mut regressions = 0;
+    let mut failed = false;
+
+    for puzzle in puzzles {
+        let input = puzzle.default_input(root);
+        if !input.exists() {
+            missing_inputs += 1;
+            continue;
+        }
+
+        eprintln!("Benchmarking {puzzle}");
+        let samples = run::build(root, *puzzle)
+            .map_err(Outcome::BuildFailed)
+            .and_then(|executable| run::bench(&executable, &input, args.bench));
+
+        let samples = match samples {
+            Ok(samples) => samples,
+            Err(Outcome::BuildFailed(e) | Outcome::Failed(e)) => {
+                problems.push(format!("{puzzle}: {e}"));
+                failed = true;
+                continue;
+            }
+            Err(_) => continue,
+        };
+
+        for (step, samples) in args.bench.steps().into_iter().zip(samples) {
+            let stats = Stats::new(&samples);
+            let mut row = vec![
+                puzzle.year.to_string(),
+                puzzle.day.to_string(),
+                step.to_string(),
+                format!("{:.2?}", stats.median),
+                format!("±{:.2?}", stats.spread),
+            ];
+
+            if let Some(previous) = history.previous(*puzzle, step) {
+                row.push(format!("{:.2?}", previous.median));
+
+                let mut change = format!("{:+.1}%", stats.change_from(&previous));
+                if stats.regressed_from(&previous) {
+                    change.push_str(" REGRESSION");
+                    regressions += 1;
+                }
+                row.push(change);
+            }
+
+            rows.push(row);
+            measurements.push(Measurement {
+                puzzle: *puzzle,
+                step: step.to_string(),
+                stats,
+            });
+        }
+    }
+
+    print_rows(&rows);
+    println!(
+        "\n{} samples after {} warmup runs per step, {regressions} regressions",
+        args.bench.samples, args.bench.warmup
+    );
+
+    if missing_inputs > 0 {
+        println!("{missing_inputs} days were skipped for lack of an input");
+    }
+
+    for problem in &problems {
+        println!("{problem}");
+    }
+
+    if !measurements.is_empty() {
+        history.push(&measurements);
+        if let Err(e) = history.save(root) {
+            eprintln!("{e}");
+            std::process::exit(2);
+        }
+    }
+
+    !failed
+}
+
+/// `answer`, to follow a label: after a space, or starting on the next line
+/// if it spans several.
+fn show(answer: &str) -> String {
+    if answer.contains('\n') {
+        format!("\n{answer}")
+    } else {
+        format!(" {answer}")
+    }
+}
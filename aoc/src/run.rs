@@ -0,0 +1,254 @@
+//! Building and running the `<year>-dayN` binaries of each year.
+
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    time::{Duration, Instant},
+};
+
+use aoc_common::bench::BenchConfig;
+
+use crate::json::Value;
+
+/// A single puzzle: a `<year>-dayN` binary in the crate of `year`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Puzzle {
+    pub year: u32,
+    pub day: u32,
+}
+
+impl Puzzle {
+    /// The input used when none is given: `<year>/inputs/day<N>.txt`.
+    pub fn default_input(&self, root: &Path) -> PathBuf {
+        root.join(self.year.to_string())
+            .join("inputs")
+            .join(format!("day{}.txt", self.day))
+    }
+}
+
+impl std::fmt::Display for Puzzle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} day {}", self.year, self.day)
+    }
+}
+
+/// What happened when a [`Puzzle`] was run.
+#[derive(Debug)]
+pub enum Outcome {
+    Solved {
+        /// The answers found by [`aoc_common::parse_answers`].
+        answers: [Option<String>; 2],
+        /// Everything the binary printed.
+        output: String,
+        elapsed: Duration,
+    },
+    MissingInput(PathBuf),
+    BuildFailed(String),
+    /// The binary exited unsuccessfully, with the given stderr.
+    Failed(String),
+}
+
+/// The years that have a crate in `root`, in order.
+pub fn years(root: &Path) -> Vec<u32> {
+    let mut years: Vec<u32> = std::fs::read_dir(root)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let year = entry.file_name().to_str()?.parse().ok()?;
+            entry.path().join("Cargo.toml").exists().then_some(year)
+        })
+        .collect();
+
+    years.sort();
+    years
+}
+
+/// The days of `year` that have a binary, in order.
+pub fn days(root: &Path, year: u32) -> Vec<u32> {
+    let bins = root.join(year.to_string()).join("src").join("bin");
+
+    let mut days: Vec<u32> = std::fs::read_dir(bins)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.ok()?.file_name();
+            name.to_str()?
+                .strip_prefix("day")?
+                .strip_suffix(".rs")?
+                .parse()
+                .ok()
+        })
+        .collect();
+
+    days.sort();
+    days
+}
+
+/// Build the binary for `puzzle` in release mode, returning the path of the
+/// executable.
+pub fn build(root: &Path, puzzle: Puzzle) -> Result<PathBuf, String> {
+    let manifest = root.join("Cargo.toml");
+    let name = format!("{}-day{}", puzzle.year, puzzle.day);
+
+    let output = Command::new(std::env::var("CARGO").unwrap_or_else(|_| "cargo".into()))
+        .args(["build", "--release", "--quiet"])
+        .arg("--message-format=json-render-diagnostics")
+        .arg("--manifest-path")
+        .arg(&manifest)
+        .arg("--bin")
+        .arg(&name)
+        .stderr(Stdio::inherit())
+        .output()
+        .map_err(|e| format!("could not run cargo: {e}"))?;
+
+    if !output.status.success() {
+        return Err(format!("cargo build failed ({})", output.status));
+    }
+
+    executable(&String::from_utf8_lossy(&output.stdout), &name)
+        .ok_or_else(|| format!("cargo did not report an executable for {name}"))
+}
+
+/// The executable of binary `name`, as named by its `compiler-artifact`
+/// message in the JSON `messages` of cargo.
+fn executable(messages: &str, name: &str) -> Option<PathBuf> {
+    messages
+        .lines()
+        .filter_map(|line| Value::parse(line).ok())
+        .filter(|message| {
+            message.get("reason").and_then(Value::as_str) == Some("compiler-artifact")
+                && message
+                    .get("target")
+                    .and_then(|target| target.get("name"))
+                    .and_then(Value::as_str)
+                    == Some(name)
+        })
+        .find_map(|message| message.get("executable")?.as_str().map(PathBuf::from))
+}
+
+/// Run `executable` with `args` and the contents of `input` on stdin,
+/// returning what it printed and how long it took.
+///
+/// If it could not be run successfully, the error is the [`Outcome`] to
+/// report instead.
+fn execute(
+    executable: &Path,
+    args: &[String],
+    input: &Path,
+) -> Result<(String, Duration), Outcome> {
+    let stdin = match File::open(input) {
+        Ok(file) => file,
+        Err(_) => return Err(Outcome::MissingInput(input.to_path_buf())),
+    };
+
+    let start = Instant::now();
+    let output = match Command::new(executable).args(args).stdin(stdin).output() {
+        Ok(output) => output,
+        Err(e) => {
+            let message = format!("could not run {}: {e}", executable.display());
+            return Err(Outcome::Failed(message));
+        }
+    };
+    let elapsed = start.elapsed();
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(Outcome::Failed(format!(
+            "{} {}",
+            output.status,
+            stderr.trim()
+        )));
+    }
+
+    Ok((
+        String::from_utf8_lossy(&output.stdout).into_owned(),
+        elapsed,
+    ))
+}
+
+/// Run `executable` with the contents of `input` on stdin. With `part`,
+/// only that part is solved.
+pub fn run(executable: &Path, input: &Path, part: Option<usize>) -> Outcome {
+    let args = match part {
+        Some(part) => vec!["--part".to_string(), part.to_string()],
+        None => Vec::new(),
+    };
+
+    let (output, elapsed) = match execute(executable, &args, input) {
+        Ok(result) => result,
+        Err(outcome) => return outcome,
+    };
+
+    Outcome::Solved {
+        answers: aoc_common::parse_answers(&output),
+        elapsed,
+        output,
+    }
+}
+
+/// Time the steps of `executable` on `input` with its `--bench` mode,
+/// returning the samples of every step in [`BenchConfig::steps`].
+pub fn bench(
+    executable: &Path,
+    input: &Path,
+    config: BenchConfig,
+) -> Result<Vec<Vec<Duration>>, Outcome> {
+    let mut args = vec![
+        "--bench".to_string(),
+        "--warmup".to_string(),
+        config.warmup.to_string(),
+        "--samples".to_string(),
+        config.samples.to_string(),
+    ];
+    if let Some(part) = config.part {
+        args.extend(["--part".to_string(), part.to_string()]);
+    }
+    let (output, _) = execute(executable, &args, input)?;
+
+    config
+        .steps()
+        .iter()
+        .map(|step| {
+            let prefix = format!("Bench {step}: ");
+            let samples = output
+                .lines()
+                .find_map(|line| line.strip_prefix(&prefix))
+                .ok_or_else(|| Outcome::Failed(format!("no samples for {step}")))?;
+
+            samples
+                .split_whitespace()
+                .map(|nanos| nanos.parse().map(Duration::from_nanos))
+                .collect::<Result<_, _>>()
+                .map_err(|_| Outcome::Failed(format!("invalid samples for {step}: {samples}")))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn executable_from_messages() {
+        let messages = r#"{"reason":"compiler-artifact","target":{"kind":["lib"],"name":"aoc_common"},"executable":null,"fresh":true}
+{"reason":"build-script-executed","package_id":"x","executable":"/not/this"}
+{"reason":"compiler-artifact","target":{"kind":["bin"],"name":"2024-day1"},"executable":"C:\\aoc\\target\\release\\2024-day1.exe","fresh":false}
+{"reason":"compiler-artifact","target":{"kind":["bin"],"name":"2024-day2"},"executable":"/aoc/target/\"quoted\"/2024-day2"}
+{"reason":"build-finished","success":true}
+"#;
+
+        assert_eq!(
+            executable(messages, "2024-day1"),
+            Some(PathBuf::from(r"C:\aoc\target\release\2024-day1.exe"))
+        );
+        assert_eq!(
+            executable(messages, "2024-day2"),
+            Some(PathBuf::from("/aoc/target/\"quoted\"/2024-day2"))
+        );
+        assert_eq!(executable(messages, "aoc_common"), None);
+        assert_eq!(executable(messages, "2024-day3"), None);
+        assert_eq!(executable("not json\n", "2024-day1"), None);
+    }
+}